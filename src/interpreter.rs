@@ -1 +1,5 @@
+pub mod host;
+#[allow(clippy::module_inception)]
 pub mod interpreter;
+pub mod io;
+pub mod test_runner;
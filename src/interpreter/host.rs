@@ -0,0 +1,579 @@
+use crate::interpreter::interpreter::{apply, type_of_value, EnvValue};
+use crate::ir::ast::{Environment, Exception, Expression, Function, NativeFunction, Type};
+use crate::stdlib::builtins::NUMBER;
+use crate::tc::type_checker::join_types;
+use std::rc::Rc;
+
+/*
+ * The API for programs that embed the interpreter: they register Rust
+ * closures that scripts call like declared functions, set and read back
+ * globals as Rust values, and call the functions a script defines.
+ */
+
+/* a Rust value that scripts can hold */
+pub trait IntoValue {
+    fn into_value(self) -> Expression;
+}
+
+/* a Rust value that a script value can be read back as */
+pub trait FromValue: Sized {
+    fn from_value(value: &Expression) -> Option<Self>;
+}
+
+impl IntoValue for Expression {
+    fn into_value(self) -> Expression {
+        self
+    }
+}
+
+impl IntoValue for i32 {
+    fn into_value(self) -> Expression {
+        Expression::CInt(self)
+    }
+}
+
+impl IntoValue for f64 {
+    fn into_value(self) -> Expression {
+        Expression::CReal(self)
+    }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> Expression {
+        if self {
+            Expression::CTrue
+        } else {
+            Expression::CFalse
+        }
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Expression {
+        Expression::CString(self)
+    }
+}
+
+impl IntoValue for &str {
+    fn into_value(self) -> Expression {
+        Expression::CString(self.to_string())
+    }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> Expression {
+        Expression::CNone
+    }
+}
+
+/* 'Option' is the 'Maybe' of scripts */
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Expression {
+        match self {
+            Some(value) => Expression::CJust(Box::new(value.into_value())),
+            None => Expression::CNothing,
+        }
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Expression {
+        Expression::CList(self.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+impl FromValue for Expression {
+    fn from_value(value: &Expression) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromValue for i32 {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CInt(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+/* an 'int' widens to a 'real', as it does in scripts */
+impl FromValue for f64 {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CReal(r) => Some(*r),
+            Expression::CInt(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CTrue => Some(true),
+            Expression::CFalse => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CString(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for () {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CNone => Some(()),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CJust(value) => Some(Some(T::from_value(value)?)),
+            Expression::CNothing => Some(None),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Expression) -> Option<Self> {
+        match value {
+            Expression::CList(values) => values.iter().map(T::from_value).collect(),
+            _ => None,
+        }
+    }
+}
+
+/* reads a script value as a Rust value, naming what holds it when it does not convert */
+pub fn from_value<T: FromValue>(value: EnvValue, context: &str) -> Result<T, Exception> {
+    let converted = match &value {
+        EnvValue::Exp(exp) => T::from_value(exp),
+        _ => None,
+    };
+
+    converted.ok_or_else(|| {
+        Exception::new(
+            "TypeError",
+            format!(
+                "{} is a value of type '{}', which does not convert to '{}'.",
+                context,
+                type_of_value(&value),
+                std::any::type_name::<T>()
+            ),
+        )
+    })
+}
+
+impl Environment<EnvValue> {
+    /* binds a function without a body, which runs the native implementation */
+    pub fn insert_native(&mut self, declaration: Function, native: NativeFunction) {
+        self.natives.insert(declaration.name.clone(), native);
+        self.insert_builtin(declaration.clone(), EnvValue::Func(Box::new(declaration)));
+    }
+
+    /* registers a host closure that scripts call as 'name(...)'; its arguments are checked
+    against the parameter types before it runs, and its result against the return type */
+    pub fn register_function<F>(
+        &mut self,
+        name: &str,
+        params: Vec<(&str, Type)>,
+        kind: Option<Type>,
+        native: F,
+    ) where
+        F: Fn(Vec<Expression>) -> Result<Expression, Exception> + 'static,
+    {
+        let declaration = Function {
+            name: name.to_string(),
+            kind,
+            params: Some(
+                params
+                    .into_iter()
+                    .map(|(param, kind)| (param.to_string(), kind))
+                    .collect(),
+            ),
+            ..Function::new()
+        };
+
+        self.insert_native(declaration, Rc::new(native));
+    }
+
+    pub fn set_global<T: IntoValue>(&mut self, name: &str, value: T) {
+        self.insert_variable(name.to_string(), EnvValue::Exp(value.into_value()));
+    }
+
+    /* the value of a top-level name, once a script ran in this environment */
+    pub fn global(&self, name: &str) -> Option<EnvValue> {
+        self.search_frame(name.to_string()).cloned()
+    }
+
+    pub fn get_global<T: FromValue>(&self, name: &str) -> Result<T, Exception> {
+        match self.global(name) {
+            Some(value) => from_value(value, &format!("global '{}'", name)),
+            None => Err(Exception::new(
+                "NameError",
+                format!("'{}' is not defined.", name),
+            )),
+        }
+    }
+
+    /* calls a function a script defined, or any other function bound to the name */
+    pub fn call_function(&self, name: &str, args: Vec<Expression>) -> Result<EnvValue, Exception> {
+        let func = match self.global(name) {
+            Some(EnvValue::Func(func)) => *func,
+            Some(value) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!("'{}' object is not callable.", type_of_value(&value)),
+                ))
+            }
+            None => match self
+                .builtin_functions()
+                .into_iter()
+                .find(|func| func.name == name)
+            {
+                Some(func) => func,
+                None => {
+                    return Err(Exception::new(
+                        "NameError",
+                        format!("'{}' is not defined.", name),
+                    ))
+                }
+            },
+        };
+
+        let args = args.into_iter().map(EnvValue::Exp).collect();
+        apply(func, args, vec![], self)
+    }
+
+    /* the environment to type check scripts in before running them here, binding the
+    signatures of the native functions and the types of the globals */
+    pub fn type_env(&self) -> Environment<Type> {
+        let mut env: Environment<Type> = Environment::new();
+
+        env.inference.constrain_numeric(NUMBER.to_string());
+        for func in self.builtin_functions() {
+            let kind = func.function_type();
+            env.insert_builtin(func, kind);
+        }
+
+        env.definitions = self.definitions.clone();
+        env.settings = self.settings.clone();
+        env.sandbox = self.sandbox.clone();
+        env.modules = self.modules.with_same_loader();
+
+        for (name, value) in &self.get_frame(self.scope_key()).variables {
+            let kind = type_of_global(value, &env);
+            env.insert_variable(name.clone(), kind);
+        }
+        env
+    }
+}
+
+fn type_of_global(value: &EnvValue, env: &Environment<Type>) -> Type {
+    match value {
+        EnvValue::Exp(exp) => type_of_exp(exp, env),
+        EnvValue::Func(func) => func.function_type(),
+        EnvValue::Object(object) => Type::TClass(object.borrow().class.clone()),
+        EnvValue::Module(module) => Type::TModule(module.clone()),
+    }
+}
+
+/* the type of a value; parts a value leaves open, like the elements of an empty list,
+are unknown, and the elements of a list whose types do not join are dynamic */
+fn type_of_exp(value: &Expression, env: &Environment<Type>) -> Type {
+    match value {
+        Expression::CInt(_) => Type::TInteger,
+        Expression::CReal(_) => Type::TReal,
+        Expression::CTrue | Expression::CFalse => Type::TBool,
+        Expression::CString(_) => Type::TString,
+        Expression::CNone => Type::TUnit,
+        Expression::CList(values) => Type::TList(Box::new(
+            values
+                .iter()
+                .try_fold(Type::TUnknown, |joined, value| {
+                    join_types(&joined, &type_of_exp(value, env), env)
+                })
+                .unwrap_or(Type::TAny),
+        )),
+        Expression::CJust(value) => Type::TMaybe(Box::new(type_of_exp(value, env))),
+        Expression::CNothing => Type::TMaybe(Box::new(Type::TUnknown)),
+        Expression::COk(value) => {
+            Type::TResult(Box::new(type_of_exp(value, env)), Box::new(Type::TUnknown))
+        }
+        Expression::CErr(value) => {
+            Type::TResult(Box::new(Type::TUnknown), Box::new(type_of_exp(value, env)))
+        }
+        Expression::Record(name, _) => Type::TRecord(name.clone()),
+        Expression::Constructor(name, _) => match env.search_constructor(name) {
+            Some((adt_name, _)) => Type::TAdt(adt_name),
+            None => Type::TAny,
+        },
+        _ => Type::TAny,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::interpreter::{execute, ControlFlow};
+    use crate::ir::ast::Expression::*;
+    use crate::ir::ast::Statement::*;
+    use crate::ir::ast::Type::*;
+    use crate::ir::ast::{Statement, TypeDefinition};
+    use crate::ir::module::{MemoryLoader, Modules};
+    use crate::stdlib::builtins::interpreter_env;
+    use crate::tc::type_checker::check_stmt;
+    use std::cell::Cell;
+
+    fn run(program: Statement, env: &Environment<EnvValue>) -> Environment<EnvValue> {
+        match execute(program, env) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("unexpected control flow"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn host_functions_are_checked_and_called() {
+        let mut env = interpreter_env();
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+
+        env.register_function(
+            "discount",
+            vec![("price", TReal), ("rate", TReal)],
+            Some(TReal),
+            move |args| {
+                counter.set(counter.get() + 1);
+                let price = f64::from_value(&args[0]).unwrap();
+                let rate = f64::from_value(&args[1]).unwrap();
+                Ok((price * (1.0 - rate)).into_value())
+            },
+        );
+        env.register_function("label", vec![], Some(TInteger), |_| Ok("ten".into_value()));
+        env.set_global("rate", 0.25);
+
+        /* > total = discount(100, rate) */
+        let program = Assignment(
+            "total".to_string(),
+            Box::new(FuncCall(
+                "discount".to_string(),
+                vec![CInt(100), Var("rate".to_string())],
+            )),
+            None,
+        );
+
+        match check_stmt(program.clone(), &env.type_env()) {
+            Ok(crate::tc::type_checker::ControlFlow::Continue(type_env)) => {
+                assert_eq!(type_env.search_frame("total".to_string()), Some(&TReal))
+            }
+            Ok(_) => panic!("unexpected control flow"),
+            Err(s) => panic!("{}", s),
+        }
+
+        let new_env = run(program, &env);
+
+        assert_eq!(new_env.get_global::<f64>("total"), Ok(75.0));
+        assert_eq!(calls.get(), 1);
+
+        /* > discount("ten", rate) */
+        assert_eq!(
+            check_stmt(
+                ExprStmt(Box::new(FuncCall(
+                    "discount".to_string(),
+                    vec![CString("ten".to_string()), Var("rate".to_string())],
+                ))),
+                &env.type_env()
+            )
+            .err(),
+            Some(String::from("[Type Error on '__main__()'] 'discount()' has mismatched arguments: expected 'TReal', found 'TString'."))
+        );
+
+        assert_eq!(
+            env.call_function("label", vec![]),
+            Err(Exception::new(
+                "TypeError",
+                String::from(
+                    "'label()' return value expected 'TInteger', found a value of type 'str'."
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn script_functions_and_globals_are_read_back() {
+        let env = interpreter_env();
+
+        /*
+         * > def square(x: int) -> int:
+         * >     return x * x
+         * >
+         * > names = ["a", "b"]
+         * > found = Just(3)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "square".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("x".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Mul(
+                    Box::new(Var("x".to_string())),
+                    Box::new(Var("x".to_string())),
+                ))))),
+            })),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "names".to_string(),
+                    Box::new(vec!["a", "b"].into_value()),
+                    None,
+                )),
+                Box::new(Assignment(
+                    "found".to_string(),
+                    Box::new(CJust(Box::new(CInt(3)))),
+                    None,
+                )),
+            )),
+        );
+
+        let new_env = run(program, &env);
+
+        let squared = new_env.call_function("square", vec![7.into_value()]);
+        assert_eq!(from_value::<i32>(squared.unwrap(), "result"), Ok(49));
+        assert_eq!(
+            new_env.call_function("abs", vec![(-2).into_value()]),
+            Ok(EnvValue::Exp(CInt(2)))
+        );
+        assert_eq!(
+            new_env.get_global::<Vec<String>>("names"),
+            Ok(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(new_env.get_global::<Option<i32>>("found"), Ok(Some(3)));
+
+        assert_eq!(
+            new_env.call_function("square", vec!["x".into_value()]),
+            Err(Exception::new(
+                "TypeError",
+                String::from(
+                    "'square()' argument 'x' expected 'TInteger', found a value of type 'str'."
+                )
+            ))
+        );
+        assert_eq!(
+            new_env.call_function("names", vec![]),
+            Err(Exception::new(
+                "TypeError",
+                String::from("'list' object is not callable.")
+            ))
+        );
+        assert_eq!(
+            new_env.get_global::<i32>("missing"),
+            Err(Exception::new(
+                "NameError",
+                String::from("'missing' is not defined.")
+            ))
+        );
+
+        let error = new_env.get_global::<bool>("found").unwrap_err();
+        assert_eq!(error.kind, "TypeError");
+        assert!(error
+            .message
+            .starts_with("global 'found' is a value of type 'Maybe'"));
+    }
+
+    #[test]
+    fn globals_are_typed_for_checking() {
+        let mut env = interpreter_env();
+
+        env.definitions.insert(
+            "Shape".to_string(),
+            TypeDefinition::Adt(vec![("Circle".to_string(), vec![TReal])]),
+        );
+        env.set_global("parsed", COk(Box::new(CInt(1))));
+        env.set_global("failed", CErr(Box::new(CString("bad".to_string()))));
+        env.set_global("shape", Constructor("Circle".to_string(), vec![CReal(1.0)]));
+
+        let type_env = env.type_env();
+        let kind = |name: &str| type_env.search_frame(name.to_string()).cloned();
+
+        assert_eq!(
+            kind("parsed"),
+            Some(TResult(Box::new(TInteger), Box::new(TUnknown)))
+        );
+        assert_eq!(
+            kind("failed"),
+            Some(TResult(Box::new(TUnknown), Box::new(TString)))
+        );
+        assert_eq!(kind("shape"), Some(TAdt("Shape".to_string())));
+    }
+
+    #[test]
+    fn list_globals_join_their_elements() {
+        let mut env = interpreter_env();
+
+        env.set_global("empty", CList(vec![]));
+        env.set_global("numbers", CList(vec![CInt(1), CReal(2.5)]));
+        env.set_global("mixed", CList(vec![CInt(1), CString("a".to_string())]));
+
+        let type_env = env.type_env();
+        let kind = |name: &str| type_env.search_frame(name.to_string()).cloned();
+
+        assert_eq!(kind("empty"), Some(TList(Box::new(TUnknown))));
+        assert_eq!(kind("numbers"), Some(TList(Box::new(TReal))));
+        assert_eq!(kind("mixed"), Some(TList(Box::new(TAny))));
+    }
+
+    #[test]
+    fn scripts_import_the_host_modules() {
+        let mut loader = MemoryLoader::new();
+        /* > # limits
+         * > top = 10
+         */
+        loader.insert(
+            "limits",
+            Assignment("top".to_string(), Box::new(CInt(10)), None),
+        );
+
+        let mut env = interpreter_env();
+        env.modules = Modules::new(Rc::new(loader));
+
+        /* > from limits import top */
+        let program = FromImport("limits".to_string(), vec!["top".to_string()]);
+
+        match check_stmt(program, &env.type_env()) {
+            Ok(crate::tc::type_checker::ControlFlow::Continue(type_env)) => {
+                assert_eq!(type_env.search_frame("top".to_string()), Some(&TInteger))
+            }
+            Ok(_) => panic!("unexpected control flow"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn values_convert_both_ways() {
+        let value = Some(vec![1, 2]).into_value();
+
+        assert_eq!(value, CJust(Box::new(CList(vec![CInt(1), CInt(2)]))));
+        assert_eq!(
+            Option::<Vec<i32>>::from_value(&value),
+            Some(Some(vec![1, 2]))
+        );
+        assert_eq!(Vec::<i32>::from_value(&CList(vec![CInt(1), CTrue])), None);
+        assert_eq!(f64::from_value(&CInt(2)), Some(2.0));
+        assert_eq!(bool::from_value(&true.into_value()), Some(true));
+        assert_eq!(<()>::from_value(&().into_value()), Some(()));
+    }
+}
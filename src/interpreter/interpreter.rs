@@ -1,19 +1,73 @@
-use crate::ir::ast::{Environment, Expression, Function, Name, Statement};
-
-type ErrorMessage = String;
+use crate::interpreter::io::env_io;
+use crate::ir::ast::{
+    split_arguments, CallArguments, Class, Environment, Exception, Expression, Function, Name,
+    Pattern, Statement, Type, TypeDefinition,
+};
+use crate::ir::sandbox::Denial;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum EnvValue {
     Exp(Expression),
-    Func(Function),
+    /* boxed, since a declaration is larger than the other values */
+    Func(Box<Function>),
+    /* objects live on the heap, so every copy of the value shares its fields */
+    Object(Rc<RefCell<Object>>),
+    /* a module bound by 'import', whose exports live in the module registry */
+    Module(Name),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Object {
+    pub class: Name,
+    pub fields: HashMap<Name, EnvValue>,
+}
+
+/* why evaluating an expression stopped before yielding a value: an exception, or a '?'
+returning early from the enclosing function */
+enum Unwind {
+    Raise(Exception),
+    Return(Box<EnvValue>),
+}
+
+impl From<Exception> for Unwind {
+    fn from(exception: Exception) -> Unwind {
+        Unwind::Raise(exception)
+    }
+}
+
+impl From<std::io::Error> for Unwind {
+    fn from(error: std::io::Error) -> Unwind {
+        Unwind::Raise(error.into())
+    }
+}
+
+impl From<Denial> for Unwind {
+    fn from(denial: Denial) -> Unwind {
+        Unwind::Raise(denial.into())
+    }
 }
 
 pub enum ControlFlow {
     Continue(Environment<EnvValue>),
     Return(EnvValue),
+    Raise(Exception),
+}
+
+pub fn eval(exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Exception> {
+    match eval_exp(exp, env) {
+        Ok(value) => Ok(value),
+        Err(Unwind::Raise(exception)) => Err(exception),
+        Err(Unwind::Return(_)) => Err(Exception::new(
+            "SyntaxError",
+            String::from("'?' used outside a function."),
+        )),
+    }
 }
 
-pub fn eval(exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, ErrorMessage> {
+fn eval_exp(exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
     match exp {
         Expression::Add(lhs, rhs) => add(*lhs, *rhs, env),
         Expression::Sub(lhs, rhs) => sub(*lhs, *rhs, env),
@@ -23,1000 +77,5250 @@ pub fn eval(exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Er
         Expression::Or(lhs, rhs) => or(*lhs, *rhs, env),
         Expression::Not(lhs) => not(*lhs, env),
         Expression::EQ(lhs, rhs) => eq(*lhs, *rhs, env),
+        Expression::NEQ(lhs, rhs) => neq(*lhs, *rhs, env),
         Expression::GT(lhs, rhs) => gt(*lhs, *rhs, env),
         Expression::LT(lhs, rhs) => lt(*lhs, *rhs, env),
         Expression::GTE(lhs, rhs) => gte(*lhs, *rhs, env),
         Expression::LTE(lhs, rhs) => lte(*lhs, *rhs, env),
-        Expression::Var(name) => lookup(name, env),
-        Expression::FuncCall(name, args) => call(name, args, env),
+        Expression::Var(name) => Ok(lookup(name, env)?),
+        Expression::FuncCall(name, args) => call(lookup(name, env)?, args, env),
+        Expression::Record(name, fields) => record(name, fields, env),
+        Expression::FieldAccess(exp, field) => field_access(*exp, field, env),
+        Expression::Constructor(name, args) => constructor(name, args, env),
+        Expression::New(name, args) => new_object(name, args, env),
+        Expression::MethodCall(exp, method, args) => method_call(*exp, method, args, env),
+        Expression::MatchExp(exp, arms) => match_exp(*exp, arms, env),
+        Expression::CList(values) => list(values, env),
+        Expression::KeywordArg(name, _) => Err(Exception::new(
+            "SyntaxError",
+            format!("keyword argument '{}' used outside a call.", name),
+        )
+        .into()),
+        Expression::CJust(exp) => Ok(EnvValue::Exp(Expression::CJust(Box::new(eval_value(
+            *exp, env,
+        )?)))),
+        Expression::COk(exp) => Ok(EnvValue::Exp(Expression::COk(Box::new(eval_value(
+            *exp, env,
+        )?)))),
+        Expression::CErr(exp) => Ok(EnvValue::Exp(Expression::CErr(Box::new(eval_value(
+            *exp, env,
+        )?)))),
+        Expression::IsJust(exp) => is_variant(*exp, "Just", env),
+        Expression::IsNothing(exp) => is_variant(*exp, "Nothing", env),
+        Expression::IsOk(exp) => is_variant(*exp, "Ok", env),
+        Expression::IsErr(exp) => is_variant(*exp, "Err", env),
+        Expression::Unwrap(exp) => unwrap(*exp, env),
+        Expression::UnwrapOr(exp, default) => unwrap_or(*exp, *default, env),
+        Expression::Propagate(exp) => propagate(*exp, env),
+        Expression::ReadFile(file_path_exp) => read_file(*file_path_exp, env),
+        Expression::ReadString => read_string(env),
+        Expression::ReadInt => read_int(env),
+        Expression::ReadFloat => read_float(env),
+        _ if is_constant(exp.clone()) => Ok(EnvValue::Exp(exp)),
+        _ => Err(Exception::new("RuntimeError", String::from("Not implemented yet.")).into()),
+    }
+}
 
-        //TODO: separar as seguintes expressions em funções pra ficar consistente com as outras
+fn match_exp(
+    exp: Expression,
+    arms: Vec<(Pattern, Expression)>,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    let value = eval_exp(exp, env)?;
 
-        Expression::ReadFile(file_path_exp) => {
-            let file_path_value = eval(*file_path_exp, env)?;
-            if let EnvValue::Exp(Expression::CString(file_path)) = file_path_value {
-                let content = std::fs::read_to_string(file_path).map_err(|e| e.to_string())?;
-                Ok(EnvValue::Exp(Expression::CString(content)))
-            } else {
-                Err(String::from("read_file expects a string as the file path"))
-            }
+    for (pattern, arm) in arms {
+        if let Some(bindings) = match_pattern(&pattern, &value)? {
+            return eval_exp(arm, &bind_all(bindings, env));
         }
+    }
+
+    Err(no_match_error(&value).into())
+}
 
-        Expression::ReadString => {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+fn list(values: Vec<Expression>, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let mut list = vec![];
+    for value in values {
+        list.push(eval_value(value, env)?);
+    }
+    Ok(EnvValue::Exp(Expression::CList(list)))
+}
 
-            let input = input.trim().to_string();
-            Ok(EnvValue::Exp(Expression::CString(input)))
+fn unwrap(exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    match eval_value(exp, env)? {
+        Expression::CJust(value) | Expression::COk(value) => Ok(EnvValue::Exp(*value)),
+        Expression::CNothing => {
+            Err(Exception::new("UnwrapError", String::from("called unwrap on 'Nothing'.")).into())
         }
+        value @ Expression::CErr(_) => Err(Exception::new(
+            "UnwrapError",
+            format!(
+                "called unwrap on '{}'.",
+                value_to_string(&value).unwrap_or(format!("{:?}", value))
+            ),
+        )
+        .into()),
+        _ => Err(expected_maybe_or_result("unwrap").into()),
+    }
+}
 
-        Expression::ReadInt => {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+fn unwrap_or(
+    exp: Expression,
+    default: Expression,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    match eval_value(exp, env)? {
+        Expression::CJust(value) | Expression::COk(value) => Ok(EnvValue::Exp(*value)),
+        Expression::CNothing | Expression::CErr(_) => eval_exp(default, env),
+        _ => Err(expected_maybe_or_result("unwrap_or").into()),
+    }
+}
 
-            let input = input.trim().parse::<i32>().map_err(|e| e.to_string())?;
-            Ok(EnvValue::Exp(Expression::CInt(input)))
+fn propagate(exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    match eval_value(exp, env)? {
+        Expression::CJust(value) | Expression::COk(value) => Ok(EnvValue::Exp(*value)),
+        value @ (Expression::CNothing | Expression::CErr(_)) => {
+            Err(Unwind::Return(Box::new(EnvValue::Exp(value))))
         }
+        _ => Err(expected_maybe_or_result("'?'").into()),
+    }
+}
 
-        Expression::ReadFloat => {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+fn read_file(file_path_exp: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let file_path_value = eval_exp(file_path_exp, env)?;
+    if let EnvValue::Exp(Expression::CString(file_path)) = file_path_value {
+        env.sandbox.check_read(&file_path)?;
+        let content = env_io(env).read_file(&file_path)?;
+        Ok(EnvValue::Exp(Expression::CString(content)))
+    } else {
+        Err(Exception::new(
+            "TypeError",
+            String::from("read_file expects a string as the file path"),
+        )
+        .into())
+    }
+}
 
-            let input = input.trim().parse::<f64>().map_err(|e| e.to_string())?;
-            Ok(EnvValue::Exp(Expression::CReal(input)))
-        }
+fn read_string(env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let input = read_input(env)?.trim().to_string();
+    Ok(EnvValue::Exp(Expression::CString(input)))
+}
 
+fn read_int(env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let input = read_input(env)?
+        .trim()
+        .parse::<i32>()
+        .map_err(|e| Exception::new("ValueError", e.to_string()))?;
+    Ok(EnvValue::Exp(Expression::CInt(input)))
+}
 
-        _ if is_constant(exp.clone()) => Ok(EnvValue::Exp(exp)),
-        _ => Err(String::from("Not implemented yet.")),
-    }
+fn read_float(env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let input = read_input(env)?
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| Exception::new("ValueError", e.to_string()))?;
+    Ok(EnvValue::Exp(Expression::CReal(input)))
 }
 
-pub fn execute(stmt: Statement, env: &Environment<EnvValue>) -> Result<ControlFlow, ErrorMessage> {
-    let mut new_env = env.clone();
+/* the next line of input, which must exist */
+fn read_input(env: &Environment<EnvValue>) -> Result<String, Exception> {
+    match env_io(env).read_line()? {
+        Some(line) => Ok(line),
+        None => Err(Exception::new(
+            "EOFError",
+            String::from("end of input reached while reading a line."),
+        )),
+    }
+}
 
-    match stmt {
-        Statement::Assignment(name, exp, _) => {
-            let value = eval(*exp, &new_env)?;
+/* a '?' that returns early from the statement returns from the enclosing function */
+pub fn execute(stmt: Statement, env: &Environment<EnvValue>) -> Result<ControlFlow, Exception> {
+    match execute_stmt(stmt, env) {
+        Ok(flow) => Ok(flow),
+        Err(Unwind::Raise(exception)) => Err(exception),
+        Err(Unwind::Return(value)) => Ok(ControlFlow::Return(*value)),
+    }
+}
 
-            new_env.insert_variable(name, value);
+fn execute_stmt(stmt: Statement, env: &Environment<EnvValue>) -> Result<ControlFlow, Unwind> {
+    let new_env = env.clone();
 
-            Ok(ControlFlow::Continue(new_env))
+    match stmt {
+        Statement::Assignment(name, exp, kind) => {
+            execute_assignment(vec![name], *exp, kind, new_env)
         }
-        Statement::IfThenElse(cond, stmt_then, stmt_else) => {
-            let value = eval(*cond, &new_env)?;
-
-            if value == EnvValue::Exp(Expression::CTrue) {
-                execute(*stmt_then, &new_env)
-            } else {
-                match stmt_else {
-                    Some(stmt_else) => execute(*stmt_else, &new_env),
-                    None => Ok(ControlFlow::Continue(new_env)),
-                }
-            }
+        Statement::ChainedAssignment(names, exp, kind) => {
+            execute_assignment(names, *exp, kind, new_env)
         }
-        Statement::While(cond, stmt) => {
-            let mut value = eval(*cond.clone(), &new_env)?;
-
-            loop {
-                match value {
-                    EnvValue::Exp(Expression::CTrue) => match execute(*stmt.clone(), &new_env)? {
-                        ControlFlow::Continue(control_env) => {
-                            new_env = control_env;
-                            value = eval(*cond.clone(), &new_env)?;
-                        }
-                        ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
-                    },
-                    EnvValue::Exp(Expression::CFalse) => return Ok(ControlFlow::Continue(new_env)),
-                    _ => unreachable!(),
-                }
-            }
+        Statement::AddAssignment(_, _)
+        | Statement::SubAssignment(_, _)
+        | Statement::MulAssignment(_, _)
+        | Statement::DivAssignment(_, _) => {
+            execute_stmt(stmt.expand_augmented_assignment(), &new_env)
         }
-        Statement::Sequence(s1, s2) => match execute(*s1, &new_env)? {
-            ControlFlow::Continue(control_env) => {
-                new_env = control_env;
-                execute(*s2, &new_env)
-            }
-            ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+        Statement::IfThenElse(cond, stmt_then, stmt_else) => {
+            execute_if_then_else(*cond, *stmt_then, stmt_else, new_env)
+        }
+        Statement::While(cond, stmt) => execute_while(*cond, *stmt, new_env),
+        Statement::Sequence(s1, s2) => match execute_stmt(*s1, &new_env)? {
+            ControlFlow::Continue(control_env) => execute_stmt(*s2, &control_env),
+            control_flow => Ok(control_flow),
         },
-        Statement::FuncDef(func) => {
-            new_env.insert_variable(func.name.clone(), EnvValue::Func(func));
-
-            Ok(ControlFlow::Continue(new_env))
+        Statement::FuncDef(_)
+        | Statement::ClassDef(_)
+        | Statement::RecordDef(_, _)
+        | Statement::AdtDef(_, _)
+        | Statement::TypeAlias(_, _)
+        | Statement::Import(_)
+        | Statement::FromImport(_, _) => execute_declaration(stmt, new_env),
+        Statement::Match(exp, arms) => execute_match(*exp, arms, env),
+        Statement::FieldAssignment(name, field, exp) => {
+            execute_field_assignment(name, field, *exp, new_env)
         }
         Statement::Return(exp) => {
-            let exp_value = eval(*exp, &new_env)?;
+            let exp_value = eval_exp(*exp, &new_env)?;
             Ok(ControlFlow::Return(exp_value))
         }
+        Statement::BareReturn => Ok(ControlFlow::Return(EnvValue::Exp(Expression::CNone))),
+        Statement::ExprStmt(exp) => {
+            eval_exp(*exp, &new_env)?;
+            Ok(ControlFlow::Continue(new_env))
+        }
         Statement::WriteToFile(file_path_exp, content_exp) => {
-            let file_path_value = eval(*file_path_exp, &new_env)?;
-            let content_value = eval(*content_exp, &new_env)?;
-
-            if let (EnvValue::Exp(Expression::CString(file_path)), EnvValue::Exp(Expression::CString(content))) = (file_path_value, content_value) {
-                std::fs::write(file_path, content).map_err(|e| e.to_string())?;
-                Ok(ControlFlow::Continue(new_env))
-            } else {
-                Err(String::from("write_to_file expects two string arguments"))
-            }
+            execute_write_to_file(*file_path_exp, *content_exp, new_env)
+        }
+        Statement::Print(exp) => execute_print(*exp, new_env),
+        Statement::Raise(kind, exp) => execute_raise(kind, *exp, env),
+        Statement::Assert(cond, exp) => execute_assert(*cond, *exp, new_env),
+        Statement::TryExcept(stmt_try, handlers, stmt_finally) => {
+            execute_try_except(*stmt_try, handlers, stmt_finally, new_env)
         }
+        _ => Err(Exception::new("RuntimeError", String::from("not implemented yet")).into()),
+    }
+}
 
-        Statement::Print(exp) => {
-            let value = eval(*exp, &new_env)?;
+/* binds the value to each name in turn, as a chained assignment does */
+fn execute_assignment(
+    names: Vec<Name>,
+    exp: Expression,
+    kind: Option<Type>,
+    mut new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    let value = eval_exp(exp, &new_env)?;
+
+    for name in names {
+        let value = coerce_assigned(&name, value.clone(), &kind, &new_env)?;
+        new_env.insert_variable(name, value);
+    }
 
-            match value {
-                EnvValue::Exp(Expression::CInt(i)) => println!("{}", i),
-                EnvValue::Exp(Expression::CReal(r)) => println!("{}", r),
-                EnvValue::Exp(Expression::CString(s)) => println!("{}", s),
-                EnvValue::Exp(Expression::CTrue) => println!("true"),
-                EnvValue::Exp(Expression::CFalse) => println!("false"),
-                _ => return Err(String::from("Cannot print this type of value")),
-            }
+    Ok(ControlFlow::Continue(new_env))
+}
 
-            Ok(ControlFlow::Continue(new_env))
+fn execute_if_then_else(
+    cond: Expression,
+    stmt_then: Statement,
+    stmt_else: Option<Box<Statement>>,
+    new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    let value = eval_exp(cond, &new_env)?;
+
+    if value == EnvValue::Exp(Expression::CTrue) {
+        Ok(execute(stmt_then, &new_env)?)
+    } else if value != EnvValue::Exp(Expression::CFalse) {
+        Err(Exception::new(
+            "TypeError",
+            format!(
+                "if expression must be boolean, found a value of type '{}'.",
+                type_of_value(&value)
+            ),
+        )
+        .into())
+    } else {
+        match stmt_else {
+            Some(stmt_else) => Ok(execute(*stmt_else, &new_env)?),
+            None => Ok(ControlFlow::Continue(new_env)),
         }
-        _ => Err(String::from("not implemented yet")),
     }
 }
 
-fn call(
-    name: Name,
-    args: Vec<Expression>,
-    env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    let mut new_env = env.clone();
+/* binds the names a definition or an import introduces */
+fn execute_declaration(
+    stmt: Statement,
+    mut new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    match stmt {
+        Statement::FuncDef(func) => execute_func_def(func, new_env),
+        Statement::ClassDef(class) => execute_class_def(class, new_env),
+        Statement::RecordDef(name, fields) => {
+            new_env.insert_definition(name, TypeDefinition::Record(fields));
 
-    if let Ok(EnvValue::Func(func)) = lookup(name, &new_env) {
-        new_env.insert_frame(func.clone());
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::AdtDef(name, constructors) => {
+            new_env.insert_definition(name, TypeDefinition::Adt(constructors));
 
-        if let Some(params) = func.params.clone() {
-            for (arg, (param, _)) in args.iter().zip(params) {
-                let value = eval(arg.clone(), &new_env)?;
-                new_env.insert_variable(param, value);
-            }
+            Ok(ControlFlow::Continue(new_env))
         }
+        Statement::TypeAlias(name, kind) => {
+            new_env.insert_definition(name, TypeDefinition::Alias(kind));
 
-        if let None = new_env.search_frame(func.name.clone()) {
-            new_env.insert_variable(func.name.clone(), EnvValue::Func(func.clone()));
+            Ok(ControlFlow::Continue(new_env))
         }
+        Statement::Import(module) => Ok(import(module, None, new_env)?),
+        Statement::FromImport(module, names) => Ok(import(module, Some(names), new_env)?),
+        _ => unreachable!(),
+    }
+}
 
-        match execute(*func.body.unwrap(), &new_env)? {
-            ControlFlow::Return(value) => {
-                new_env.remove_frame();
-                return Ok(value);
+fn execute_while(
+    cond: Expression,
+    stmt: Statement,
+    mut new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    let mut value = eval_exp(cond.clone(), &new_env)?;
+
+    loop {
+        match value {
+            EnvValue::Exp(Expression::CTrue) => match execute(stmt.clone(), &new_env)? {
+                ControlFlow::Continue(control_env) => {
+                    new_env = control_env;
+                    value = eval_exp(cond.clone(), &new_env)?;
+                }
+                ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+                ControlFlow::Raise(exception) => return Ok(ControlFlow::Raise(exception)),
+            },
+            EnvValue::Exp(Expression::CFalse) => return Ok(ControlFlow::Continue(new_env)),
+            value => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!(
+                        "while expression must be boolean, found a value of type '{}'.",
+                        type_of_value(&value)
+                    ),
+                )
+                .into())
             }
-            ControlFlow::Continue(_) => unreachable!(),
         }
     }
-    unreachable!()
 }
 
-fn is_constant(exp: Expression) -> bool {
-    match exp {
-        Expression::CTrue => true,
-        Expression::CFalse => true,
-        Expression::CInt(_) => true,
-        Expression::CReal(_) => true,
-        Expression::CString(_) => true,
-        _ => false,
+fn execute_func_def(
+    mut func: Function,
+    mut new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    /* defaults are evaluated once, where the function is defined */
+    for (_, default) in func.defaults.iter_mut() {
+        *default = eval_value(default.clone(), &new_env)?;
     }
-}
 
-fn lookup(name: String, env: &Environment<EnvValue>) -> Result<EnvValue, ErrorMessage> {
-    let mut curr_scope = env.scope_key();
+    new_env.insert_variable(func.name.clone(), EnvValue::Func(Box::new(func)));
 
-    loop {
-        let frame = env.get_frame(curr_scope.clone());
+    Ok(ControlFlow::Continue(new_env))
+}
 
-        match frame.variables.get(&name) {
-            Some(value) => return Ok(value.clone()),
-            None => curr_scope = frame.parent_key.clone().unwrap(),
+fn execute_class_def(
+    class: Class,
+    mut new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    if let Some(parent) = &class.parent {
+        if new_env.search_class(parent).is_none() {
+            return Err(
+                Exception::new("NameError", format!("class '{}' is not defined.", parent)).into(),
+            );
         }
     }
+
+    new_env.insert_definition(class.name.clone(), TypeDefinition::Class(class));
+
+    Ok(ControlFlow::Continue(new_env))
 }
 
-/* Arithmetic Operations */
-fn eval_binary_arith_op<F>(
-    lhs: Expression,
-    rhs: Expression,
+fn execute_match(
+    exp: Expression,
+    arms: Vec<(Pattern, Statement)>,
     env: &Environment<EnvValue>,
-    op: F,
-    error_msg: &str,
-) -> Result<EnvValue, ErrorMessage>
-where
-    F: Fn(f64, f64) -> f64,
-{
-    let v1 = eval(lhs, env)?;
-    let v2 = eval(rhs, env)?;
-    match (v1, v2) {
-        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CInt(v2))) => Ok(
-            EnvValue::Exp(Expression::CInt(op(v1 as f64, v2 as f64) as i32)),
-        ),
-        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
-            Ok(EnvValue::Exp(Expression::CReal(op(v1 as f64, v2))))
+) -> Result<ControlFlow, Unwind> {
+    let value = eval_exp(exp, env)?;
+
+    for (pattern, arm) in arms {
+        if let Some(bindings) = match_pattern(&pattern, &value)? {
+            return Ok(execute(arm, &bind_all(bindings, env))?);
         }
-        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
-            Ok(EnvValue::Exp(Expression::CReal(op(v1, v2 as f64))))
+    }
+
+    Err(no_match_error(&value).into())
+}
+
+fn execute_field_assignment(
+    name: Name,
+    field: Name,
+    exp: Expression,
+    mut new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    let (record_name, mut fields) = match lookup(name.clone(), &new_env)? {
+        EnvValue::Exp(Expression::Record(record_name, fields)) => (record_name, fields),
+        EnvValue::Object(object) => {
+            let value = eval_exp(exp, &new_env)?;
+            set_attribute(&object, field, value, &new_env)?;
+            return Ok(ControlFlow::Continue(new_env));
         }
-        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
-            Ok(EnvValue::Exp(Expression::CReal(op(v1, v2))))
+        _ => {
+            return Err(Exception::new("TypeError", format!("'{}' is not a record.", name)).into())
+        }
+    };
+
+    let value = match eval_exp(exp, &new_env)? {
+        EnvValue::Exp(value) => value,
+        EnvValue::Func(_) => {
+            return Err(Exception::new(
+                "TypeError",
+                String::from("record fields cannot hold functions."),
+            )
+            .into())
+        }
+        EnvValue::Object(_) => {
+            return Err(Exception::new(
+                "TypeError",
+                String::from("record fields cannot hold objects."),
+            )
+            .into())
+        }
+        EnvValue::Module(_) => {
+            return Err(Exception::new(
+                "TypeError",
+                String::from("record fields cannot hold modules."),
+            )
+            .into())
+        }
+    };
+
+    let declared_fields = match new_env.search_definition(&record_name) {
+        Some(TypeDefinition::Record(declared_fields)) => declared_fields.clone(),
+        _ => vec![],
+    };
+
+    match fields
+        .iter_mut()
+        .find(|(field_name, _)| *field_name == field)
+    {
+        Some((_, field_value)) => {
+            *field_value = match declared_fields.iter().find(|(name, _)| *name == field) {
+                Some((_, kind)) => {
+                    let context = format!("'{}.{}'", record_name, field);
+                    coerce_exp(value, kind, &context, &new_env)?
+                }
+                None => value,
+            }
+        }
+        None => {
+            return Err(Exception::new(
+                "AttributeError",
+                format!("record '{}' has no field '{}'.", record_name, field),
+            )
+            .into())
         }
-        _ => Err(error_msg.to_string()),
     }
+
+    new_env.insert_variable(name, EnvValue::Exp(Expression::Record(record_name, fields)));
+
+    Ok(ControlFlow::Continue(new_env))
 }
 
-fn add(
-    lhs: Expression,
-    rhs: Expression,
-    env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_arith_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| a + b,
-        "addition '(+)' is only defined for numbers (integers and real).",
-    )
+fn execute_write_to_file(
+    file_path_exp: Expression,
+    content_exp: Expression,
+    new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    let file_path_value = eval_exp(file_path_exp, &new_env)?;
+    let content_value = eval_exp(content_exp, &new_env)?;
+
+    if let (
+        EnvValue::Exp(Expression::CString(file_path)),
+        EnvValue::Exp(Expression::CString(content)),
+    ) = (file_path_value, content_value)
+    {
+        new_env.sandbox.check_write(&file_path, content.len())?;
+        env_io(&new_env).write_file(&file_path, &content)?;
+        new_env.sandbox.record_write(content.len());
+        Ok(ControlFlow::Continue(new_env))
+    } else {
+        Err(Exception::new(
+            "TypeError",
+            String::from("write_to_file expects two string arguments"),
+        )
+        .into())
+    }
 }
 
-fn sub(
-    lhs: Expression,
-    rhs: Expression,
-    env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_arith_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| a - b,
-        "subtraction '(-)' is only defined for numbers (integers and real).",
-    )
+fn execute_print(exp: Expression, new_env: Environment<EnvValue>) -> Result<ControlFlow, Unwind> {
+    let value = eval_exp(exp, &new_env)?;
+
+    match value {
+        EnvValue::Exp(value) => match value_to_string(&value) {
+            Some(text) => env_io(&new_env).write_line(&text)?,
+            None => {
+                return Err(Exception::new(
+                    "TypeError",
+                    String::from("Cannot print this type of value"),
+                )
+                .into())
+            }
+        },
+        _ => {
+            return Err(Exception::new(
+                "TypeError",
+                String::from("Cannot print this type of value"),
+            )
+            .into())
+        }
+    }
+
+    Ok(ControlFlow::Continue(new_env))
 }
 
-fn mul(
-    lhs: Expression,
-    rhs: Expression,
+fn execute_raise(
+    kind: Name,
+    exp: Expression,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_arith_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| a * b,
-        "multiplication '(*)' is only defined for numbers (integers and real).",
-    )
+) -> Result<ControlFlow, Unwind> {
+    match eval_exp(exp, env)? {
+        EnvValue::Exp(Expression::CString(message)) => {
+            Ok(ControlFlow::Raise(Exception::new(&kind, message)))
+        }
+        _ => Err(Exception::new(
+            "TypeError",
+            String::from("raise expects a string as the exception message"),
+        )
+        .into()),
+    }
 }
 
-fn div(
-    lhs: Expression,
-    rhs: Expression,
-    env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_arith_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| a / b,
-        "division '(/)' is only defined for numbers (integers and real).",
-    )
+fn execute_assert(
+    cond: Expression,
+    exp: Expression,
+    new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    match eval_exp(cond, &new_env)? {
+        EnvValue::Exp(Expression::CTrue) => Ok(ControlFlow::Continue(new_env)),
+        EnvValue::Exp(Expression::CFalse) => match eval_exp(exp, &new_env)? {
+            EnvValue::Exp(Expression::CString(message)) => Ok(ControlFlow::Raise(Exception::new(
+                "AssertionError",
+                message,
+            ))),
+            _ => Err(Exception::new(
+                "TypeError",
+                String::from("assert expects a string as the failure message"),
+            )
+            .into()),
+        },
+        _ => Err(Exception::new(
+            "TypeError",
+            String::from("assert expects a boolean condition"),
+        )
+        .into()),
+    }
 }
 
-/* Boolean Expressions */
-fn eval_binary_boolean_op<F>(
-    lhs: Expression,
-    rhs: Expression,
-    env: &Environment<EnvValue>,
-    op: F,
-    error_msg: &str,
-) -> Result<EnvValue, ErrorMessage>
-where
-    F: Fn(bool, bool) -> Expression,
-{
-    let v1 = eval(lhs, env)?;
-    let v2 = eval(rhs, env)?;
-    match (v1, v2) {
-        (EnvValue::Exp(Expression::CTrue), EnvValue::Exp(Expression::CTrue)) => {
-            Ok(EnvValue::Exp(op(true, true)))
-        }
-        (EnvValue::Exp(Expression::CTrue), EnvValue::Exp(Expression::CFalse)) => {
-            Ok(EnvValue::Exp(op(true, false)))
-        }
-        (EnvValue::Exp(Expression::CFalse), EnvValue::Exp(Expression::CTrue)) => {
-            Ok(EnvValue::Exp(op(false, true)))
+fn execute_try_except(
+    stmt_try: Statement,
+    handlers: Vec<(Name, Option<Name>, Statement)>,
+    stmt_finally: Option<Box<Statement>>,
+    new_env: Environment<EnvValue>,
+) -> Result<ControlFlow, Unwind> {
+    let result = match execute(stmt_try, &new_env) {
+        Ok(ControlFlow::Raise(exception)) | Err(exception) => {
+            handle_exception(exception, handlers, &new_env)
         }
-        (EnvValue::Exp(Expression::CFalse), EnvValue::Exp(Expression::CFalse)) => {
-            Ok(EnvValue::Exp(op(false, false)))
+        result => result,
+    };
+
+    match stmt_finally {
+        Some(stmt_finally) => {
+            let finally_env = match &result {
+                Ok(ControlFlow::Continue(control_env)) => control_env.clone(),
+                _ => new_env,
+            };
+
+            /* a 'finally' block that returns or raises overrides the pending result */
+            match execute(*stmt_finally, &finally_env)? {
+                ControlFlow::Continue(control_env) => match result? {
+                    ControlFlow::Continue(_) => Ok(ControlFlow::Continue(control_env)),
+                    control_flow => Ok(control_flow),
+                },
+                control_flow => Ok(control_flow),
+            }
         }
-        _ => Err(error_msg.to_string()),
+        None => Ok(result?),
     }
 }
 
-fn and(
-    lhs: Expression,
-    rhs: Expression,
+fn handle_exception(
+    exception: Exception,
+    handlers: Vec<(Name, Option<Name>, Statement)>,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_boolean_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a && b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
+) -> Result<ControlFlow, Exception> {
+    for (kind, binding, stmt) in handlers {
+        if exception.is_handled_by(&kind) {
+            let mut handler_env = env.clone();
+
+            if let Some(name) = binding {
+                let message = EnvValue::Exp(Expression::CString(exception.message));
+                handler_env.insert_variable(name, message);
             }
-        },
-        "'and' is only defined for booleans.",
-    )
+
+            return execute(stmt, &handler_env);
+        }
+    }
+
+    Ok(ControlFlow::Raise(exception))
 }
 
-fn or(
-    lhs: Expression,
-    rhs: Expression,
+fn call(
+    value: EnvValue,
+    args: Vec<Expression>,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_boolean_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a || b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
-            }
-        },
-        "'or' is only defined for booleans.",
-    )
+) -> Result<EnvValue, Unwind> {
+    let func = match value {
+        EnvValue::Func(func) => *func,
+        value => {
+            return Err(Exception::new(
+                "TypeError",
+                format!("'{}' object is not callable.", type_of_value(&value)),
+            )
+            .into())
+        }
+    };
+
+    let (positional_values, keyword_values) = eval_arguments(&func.name, args, env)?;
+
+    Ok(apply(func, positional_values, keyword_values, env)?)
 }
 
-fn not(lhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, ErrorMessage> {
-    let v = eval(lhs, env)?;
-    match v {
-        EnvValue::Exp(Expression::CTrue) => Ok(EnvValue::Exp(Expression::CFalse)),
-        EnvValue::Exp(Expression::CFalse) => Ok(EnvValue::Exp(Expression::CTrue)),
-        _ => Err(String::from("'not' is only defined for booleans.")),
+/* arguments are evaluated in the order they are written, then bound to parameters */
+fn eval_arguments(
+    name: &Name,
+    args: Vec<Expression>,
+    env: &Environment<EnvValue>,
+) -> Result<CallArguments<EnvValue>, Unwind> {
+    let (positional, keywords) =
+        split_arguments(name, args).map_err(|msg| Exception::new("SyntaxError", msg))?;
+
+    let mut positional_values = vec![];
+    for arg in positional {
+        positional_values.push(eval_exp(arg, env)?);
     }
-}
 
-/* Relational Operations */
-fn eval_binary_rel_op<F>(
-    lhs: Expression,
-    rhs: Expression,
-    env: &Environment<EnvValue>,
-    op: F,
-    error_msg: &str,
-) -> Result<EnvValue, ErrorMessage>
-where
-    F: Fn(f64, f64) -> Expression,
-{
-    let v1 = eval(lhs, env)?;
-    let v2 = eval(rhs, env)?;
-    match (v1, v2) {
-        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
-            Ok(EnvValue::Exp(op(v1 as f64, v2 as f64)))
-        }
-        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
-            Ok(EnvValue::Exp(op(v1 as f64, v2)))
-        }
-        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
-            Ok(EnvValue::Exp(op(v1, v2 as f64)))
-        }
-        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
-            Ok(EnvValue::Exp(op(v1, v2)))
-        }
-        _ => Err(error_msg.to_string()),
+    let mut keyword_values = vec![];
+    for (keyword, arg) in keywords {
+        keyword_values.push((keyword, eval_exp(arg, env)?));
     }
+
+    Ok((positional_values, keyword_values))
 }
 
-fn eq(
-    lhs: Expression,
-    rhs: Expression,
+/* calls a function on argument values, binding them to its parameters */
+pub fn apply(
+    func: Function,
+    positional_values: Vec<EnvValue>,
+    keyword_values: Vec<(Name, EnvValue)>,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_rel_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a == b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
-            }
-        },
-        "(==) is only defined for numbers (integers and real).",
-    )
+) -> Result<EnvValue, Exception> {
+    let bindings = bind_values(&func, positional_values, keyword_values, env)?;
+
+    if func.body.is_none() {
+        return call_native(&func, bindings, env);
+    }
+
+    let name = func.name.clone();
+
+    match invoke(func, bindings, env)? {
+        Some(value) => Ok(value),
+        None => Err(Exception::new(
+            "TypeError",
+            format!("'{}()' did not return a value.", name),
+        )),
+    }
 }
 
-fn gt(
-    lhs: Expression,
-    rhs: Expression,
+/* pairs each parameter with its argument or default, coerced to the parameter type, and
+collects the extra positional arguments for the variadic parameter */
+fn bind_values(
+    func: &Function,
+    positional_values: Vec<EnvValue>,
+    keyword_values: Vec<(Name, EnvValue)>,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_rel_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a > b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
+) -> Result<Vec<(Name, EnvValue)>, Exception> {
+    let (bound, extra) = func
+        .bind_arguments(positional_values, keyword_values)
+        .map_err(|msg| Exception::new("TypeError", msg))?;
+
+    let mut bindings = vec![];
+
+    for (param, kind, value) in bound {
+        let value = match value {
+            Some(value) => value,
+            None => match func.defaults.iter().find(|(name, _)| *name == param) {
+                Some((_, default)) => EnvValue::Exp(default.clone()),
+                None => EnvValue::Exp(Expression::CNone),
+            },
+        };
+        let context = format!("'{}()' argument '{}'", func.name, param);
+        bindings.push((param, coerce(value, &kind, &context, env)?));
+    }
+
+    if let Some((args_name, kind)) = &func.variadic {
+        let context = format!("'{}()' argument '{}'", func.name, args_name);
+        let mut values = vec![];
+
+        for value in extra {
+            match coerce(value, kind, &context, env)? {
+                EnvValue::Exp(value) => values.push(value),
+                value => {
+                    return Err(Exception::new(
+                        "TypeError",
+                        format!(
+                            "{} only collects values, found a value of type '{}'.",
+                            context,
+                            type_of_value(&value)
+                        ),
+                    ))
+                }
             }
-        },
-        "(>) is only defined for numbers (integers and real).",
-    )
+        }
+        bindings.push((args_name.clone(), EnvValue::Exp(Expression::CList(values))));
+    }
+
+    Ok(bindings)
 }
 
-fn lt(
-    lhs: Expression,
-    rhs: Expression,
+/* a function without a body is built in or registered by the host, and runs natively
+on the values bound to its parameters */
+fn call_native(
+    func: &Function,
+    bindings: Vec<(Name, EnvValue)>,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_rel_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a < b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
+) -> Result<EnvValue, Exception> {
+    let native = match env.natives.search(&func.name) {
+        Some(native) => native,
+        None => {
+            return Err(Exception::new(
+                "NameError",
+                format!("built-in function '{}()' is not defined.", func.name),
+            ))
+        }
+    };
+
+    let mut args = vec![];
+
+    for (param, value) in bindings {
+        match value {
+            EnvValue::Exp(value) => args.push(value),
+            value => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!(
+                        "'{}()' argument '{}' must be a value, found a value of type '{}'.",
+                        func.name,
+                        param,
+                        type_of_value(&value)
+                    ),
+                ))
             }
-        },
-        "(<) is only defined for numbers (integers and real).",
-    )
+        }
+    }
+
+    let value = EnvValue::Exp(native(args)?);
+
+    match &func.kind {
+        Some(kind) => {
+            let context = format!("'{}()' return value", func.name);
+            coerce(value, kind, &context, env)
+        }
+        None => Ok(value),
+    }
 }
 
-fn gte(
-    lhs: Expression,
-    rhs: Expression,
+/* executes a module the first time it is imported, in an environment of its own;
+later imports reuse the environment it ended with */
+fn execute_module(
+    name: &Name,
     env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_rel_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a >= b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
-            }
-        },
-        "(>=) is only defined for numbers (integers and real).",
-    )
+) -> Result<Environment<EnvValue>, Exception> {
+    if let Some(module_env) = env.modules.search(name) {
+        return Ok(module_env);
+    }
+
+    let program = match env.modules.load(name, &env.sandbox) {
+        Ok(Some(program)) => program,
+        Ok(None) => {
+            return Err(Exception::new(
+                "ModuleNotFoundError",
+                format!("no module named '{}'.", name),
+            ))
+        }
+        Err(msg) => return Err(Exception::new("ImportError", format!("{}.", msg))),
+    };
+
+    env.modules.begin(name).map_err(|cycle| {
+        Exception::new(
+            "ImportError",
+            format!("circular import: {}.", cycle.join(" -> ")),
+        )
+    })?;
+
+    let result = match execute(program, &env.for_module(name)) {
+        Ok(ControlFlow::Continue(module_env)) => Ok(tag_methods(name, module_env)),
+        Ok(ControlFlow::Return(_)) => Err(Exception::new(
+            "SyntaxError",
+            format!("module '{}' returns outside a function.", name),
+        )),
+        Ok(ControlFlow::Raise(exception)) | Err(exception) => Err(exception),
+    };
+
+    env.modules.finish(name, result.clone().ok());
+    result
 }
 
-fn lte(
-    lhs: Expression,
-    rhs: Expression,
-    env: &Environment<EnvValue>,
-) -> Result<EnvValue, ErrorMessage> {
-    eval_binary_rel_op(
-        lhs,
-        rhs,
-        env,
-        |a, b| {
-            if a <= b {
-                Expression::CTrue
-            } else {
-                Expression::CFalse
+/* the methods of the classes a module defines remember it, so that they still run in the
+module once the classes are imported */
+fn tag_methods(module: &Name, mut module_env: Environment<EnvValue>) -> Environment<EnvValue> {
+    for definition in module_env.definitions.values_mut() {
+        if let TypeDefinition::Class(class) = definition {
+            for method in class.methods.iter_mut() {
+                method.module.get_or_insert_with(|| module.clone());
             }
-        },
-        "(<=) is only defined for numbers (integers and real).",
-    )
+        }
+    }
+    module_env
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ir::ast::Expression::*;
-    use crate::ir::ast::Function;
-    use crate::ir::ast::Statement::*;
-    use crate::ir::ast::Type::*;
-    use approx::relative_eq;
+/* binds the module itself, or the names it exports that 'from module import' lists */
+fn import(
+    module: Name,
+    names: Option<Vec<Name>>,
+    mut env: Environment<EnvValue>,
+) -> Result<ControlFlow, Exception> {
+    let module_env = execute_module(&module, &env)?;
+
+    import_definitions(&module_env, &mut env);
+
+    match names {
+        None => env.insert_variable(module.clone(), EnvValue::Module(module)),
+        Some(names) => {
+            for name in names {
+                match module_attribute(&module, &name, &module_env) {
+                    Some(value) => env.insert_variable(name, value),
+                    None => {
+                        return Err(Exception::new(
+                            "ImportError",
+                            format!("cannot import name '{}' from module '{}'.", name, module),
+                        ))
+                    }
+                }
+            }
+        }
+    }
 
-    #[test]
-    fn eval_constant() {
-        let env: Environment<EnvValue> = Environment::new();
+    Ok(ControlFlow::Continue(env))
+}
 
-        let c10 = CInt(10);
-        let c20 = CInt(20);
+/* a name a module exports; its functions remember the module they come from */
+fn module_attribute(
+    module: &Name,
+    name: &Name,
+    module_env: &Environment<EnvValue>,
+) -> Option<EnvValue> {
+    match module_env.search_export(name)? {
+        EnvValue::Func(func) if func.module.is_none() => Some(EnvValue::Func(Box::new(Function {
+            module: Some(module.clone()),
+            ..*func.clone()
+        }))),
+        value => Some(value.clone()),
+    }
+}
 
-        assert_eq!(eval(c10, &env), Ok(EnvValue::Exp(CInt(10))));
-        assert_eq!(eval(c20, &env), Ok(EnvValue::Exp(CInt(20))));
+/* the types a module defines come along with the values it exports */
+fn import_definitions(module_env: &Environment<EnvValue>, env: &mut Environment<EnvValue>) {
+    for (name, definition) in &module_env.definitions {
+        if env.search_definition(name).is_none() {
+            env.insert_definition(name.clone(), definition.clone());
+        }
     }
+}
 
-    #[test]
-    fn eval_add_expression1() {
-        let env: Environment<EnvValue> = Environment::new();
+/* runs a function body in a new frame, yielding 'None' when it completes without returning */
+fn invoke(
+    func: Function,
+    bindings: Vec<(Name, EnvValue)>,
+    env: &Environment<EnvValue>,
+) -> Result<Option<EnvValue>, Exception> {
+    /* an imported function runs in its module, where the names its body reads are bound */
+    let mut new_env = match &func.module {
+        Some(module) => execute_module(module, env)?,
+        None => env.clone(),
+    };
 
-        let c10 = CInt(10);
-        let c20 = CInt(20);
-        let add1 = Add(Box::new(c10), Box::new(c20));
+    new_env.insert_frame(func.clone());
 
-        assert_eq!(eval(add1, &env), Ok(EnvValue::Exp(CInt(30))));
+    for (name, value) in bindings {
+        new_env.insert_variable(name, value);
     }
 
-    #[test]
-    fn eval_add_expression2() {
-        let env: Environment<EnvValue> = Environment::new();
-
-        let c10 = CInt(10);
-        let c20 = CInt(20);
-        let c30 = CInt(30);
-        let add1 = Add(Box::new(c10), Box::new(c20));
-        let add2 = Add(Box::new(add1), Box::new(c30));
-
-        assert_eq!(eval(add2, &env), Ok(EnvValue::Exp(CInt(60))));
+    if new_env.search_frame(func.name.clone()).is_none() {
+        new_env.insert_variable(func.name.clone(), EnvValue::Func(Box::new(func.clone())));
     }
 
-    #[test]
-    fn eval_add_expression3() {
-        let env: Environment<EnvValue> = Environment::new();
+    let result = execute(*func.body.unwrap(), &new_env);
+    new_env.remove_frame();
 
-        let c10 = CInt(10);
-        let c20 = CReal(20.5);
-        let add1 = Add(Box::new(c10), Box::new(c20));
+    let value = match result {
+        Ok(ControlFlow::Return(value)) => value,
+        /* a procedure that ends without returning returns 'None' */
+        Ok(ControlFlow::Continue(_)) => match func.kind {
+            None | Some(Type::TUnit) => EnvValue::Exp(Expression::CNone),
+            Some(_) => return Ok(None),
+        },
+        Ok(ControlFlow::Raise(exception)) | Err(exception) => return Err(exception),
+    };
 
-        assert_eq!(eval(add1, &env), Ok(EnvValue::Exp(CReal(30.5))));
-    }
+    Ok(Some(match &func.kind {
+        Some(kind) => {
+            let context = format!("'{}()' return value", func.name);
+            coerce(value, kind, &context, env)?
+        }
+        None => value,
+    }))
+}
 
-    #[test]
-    fn eval_sub_expression1() {
-        let env: Environment<EnvValue> = Environment::new();
+fn new_object(
+    name: Name,
+    args: Vec<Expression>,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    if env.search_class(&name).is_none() {
+        return Err(
+            Exception::new("NameError", format!("class '{}' is not defined.", name)).into(),
+        );
+    }
 
-        let c10 = CInt(10);
-        let c20 = CInt(20);
-        let sub1 = Sub(Box::new(c20), Box::new(c10));
+    let object = Rc::new(RefCell::new(Object {
+        class: name.clone(),
+        fields: HashMap::new(),
+    }));
 
-        assert_eq!(eval(sub1, &env), Ok(EnvValue::Exp(CInt(10))));
+    match env.search_method(&name, &String::from("__init__")) {
+        Some(init) => {
+            call_method(&name, init, object.clone(), args, env)?;
+        }
+        None if !args.is_empty() => {
+            return Err(
+                Exception::new("TypeError", format!("'{}()' takes no arguments.", name)).into(),
+            )
+        }
+        None => (),
     }
 
-    #[test]
-    fn eval_sub_expression2() {
-        let env: Environment<EnvValue> = Environment::new();
-
-        let c100 = CInt(100);
-        let c200 = CInt(300);
-        let sub1 = Sub(Box::new(c200), Box::new(c100));
+    Ok(EnvValue::Object(object))
+}
 
-        assert_eq!(eval(sub1, &env), Ok(EnvValue::Exp(CInt(200))));
+fn method_call(
+    exp: Expression,
+    method: Name,
+    args: Vec<Expression>,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    let object = match eval_exp(exp, env)? {
+        EnvValue::Object(object) => object,
+        /* 'module.f(...)' calls a function the module exports */
+        EnvValue::Module(module) => {
+            let module_env = execute_module(&module, env)?;
+
+            return match module_attribute(&module, &method, &module_env) {
+                Some(value) => call(value, args, env),
+                None => Err(Exception::new(
+                    "AttributeError",
+                    format!("module '{}' has no attribute '{}'.", module, method),
+                )
+                .into()),
+            };
+        }
+        _ => {
+            return Err(Exception::new(
+                "TypeError",
+                format!("method call '.{}()' is only defined for objects.", method),
+            )
+            .into())
+        }
+    };
+
+    /* dispatch on the runtime class of the receiver */
+    let class = object.borrow().class.clone();
+
+    let func = match env.search_method(&class, &method) {
+        Some(func) => func,
+        None => {
+            return Err(Exception::new(
+                "AttributeError",
+                format!("'{}' object has no method '{}()'.", class, method),
+            )
+            .into())
+        }
+    };
+
+    match call_method(&class, func, object, args, env)? {
+        Some(value) => Ok(value),
+        None => Err(Exception::new(
+            "TypeError",
+            format!("method '{}.{}()' does not return a value.", class, method),
+        )
+        .into()),
     }
+}
 
-    #[test]
-    fn eval_sub_expression3() {
-        let env: Environment<EnvValue> = Environment::new();
-
-        let c100 = CReal(100.5);
-        let c300 = CInt(300);
-        let sub1 = Sub(Box::new(c300), Box::new(c100));
+fn call_method(
+    class: &Name,
+    func: Function,
+    object: Rc<RefCell<Object>>,
+    args: Vec<Expression>,
+    env: &Environment<EnvValue>,
+) -> Result<Option<EnvValue>, Unwind> {
+    let mut params = func.params.clone().unwrap_or_default().into_iter();
+    let qualified_name = format!("{}.{}", class, func.name);
+
+    let receiver = match params.next() {
+        Some((receiver, _)) => receiver,
+        None => {
+            return Err(Exception::new(
+                "TypeError",
+                format!("'{}()' takes no parameter for the object.", qualified_name),
+            )
+            .into())
+        }
+    };
 
-        assert_eq!(eval(sub1, &env), Ok(EnvValue::Exp(CReal(199.5))));
-    }
+    /* the other arguments are bound like those of a function call */
+    let method = Function {
+        name: qualified_name,
+        params: Some(params.collect()),
+        ..func
+    };
+    let (positional_values, keyword_values) = eval_arguments(&method.name, args, env)?;
 
-    #[test]
-    fn eval_mul_expression1() {
-        let env: Environment<EnvValue> = Environment::new();
+    let mut bindings = bind_values(&method, positional_values, keyword_values, env)?;
+    bindings.insert(0, (receiver, EnvValue::Object(object)));
 
-        let c10 = CInt(10);
-        let c20 = CInt(20);
-        let mul1 = Mul(Box::new(c10), Box::new(c20));
+    Ok(invoke(method, bindings, env)?)
+}
 
-        assert_eq!(eval(mul1, &env), Ok(EnvValue::Exp(CInt(200))));
+/* only fields declared by the class or one of its ancestors can be set */
+fn set_attribute(
+    object: &Rc<RefCell<Object>>,
+    field: Name,
+    value: EnvValue,
+    env: &Environment<EnvValue>,
+) -> Result<(), Exception> {
+    let class = object.borrow().class.clone();
+
+    if !env
+        .class_fields(&class)
+        .iter()
+        .any(|(declared, _)| *declared == field)
+    {
+        return Err(Exception::new(
+            "AttributeError",
+            format!("'{}' object has no attribute '{}'.", class, field),
+        ));
     }
 
-    #[test]
-    fn eval_mul_expression2() {
-        let env: Environment<EnvValue> = Environment::new();
+    let kind = env
+        .class_fields(&class)
+        .into_iter()
+        .find(|(declared, _)| *declared == field)
+        .map(|(_, kind)| kind);
 
-        let c10 = CReal(10.5);
-        let c20 = CInt(20);
-        let mul1 = Mul(Box::new(c10), Box::new(c20));
+    let value = match kind {
+        Some(kind) => coerce(value, &kind, &format!("'{}.{}'", class, field), env)?,
+        None => value,
+    };
 
-        assert_eq!(eval(mul1, &env), Ok(EnvValue::Exp(CReal(210.0))));
+    object.borrow_mut().fields.insert(field, value);
+    Ok(())
+}
+
+/* converts integers flowing into 'real' positions, so a 'real' never holds a 'CInt' */
+fn widen(value: Expression, kind: &Type, env: &Environment<EnvValue>) -> Expression {
+    match (value, &env.expand_alias(kind)) {
+        (Expression::CInt(i), Type::TReal) => Expression::CReal(i as f64),
+        (Expression::CJust(value), Type::TMaybe(kind)) => {
+            Expression::CJust(Box::new(widen(*value, kind, env)))
+        }
+        (Expression::COk(value), Type::TResult(kind, _)) => {
+            Expression::COk(Box::new(widen(*value, kind, env)))
+        }
+        (Expression::CErr(value), Type::TResult(_, kind)) => {
+            Expression::CErr(Box::new(widen(*value, kind, env)))
+        }
+        (Expression::CList(values), Type::TList(kind)) => Expression::CList(
+            values
+                .into_iter()
+                .map(|value| widen(value, kind, env))
+                .collect(),
+        ),
+        (value, _) => value,
     }
+}
 
-    #[test]
-    fn eval_div_expression1() {
-        let env: Environment<EnvValue> = Environment::new();
+/* a value flowing into a statically typed position is widened, then checked against
+the type, which only fails for values coming from dynamically typed ('Any') code */
+fn coerce(
+    value: EnvValue,
+    kind: &Type,
+    context: &str,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Exception> {
+    let value = match value {
+        EnvValue::Exp(value) => EnvValue::Exp(widen(value, kind, env)),
+        value => value,
+    };
+
+    if !conforms(&value, kind, env) {
+        return Err(Exception::new(
+            "TypeError",
+            format!(
+                "{} expected '{:?}', found a value of type '{}'.",
+                context,
+                kind,
+                type_of_value(&value)
+            ),
+        ));
+    }
 
-        let c10 = CInt(10);
-        let c20 = CInt(20);
-        let div1 = Div(Box::new(c20), Box::new(c10));
+    Ok(value)
+}
 
-        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CInt(2))));
+fn coerce_exp(
+    value: Expression,
+    kind: &Type,
+    context: &str,
+    env: &Environment<EnvValue>,
+) -> Result<Expression, Exception> {
+    match coerce(EnvValue::Exp(value), kind, context, env)? {
+        EnvValue::Exp(value) => Ok(value),
+        _ => unreachable!(),
     }
+}
 
-    #[test]
-    fn eval_div_expression2() {
-        let env: Environment<EnvValue> = Environment::new();
+/* an unannotated assignment keeps the type of the variable, which a 'real' value reveals */
+fn coerce_assigned(
+    name: &Name,
+    value: EnvValue,
+    kind: &Option<Type>,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Exception> {
+    let context = format!("'{}'", name);
 
-        let c10 = CInt(10);
-        let c3 = CInt(3);
-        let div1 = Div(Box::new(c10), Box::new(c3));
+    match (kind, env.search_frame(name.clone())) {
+        (Some(kind), _) => coerce(value, kind, &context, env),
+        (None, Some(EnvValue::Exp(Expression::CReal(_)))) => {
+            coerce(value, &Type::TReal, &context, env)
+        }
+        _ => Ok(value),
+    }
+}
+
+fn conforms(value: &EnvValue, kind: &Type, env: &Environment<EnvValue>) -> bool {
+    match (value, &env.expand_alias(kind)) {
+        (_, Type::TAny | Type::TUnknown | Type::TVar(_)) => true,
+        (EnvValue::Func(func), kind @ Type::TFunction(_, _)) => {
+            same_type(&func.function_type(), kind, env)
+        }
+        (EnvValue::Module(module), Type::TModule(name)) => module == name,
+        (EnvValue::Object(object), Type::TClass(class)) => {
+            env.is_subclass(&object.borrow().class, class)
+        }
+        (EnvValue::Exp(value), kind) => match (value, kind) {
+            (Expression::CInt(_), Type::TInteger)
+            | (Expression::CReal(_), Type::TReal)
+            | (Expression::CTrue | Expression::CFalse, Type::TBool)
+            | (Expression::CString(_), Type::TString)
+            | (Expression::CNone, Type::TUnit)
+            | (Expression::CNothing, Type::TMaybe(_)) => true,
+            (Expression::CJust(value), Type::TMaybe(kind))
+            | (Expression::COk(value), Type::TResult(kind, _))
+            | (Expression::CErr(value), Type::TResult(_, kind)) => {
+                conforms(&EnvValue::Exp(*value.clone()), kind, env)
+            }
+            (Expression::Record(name, _), Type::TRecord(record)) => name == record,
+            (Expression::Constructor(cons, _), Type::TAdt(adt)) => env
+                .search_constructor(cons)
+                .is_some_and(|(name, _)| name == *adt),
+            (Expression::CList(values), Type::TList(kind)) => values
+                .iter()
+                .all(|value| conforms(&EnvValue::Exp(value.clone()), kind, env)),
+            /* tuples have no literal of their own, so a list of the right shape stands for one */
+            (Expression::CList(values), Type::TTuple(kinds)) => {
+                values.len() == kinds.len()
+                    && values
+                        .iter()
+                        .zip(kinds)
+                        .all(|(value, kind)| conforms(&EnvValue::Exp(value.clone()), kind, env))
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/* compares the signature of a function value with the one expected of it. An unknown or
+generic part of either type matches anything, and no return type means 'None' */
+fn same_type(found: &Type, expected: &Type, env: &Environment<EnvValue>) -> bool {
+    let returned = |kind: &Option<Type>| kind.clone().unwrap_or(Type::TUnit);
+
+    match (&env.expand_alias(found), &env.expand_alias(expected)) {
+        (Type::TAny | Type::TUnknown | Type::TVar(_), _)
+        | (_, Type::TAny | Type::TUnknown | Type::TVar(_)) => true,
+        (Type::TFunction(found_kind, found_params), Type::TFunction(kind, params)) => {
+            found_params.len() == params.len()
+                && same_type(&returned(found_kind), &returned(kind), env)
+                && found_params
+                    .iter()
+                    .zip(params)
+                    .all(|(found, expected)| same_type(found, expected, env))
+        }
+        (Type::TList(found), Type::TList(expected))
+        | (Type::TMaybe(found), Type::TMaybe(expected)) => same_type(found, expected, env),
+        (Type::TResult(found_ok, found_err), Type::TResult(ok, err)) => {
+            same_type(found_ok, ok, env) && same_type(found_err, err, env)
+        }
+        (Type::TTuple(found), Type::TTuple(expected)) => {
+            found.len() == expected.len()
+                && found
+                    .iter()
+                    .zip(expected)
+                    .all(|(found, expected)| same_type(found, expected, env))
+        }
+        (found, expected) => found == expected,
+    }
+}
+
+pub fn type_of_value(value: &EnvValue) -> String {
+    match value {
+        EnvValue::Exp(Expression::CInt(_)) => String::from("int"),
+        EnvValue::Exp(Expression::CReal(_)) => String::from("real"),
+        EnvValue::Exp(Expression::CTrue | Expression::CFalse) => String::from("bool"),
+        EnvValue::Exp(Expression::CString(_)) => String::from("str"),
+        EnvValue::Exp(Expression::CNone) => String::from("None"),
+        EnvValue::Exp(Expression::CList(_)) => String::from("list"),
+        EnvValue::Exp(Expression::CNothing | Expression::CJust(_)) => String::from("Maybe"),
+        EnvValue::Exp(Expression::COk(_) | Expression::CErr(_)) => String::from("Result"),
+        EnvValue::Exp(Expression::Record(name, _) | Expression::Constructor(name, _)) => {
+            name.clone()
+        }
+        EnvValue::Exp(value) => format!("{:?}", value),
+        EnvValue::Func(_) => String::from("function"),
+        EnvValue::Object(object) => object.borrow().class.clone(),
+        EnvValue::Module(_) => String::from("module"),
+    }
+}
+
+/* evaluates an expression that must produce a value rather than a function */
+fn eval_value(exp: Expression, env: &Environment<EnvValue>) -> Result<Expression, Unwind> {
+    match eval_exp(exp, env)? {
+        EnvValue::Exp(value) => Ok(value),
+        EnvValue::Func(func) => Err(Exception::new(
+            "TypeError",
+            format!("'{}()' is a function, not a value.", func.name),
+        )
+        .into()),
+        EnvValue::Object(object) => Err(Exception::new(
+            "TypeError",
+            format!(
+                "'{}' objects cannot be wrapped in values.",
+                object.borrow().class
+            ),
+        )
+        .into()),
+        EnvValue::Module(module) => {
+            Err(Exception::new("TypeError", format!("module '{}' is not a value.", module)).into())
+        }
+    }
+}
+
+fn is_variant(
+    exp: Expression,
+    variant: &str,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    let matches = match (eval_value(exp, env)?, variant) {
+        (Expression::CJust(_), "Just") | (Expression::CNothing, "Nothing") => true,
+        (Expression::COk(_), "Ok") | (Expression::CErr(_), "Err") => true,
+        (Expression::CJust(_) | Expression::CNothing, "Just" | "Nothing") => false,
+        (Expression::COk(_) | Expression::CErr(_), "Ok" | "Err") => false,
+        _ => {
+            return Err(expected_maybe_or_result(&format!("is_{}", variant.to_lowercase())).into())
+        }
+    };
+
+    Ok(EnvValue::Exp(if matches {
+        Expression::CTrue
+    } else {
+        Expression::CFalse
+    }))
+}
+
+fn expected_maybe_or_result(helper: &str) -> Exception {
+    Exception::new(
+        "TypeError",
+        format!("{} expects a 'Maybe' or 'Result' value.", helper),
+    )
+}
+
+fn record(
+    name: Name,
+    fields: Vec<(Name, Expression)>,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    let declared_fields = match env.search_definition(&name) {
+        Some(TypeDefinition::Record(declared_fields)) => declared_fields.clone(),
+        _ => {
+            return Err(
+                Exception::new("NameError", format!("record '{}' is not defined.", name)).into(),
+            )
+        }
+    };
+
+    if let Some((field, _)) = fields.iter().find(|(field, _)| {
+        !declared_fields
+            .iter()
+            .any(|(declared, _)| declared == field)
+    }) {
+        return Err(Exception::new(
+            "AttributeError",
+            format!("record '{}' has no field '{}'.", name, field),
+        )
+        .into());
+    }
+
+    let mut values = vec![];
+
+    for (field, exp) in fields {
+        match eval_exp(exp, env)? {
+            EnvValue::Exp(value) => values.push((field, value)),
+            EnvValue::Func(_) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    String::from("record fields cannot hold functions."),
+                )
+                .into())
+            }
+            EnvValue::Object(_) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    String::from("record fields cannot hold objects."),
+                )
+                .into())
+            }
+            EnvValue::Module(_) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    String::from("record fields cannot hold modules."),
+                )
+                .into())
+            }
+        }
+    }
+
+    /* record values always keep their fields in declaration order */
+    let mut record_fields = vec![];
+
+    for (declared, kind) in declared_fields {
+        match values.iter().position(|(field, _)| *field == declared) {
+            Some(index) => {
+                let (field, value) = values.remove(index);
+                let context = format!("'{}.{}'", name, field);
+                record_fields.push((field, coerce_exp(value, &kind, &context, env)?));
+            }
+            None => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!("record '{}' is missing field '{}'.", name, declared),
+                )
+                .into())
+            }
+        }
+    }
+
+    Ok(EnvValue::Exp(Expression::Record(name, record_fields)))
+}
+
+fn field_access(
+    exp: Expression,
+    field: Name,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    match eval_exp(exp, env)? {
+        EnvValue::Exp(Expression::Record(name, fields)) => {
+            match fields
+                .into_iter()
+                .find(|(field_name, _)| *field_name == field)
+            {
+                Some((_, value)) => Ok(EnvValue::Exp(value)),
+                None => Err(Exception::new(
+                    "AttributeError",
+                    format!("record '{}' has no field '{}'.", name, field),
+                )
+                .into()),
+            }
+        }
+        EnvValue::Object(object) => {
+            let object = object.borrow();
+
+            match object.fields.get(&field) {
+                Some(value) => Ok(value.clone()),
+                None => Err(Exception::new(
+                    "AttributeError",
+                    format!("'{}' object has no attribute '{}'.", object.class, field),
+                )
+                .into()),
+            }
+        }
+        EnvValue::Module(module) => {
+            let module_env = execute_module(&module, env)?;
+
+            match module_attribute(&module, &field, &module_env) {
+                Some(value) => Ok(value),
+                None => Err(Exception::new(
+                    "AttributeError",
+                    format!("module '{}' has no attribute '{}'.", module, field),
+                )
+                .into()),
+            }
+        }
+        _ => Err(Exception::new(
+            "TypeError",
+            format!("field access '.{}' is only defined for records.", field),
+        )
+        .into()),
+    }
+}
+
+fn constructor(
+    name: Name,
+    args: Vec<Expression>,
+    env: &Environment<EnvValue>,
+) -> Result<EnvValue, Unwind> {
+    let types = match env.search_constructor(&name) {
+        Some((_, types)) => types,
+        None => {
+            return Err(Exception::new(
+                "NameError",
+                format!("constructor '{}' is not defined.", name),
+            )
+            .into())
+        }
+    };
+
+    if args.len() != types.len() {
+        return Err(Exception::new(
+            "TypeError",
+            format!(
+                "constructor '{}' expected {} arguments, found {}.",
+                name,
+                types.len(),
+                args.len()
+            ),
+        )
+        .into());
+    }
+
+    let mut values = vec![];
+
+    for (arg, kind) in args.into_iter().zip(&types) {
+        match eval_exp(arg, env)? {
+            EnvValue::Exp(value) => {
+                let context = format!("constructor '{}'", name);
+                values.push(coerce_exp(value, kind, &context, env)?)
+            }
+            EnvValue::Func(_) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!("constructor '{}' cannot hold functions.", name),
+                )
+                .into())
+            }
+            EnvValue::Object(_) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!("constructor '{}' cannot hold objects.", name),
+                )
+                .into())
+            }
+            EnvValue::Module(_) => {
+                return Err(Exception::new(
+                    "TypeError",
+                    format!("constructor '{}' cannot hold modules.", name),
+                )
+                .into())
+            }
+        }
+    }
+
+    Ok(EnvValue::Exp(Expression::Constructor(name, values)))
+}
+
+/* returns the variables bound by the pattern, or 'None' when it does not match */
+fn match_pattern(
+    pattern: &Pattern,
+    value: &EnvValue,
+) -> Result<Option<Vec<(Name, EnvValue)>>, Exception> {
+    match (pattern, value) {
+        (Pattern::Wildcard, _) => Ok(Some(vec![])),
+        (Pattern::Binding(name), _) => Ok(Some(vec![(name.clone(), value.clone())])),
+        (Pattern::Literal(literal), EnvValue::Exp(_)) => {
+            if values_equal(&EnvValue::Exp(literal.clone()), value)? {
+                Ok(Some(vec![]))
+            } else {
+                Ok(None)
+            }
+        }
+        (
+            Pattern::Constructor(name, patterns),
+            EnvValue::Exp(Expression::Constructor(cons, values)),
+        ) => {
+            if name != cons || patterns.len() != values.len() {
+                return Ok(None);
+            }
+
+            let mut bindings = vec![];
+
+            for (pattern, value) in patterns.iter().zip(values) {
+                match match_pattern(pattern, &EnvValue::Exp(value.clone()))? {
+                    Some(sub_bindings) => bindings.extend(sub_bindings),
+                    None => return Ok(None),
+                }
+            }
+
+            Ok(Some(bindings))
+        }
+        (Pattern::Constructor(name, patterns), EnvValue::Exp(wrapped)) => {
+            let inner = match (name.as_str(), wrapped) {
+                ("Just", Expression::CJust(inner))
+                | ("Ok", Expression::COk(inner))
+                | ("Err", Expression::CErr(inner)) => Some(inner),
+                ("Nothing", Expression::CNothing) => None,
+                _ => return Ok(None),
+            };
+
+            match (patterns.as_slice(), inner) {
+                ([pattern], Some(inner)) => match_pattern(pattern, &EnvValue::Exp(*inner.clone())),
+                ([], None) => Ok(Some(vec![])),
+                _ => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn bind_all(bindings: Vec<(Name, EnvValue)>, env: &Environment<EnvValue>) -> Environment<EnvValue> {
+    let mut new_env = env.clone();
+
+    for (name, value) in bindings {
+        new_env.insert_variable(name, value);
+    }
+
+    new_env
+}
+
+fn no_match_error(value: &EnvValue) -> Exception {
+    let text = match value {
+        EnvValue::Exp(exp) => value_to_string(exp).unwrap_or(format!("{:?}", exp)),
+        EnvValue::Func(func) => format!("{}()", func.name),
+        EnvValue::Object(object) => format!("{} object", object.borrow().class),
+        EnvValue::Module(module) => format!("module '{}'", module),
+    };
+
+    Exception::new("MatchError", format!("no pattern matches '{}'.", text))
+}
+
+pub fn value_to_string(value: &Expression) -> Option<String> {
+    match value {
+        Expression::CInt(i) => Some(i.to_string()),
+        Expression::CReal(r) => Some(r.to_string()),
+        Expression::CString(s) => Some(s.clone()),
+        Expression::CTrue => Some(String::from("true")),
+        Expression::CFalse => Some(String::from("false")),
+        Expression::Record(name, fields) => {
+            let mut texts = vec![];
+
+            for (field, value) in fields {
+                let text = match value {
+                    Expression::CString(s) => format!("{:?}", s),
+                    _ => value_to_string(value)?,
+                };
+                texts.push(format!("{}={}", field, text));
+            }
+
+            Some(format!("{}({})", name, texts.join(", ")))
+        }
+        Expression::CNone => Some(String::from("None")),
+        Expression::CList(values) => {
+            let mut texts = vec![];
+            for value in values {
+                texts.push(wrapped_to_string(value)?);
+            }
+            Some(format!("[{}]", texts.join(", ")))
+        }
+        Expression::CNothing => Some(String::from("Nothing")),
+        Expression::CJust(value) => Some(format!("Just({})", wrapped_to_string(value)?)),
+        Expression::COk(value) => Some(format!("Ok({})", wrapped_to_string(value)?)),
+        Expression::CErr(value) => Some(format!("Err({})", wrapped_to_string(value)?)),
+        Expression::Constructor(name, values) if values.is_empty() => Some(name.clone()),
+        Expression::Constructor(name, values) => {
+            let mut texts = vec![];
+
+            for value in values {
+                match value {
+                    Expression::CString(s) => texts.push(format!("{:?}", s)),
+                    _ => texts.push(value_to_string(value)?),
+                }
+            }
+
+            Some(format!("{}({})", name, texts.join(", ")))
+        }
+        _ => None,
+    }
+}
+
+fn wrapped_to_string(value: &Expression) -> Option<String> {
+    match value {
+        Expression::CString(s) => Some(format!("{:?}", s)),
+        _ => value_to_string(value),
+    }
+}
+
+fn is_constant(exp: Expression) -> bool {
+    matches!(
+        exp,
+        Expression::CTrue
+            | Expression::CFalse
+            | Expression::CInt(_)
+            | Expression::CReal(_)
+            | Expression::CString(_)
+            | Expression::CNone
+            | Expression::CNothing
+    )
+}
+
+fn lookup(name: String, env: &Environment<EnvValue>) -> Result<EnvValue, Exception> {
+    let mut curr_scope = env.scope_key();
+
+    loop {
+        let frame = env.get_frame(curr_scope.clone());
+
+        match (frame.variables.get(&name), &frame.parent_key) {
+            (Some(value), _) => return Ok(value.clone()),
+            (None, Some(parent)) => curr_scope = parent.clone(),
+            (None, None) => {
+                return Err(Exception::new(
+                    "NameError",
+                    format!("'{}' is not defined.", name),
+                ))
+            }
+        }
+    }
+}
+
+/* Arithmetic Operations */
+fn eval_binary_arith_op<F>(
+    lhs: Expression,
+    rhs: Expression,
+    env: &Environment<EnvValue>,
+    op: F,
+    error_msg: &str,
+) -> Result<EnvValue, Unwind>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let v1 = eval_exp(lhs, env)?;
+    let v2 = eval_exp(rhs, env)?;
+    Ok(apply_binary_arith_op(v1, v2, op, error_msg)?)
+}
+
+fn apply_binary_arith_op<F>(
+    v1: EnvValue,
+    v2: EnvValue,
+    op: F,
+    error_msg: &str,
+) -> Result<EnvValue, Exception>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    match (v1, v2) {
+        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CInt(v2))) => Ok(
+            EnvValue::Exp(Expression::CInt(op(v1 as f64, v2 as f64) as i32)),
+        ),
+        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
+            Ok(EnvValue::Exp(Expression::CReal(op(v1 as f64, v2))))
+        }
+        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
+            Ok(EnvValue::Exp(Expression::CReal(op(v1, v2 as f64))))
+        }
+        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
+            Ok(EnvValue::Exp(Expression::CReal(op(v1, v2))))
+        }
+        _ => Err(Exception::new("TypeError", error_msg.to_string())),
+    }
+}
+
+fn add(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_arith_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| a + b,
+        "addition '(+)' is only defined for numbers (integers and real).",
+    )
+}
+
+fn sub(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_arith_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| a - b,
+        "subtraction '(-)' is only defined for numbers (integers and real).",
+    )
+}
+
+fn mul(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_arith_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| a * b,
+        "multiplication '(*)' is only defined for numbers (integers and real).",
+    )
+}
+
+fn div(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let exp = Expression::Div(Box::new(lhs.clone()), Box::new(rhs.clone()));
+
+    let v1 = eval_exp(lhs, env)?;
+    let v2 = eval_exp(rhs, env)?;
+
+    let zero_division = match (&v1, &v2) {
+        (EnvValue::Exp(Expression::CInt(_)), EnvValue::Exp(Expression::CInt(0))) => true,
+        (EnvValue::Exp(Expression::CInt(_) | Expression::CReal(_)), EnvValue::Exp(divisor)) => {
+            env.settings.strict_real_division && is_zero(divisor)
+        }
+        _ => false,
+    };
+
+    if zero_division {
+        return Err(Exception::new(
+            "ZeroDivisionError",
+            format!(
+                "division by zero in '{:?}' on '{}()'.",
+                exp,
+                env.scope_name()
+            ),
+        )
+        .into());
+    }
+
+    Ok(apply_binary_arith_op(
+        v1,
+        v2,
+        |a, b| a / b,
+        "division '(/)' is only defined for numbers (integers and real).",
+    )?)
+}
+
+fn is_zero(exp: &Expression) -> bool {
+    match exp {
+        Expression::CInt(v) => *v == 0,
+        Expression::CReal(v) => *v == 0.0,
+        _ => false,
+    }
+}
+
+/* Boolean Expressions */
+fn eval_binary_boolean_op<F>(
+    lhs: Expression,
+    rhs: Expression,
+    env: &Environment<EnvValue>,
+    op: F,
+    error_msg: &str,
+) -> Result<EnvValue, Unwind>
+where
+    F: Fn(bool, bool) -> Expression,
+{
+    let v1 = eval_exp(lhs, env)?;
+    let v2 = eval_exp(rhs, env)?;
+    match (v1, v2) {
+        (EnvValue::Exp(Expression::CTrue), EnvValue::Exp(Expression::CTrue)) => {
+            Ok(EnvValue::Exp(op(true, true)))
+        }
+        (EnvValue::Exp(Expression::CTrue), EnvValue::Exp(Expression::CFalse)) => {
+            Ok(EnvValue::Exp(op(true, false)))
+        }
+        (EnvValue::Exp(Expression::CFalse), EnvValue::Exp(Expression::CTrue)) => {
+            Ok(EnvValue::Exp(op(false, true)))
+        }
+        (EnvValue::Exp(Expression::CFalse), EnvValue::Exp(Expression::CFalse)) => {
+            Ok(EnvValue::Exp(op(false, false)))
+        }
+        _ => Err(Exception::new("TypeError", error_msg.to_string()).into()),
+    }
+}
+
+fn and(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_boolean_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| {
+            if a && b {
+                Expression::CTrue
+            } else {
+                Expression::CFalse
+            }
+        },
+        "'and' is only defined for booleans.",
+    )
+}
+
+fn or(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_boolean_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| {
+            if a || b {
+                Expression::CTrue
+            } else {
+                Expression::CFalse
+            }
+        },
+        "'or' is only defined for booleans.",
+    )
+}
+
+fn not(lhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let v = eval_exp(lhs, env)?;
+    match v {
+        EnvValue::Exp(Expression::CTrue) => Ok(EnvValue::Exp(Expression::CFalse)),
+        EnvValue::Exp(Expression::CFalse) => Ok(EnvValue::Exp(Expression::CTrue)),
+        _ => Err(Exception::new(
+            "TypeError",
+            String::from("'not' is only defined for booleans."),
+        )
+        .into()),
+    }
+}
+
+/* Relational Operations */
+fn eval_binary_rel_op<F>(
+    lhs: Expression,
+    rhs: Expression,
+    env: &Environment<EnvValue>,
+    op: F,
+    error_msg: &str,
+) -> Result<EnvValue, Unwind>
+where
+    F: Fn(f64, f64) -> Expression,
+{
+    let v1 = eval_exp(lhs, env)?;
+    let v2 = eval_exp(rhs, env)?;
+    match (v1, v2) {
+        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
+            Ok(EnvValue::Exp(op(v1 as f64, v2 as f64)))
+        }
+        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
+            Ok(EnvValue::Exp(op(v1 as f64, v2)))
+        }
+        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
+            Ok(EnvValue::Exp(op(v1, v2 as f64)))
+        }
+        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
+            Ok(EnvValue::Exp(op(v1, v2)))
+        }
+        _ => Err(Exception::new("TypeError", error_msg.to_string()).into()),
+    }
+}
+
+/* Equality Operations */
+fn eq(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let v1 = eval_exp(lhs, env)?;
+    let v2 = eval_exp(rhs, env)?;
+
+    if values_equal(&v1, &v2)? {
+        Ok(EnvValue::Exp(Expression::CTrue))
+    } else {
+        Ok(EnvValue::Exp(Expression::CFalse))
+    }
+}
+
+fn neq(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    let v1 = eval_exp(lhs, env)?;
+    let v2 = eval_exp(rhs, env)?;
+
+    if values_equal(&v1, &v2)? {
+        Ok(EnvValue::Exp(Expression::CFalse))
+    } else {
+        Ok(EnvValue::Exp(Expression::CTrue))
+    }
+}
+
+fn values_equal(v1: &EnvValue, v2: &EnvValue) -> Result<bool, Exception> {
+    match (v1, v2) {
+        (EnvValue::Exp(Expression::CInt(v1)), EnvValue::Exp(Expression::CReal(v2))) => {
+            Ok(*v1 as f64 == *v2)
+        }
+        (EnvValue::Exp(Expression::CReal(v1)), EnvValue::Exp(Expression::CInt(v2))) => {
+            Ok(*v1 == *v2 as f64)
+        }
+        (
+            EnvValue::Exp(Expression::Record(name1, fields1)),
+            EnvValue::Exp(Expression::Record(name2, fields2)),
+        ) => {
+            if name1 != name2 || fields1.len() != fields2.len() {
+                return Ok(false);
+            }
+
+            for ((_, v1), (_, v2)) in fields1.iter().zip(fields2) {
+                if !values_equal(&EnvValue::Exp(v1.clone()), &EnvValue::Exp(v2.clone()))? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+        (
+            EnvValue::Exp(Expression::Constructor(name1, values1)),
+            EnvValue::Exp(Expression::Constructor(name2, values2)),
+        ) => {
+            if name1 != name2 || values1.len() != values2.len() {
+                return Ok(false);
+            }
+
+            for (v1, v2) in values1.iter().zip(values2) {
+                if !values_equal(&EnvValue::Exp(v1.clone()), &EnvValue::Exp(v2.clone()))? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+        (EnvValue::Exp(Expression::CJust(v1)), EnvValue::Exp(Expression::CJust(v2)))
+        | (EnvValue::Exp(Expression::COk(v1)), EnvValue::Exp(Expression::COk(v2)))
+        | (EnvValue::Exp(Expression::CErr(v1)), EnvValue::Exp(Expression::CErr(v2))) => {
+            values_equal(&EnvValue::Exp(*v1.clone()), &EnvValue::Exp(*v2.clone()))
+        }
+        (EnvValue::Exp(Expression::CList(l1)), EnvValue::Exp(Expression::CList(l2))) => {
+            if l1.len() != l2.len() {
+                return Ok(false);
+            }
+            for (v1, v2) in l1.iter().zip(l2) {
+                if !values_equal(&EnvValue::Exp(v1.clone()), &EnvValue::Exp(v2.clone()))? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (EnvValue::Exp(Expression::CNothing), EnvValue::Exp(Expression::CNothing)) => Ok(true),
+        /* a wrapped value never equals a value wrapped by another constructor, or a bare one */
+        (
+            EnvValue::Exp(
+                Expression::CJust(_)
+                | Expression::CNothing
+                | Expression::COk(_)
+                | Expression::CErr(_),
+            ),
+            EnvValue::Exp(_),
+        )
+        | (
+            EnvValue::Exp(_),
+            EnvValue::Exp(
+                Expression::CJust(_)
+                | Expression::CNothing
+                | Expression::COk(_)
+                | Expression::CErr(_),
+            ),
+        ) => Ok(false),
+        (EnvValue::Exp(v1), EnvValue::Exp(v2)) if is_constant(v1.clone()) => Ok(v1 == v2),
+        /* objects are equal only to themselves */
+        (EnvValue::Object(o1), EnvValue::Object(o2)) => Ok(Rc::ptr_eq(o1, o2)),
+        (EnvValue::Module(m1), EnvValue::Module(m2)) => Ok(m1 == m2),
+        (EnvValue::Func(_), _) | (_, EnvValue::Func(_)) => Err(Exception::new(
+            "TypeError",
+            String::from("(==) and (!=) are not defined for functions."),
+        )),
+        /* values of different kinds are never equal */
+        _ => Ok(false),
+    }
+}
+
+fn gt(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_rel_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| {
+            if a > b {
+                Expression::CTrue
+            } else {
+                Expression::CFalse
+            }
+        },
+        "(>) is only defined for numbers (integers and real).",
+    )
+}
+
+fn lt(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_rel_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| {
+            if a < b {
+                Expression::CTrue
+            } else {
+                Expression::CFalse
+            }
+        },
+        "(<) is only defined for numbers (integers and real).",
+    )
+}
+
+fn gte(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_rel_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| {
+            if a >= b {
+                Expression::CTrue
+            } else {
+                Expression::CFalse
+            }
+        },
+        "(>=) is only defined for numbers (integers and real).",
+    )
+}
+
+fn lte(lhs: Expression, rhs: Expression, env: &Environment<EnvValue>) -> Result<EnvValue, Unwind> {
+    eval_binary_rel_op(
+        lhs,
+        rhs,
+        env,
+        |a, b| {
+            if a <= b {
+                Expression::CTrue
+            } else {
+                Expression::CFalse
+            }
+        },
+        "(<=) is only defined for numbers (integers and real).",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use super::*;
+    use crate::interpreter::io::MemoryIo;
+    use crate::ir::ast::Class;
+    use crate::ir::ast::Expression::*;
+    use crate::ir::ast::Function;
+    use crate::ir::ast::Statement::*;
+    use crate::ir::ast::Type::*;
+    use crate::ir::module::{MemoryLoader, ModuleLoader, Modules};
+    use crate::ir::sandbox::{Policy, Sandbox};
+    use crate::stdlib::builtins::interpreter_env;
+    use approx::relative_eq;
+    use std::cell::Cell;
+
+    #[test]
+    fn eval_constant() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+
+        assert_eq!(eval(c10, &env), Ok(EnvValue::Exp(CInt(10))));
+        assert_eq!(eval(c20, &env), Ok(EnvValue::Exp(CInt(20))));
+    }
+
+    #[test]
+    fn eval_add_expression1() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+        let add1 = Add(Box::new(c10), Box::new(c20));
+
+        assert_eq!(eval(add1, &env), Ok(EnvValue::Exp(CInt(30))));
+    }
+
+    #[test]
+    fn eval_add_expression2() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+        let c30 = CInt(30);
+        let add1 = Add(Box::new(c10), Box::new(c20));
+        let add2 = Add(Box::new(add1), Box::new(c30));
+
+        assert_eq!(eval(add2, &env), Ok(EnvValue::Exp(CInt(60))));
+    }
+
+    #[test]
+    fn eval_add_expression3() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CReal(20.5);
+        let add1 = Add(Box::new(c10), Box::new(c20));
+
+        assert_eq!(eval(add1, &env), Ok(EnvValue::Exp(CReal(30.5))));
+    }
+
+    #[test]
+    fn eval_sub_expression1() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+        let sub1 = Sub(Box::new(c20), Box::new(c10));
+
+        assert_eq!(eval(sub1, &env), Ok(EnvValue::Exp(CInt(10))));
+    }
+
+    #[test]
+    fn eval_sub_expression2() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c100 = CInt(100);
+        let c200 = CInt(300);
+        let sub1 = Sub(Box::new(c200), Box::new(c100));
+
+        assert_eq!(eval(sub1, &env), Ok(EnvValue::Exp(CInt(200))));
+    }
+
+    #[test]
+    fn eval_sub_expression3() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c100 = CReal(100.5);
+        let c300 = CInt(300);
+        let sub1 = Sub(Box::new(c300), Box::new(c100));
+
+        assert_eq!(eval(sub1, &env), Ok(EnvValue::Exp(CReal(199.5))));
+    }
+
+    #[test]
+    fn eval_mul_expression1() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+        let mul1 = Mul(Box::new(c10), Box::new(c20));
+
+        assert_eq!(eval(mul1, &env), Ok(EnvValue::Exp(CInt(200))));
+    }
+
+    #[test]
+    fn eval_mul_expression2() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CReal(10.5);
+        let c20 = CInt(20);
+        let mul1 = Mul(Box::new(c10), Box::new(c20));
+
+        assert_eq!(eval(mul1, &env), Ok(EnvValue::Exp(CReal(210.0))));
+    }
+
+    #[test]
+    fn eval_div_expression1() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+        let div1 = Div(Box::new(c20), Box::new(c10));
+
+        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CInt(2))));
+    }
+
+    #[test]
+    fn eval_div_expression2() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c3 = CInt(3);
+        let div1 = Div(Box::new(c10), Box::new(c3));
+
+        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CInt(3))));
+    }
+
+    #[test]
+    fn eval_div_expression3() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c3 = CInt(3);
+        let c21 = CInt(21);
+        let div1 = Div(Box::new(c21), Box::new(c3));
+
+        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CInt(7))));
+    }
+
+    #[test]
+    fn eval_div_expression4() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let c10 = CInt(10);
+        let c3 = CReal(3.0);
+        let div1 = Div(Box::new(c10), Box::new(c3));
+        let res = eval(div1, &env);
+
+        match res {
+            Ok(EnvValue::Exp(Expression::CReal(v))) => {
+                assert!(relative_eq!(v, 3.3333333333333335, epsilon = f64::EPSILON))
+            }
+            Err(msg) => assert!(false, "{}", msg),
+            _ => assert!(false, "Not expected."),
+        }
+    }
+
+    #[test]
+    fn eval_div_integer_by_zero() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let div1 = Div(Box::new(CInt(1)), Box::new(CInt(0)));
+
+        assert_eq!(
+            eval(div1, &env),
+            Err(Exception::new(
+                "ZeroDivisionError",
+                String::from("division by zero in 'Div(CInt(1), CInt(0))' on '__main__()'."),
+            ))
+        );
+    }
+
+    #[test]
+    fn eval_div_mixed_by_zero() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let div1 = Div(Box::new(CReal(1.5)), Box::new(CInt(0)));
+        let div2 = Div(Box::new(CInt(1)), Box::new(CReal(0.0)));
+        let div3 = Div(Box::new(CReal(1.5)), Box::new(CReal(0.0)));
+
+        assert_eq!(
+            eval(div1, &env),
+            Err(Exception::new(
+                "ZeroDivisionError",
+                String::from("division by zero in 'Div(CReal(1.5), CInt(0))' on '__main__()'."),
+            ))
+        );
+        assert_eq!(
+            eval(div2, &env),
+            Err(Exception::new(
+                "ZeroDivisionError",
+                String::from("division by zero in 'Div(CInt(1), CReal(0.0))' on '__main__()'."),
+            ))
+        );
+        assert_eq!(
+            eval(div3, &env),
+            Err(Exception::new(
+                "ZeroDivisionError",
+                String::from("division by zero in 'Div(CReal(1.5), CReal(0.0))' on '__main__()'."),
+            ))
+        );
+    }
+
+    #[test]
+    fn eval_div_real_by_zero_non_strict() {
+        let mut env: Environment<EnvValue> = Environment::new();
+        env.settings.strict_real_division = false;
+
+        let div1 = Div(Box::new(CReal(1.5)), Box::new(CInt(0)));
+        let div2 = Div(Box::new(CInt(1)), Box::new(CInt(0)));
+
+        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CReal(f64::INFINITY))));
+        assert!(eval(div2, &env).is_err());
+    }
+
+    #[test]
+    fn eval_div_by_zero_variable() {
+        let mut env = Environment::new();
+        env.insert_variable("x".to_string(), EnvValue::Exp(CInt(0)));
+
+        let div1 = Div(Box::new(CInt(10)), Box::new(Var("x".to_string())));
+
+        assert_eq!(
+            eval(div1, &env),
+            Err(Exception::new(
+                "ZeroDivisionError",
+                String::from("division by zero in 'Div(CInt(10), Var(\"x\"))' on '__main__()'."),
+            ))
+        );
+    }
+
+    #[test]
+    fn eval_equality_expressions() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let eq1 = EQ(
+            Box::new(CString("abc".to_string())),
+            Box::new(CString("abc".to_string())),
+        );
+        let eq2 = EQ(Box::new(CTrue), Box::new(CFalse));
+        let eq3 = EQ(Box::new(CInt(2)), Box::new(CReal(2.0)));
+        let neq1 = NEQ(
+            Box::new(CString("abc".to_string())),
+            Box::new(CString("abd".to_string())),
+        );
+        let neq2 = NEQ(Box::new(CFalse), Box::new(CFalse));
+
+        assert_eq!(eval(eq1, &env), Ok(EnvValue::Exp(CTrue)));
+        assert_eq!(eval(eq2, &env), Ok(EnvValue::Exp(CFalse)));
+        assert_eq!(eval(eq3, &env), Ok(EnvValue::Exp(CTrue)));
+        assert_eq!(eval(neq1, &env), Ok(EnvValue::Exp(CTrue)));
+        assert_eq!(eval(neq2, &env), Ok(EnvValue::Exp(CFalse)));
+    }
+
+    #[test]
+    fn eval_equality_of_different_kinds() {
+        let mut env = Environment::new();
+        env.insert_variable("m".to_string(), EnvValue::Module("m".to_string()));
+        env.insert_variable("n".to_string(), EnvValue::Module("n".to_string()));
+
+        /* > [1] == 1 */
+        let eq1 = EQ(Box::new(CList(vec![CInt(1)])), Box::new(CInt(1)));
+        /* > m == 1 */
+        let eq2 = EQ(Box::new(Var("m".to_string())), Box::new(CInt(1)));
+        /* > m == m */
+        let eq3 = EQ(
+            Box::new(Var("m".to_string())),
+            Box::new(Var("m".to_string())),
+        );
+        /* > m != n */
+        let neq = NEQ(
+            Box::new(Var("m".to_string())),
+            Box::new(Var("n".to_string())),
+        );
+
+        assert_eq!(eval(eq1, &env), Ok(EnvValue::Exp(CFalse)));
+        assert_eq!(eval(eq2, &env), Ok(EnvValue::Exp(CFalse)));
+        assert_eq!(eval(eq3, &env), Ok(EnvValue::Exp(CTrue)));
+        assert_eq!(eval(neq, &env), Ok(EnvValue::Exp(CTrue)));
+    }
+
+    #[test]
+    fn eval_equality_of_functions() {
+        let mut env = Environment::new();
+        env.insert_variable("f".to_string(), EnvValue::Func(Box::default()));
+
+        let eq = EQ(
+            Box::new(Var("f".to_string())),
+            Box::new(Var("f".to_string())),
+        );
+
+        assert_eq!(
+            eval(eq, &env),
+            Err(Exception::new(
+                "TypeError",
+                String::from("(==) and (!=) are not defined for functions.")
+            ))
+        );
+    }
+
+    #[test]
+    fn eval_variable() {
+        let mut env = Environment::new();
+        env.insert_variable("x".to_string(), EnvValue::Exp(CInt(10)));
+        env.insert_variable("y".to_string(), EnvValue::Exp(CInt(20)));
+
+        let v1 = Var(String::from("x"));
+        let v2 = Var(String::from("y"));
+
+        assert_eq!(eval(v1, &env), Ok(EnvValue::Exp(CInt(10))));
+        assert_eq!(eval(v2, &env), Ok(EnvValue::Exp(CInt(20))));
+    }
+
+    #[test]
+    fn eval_expression_with_variables() {
+        let mut env = Environment::new();
+        env.insert_variable("a".to_string(), EnvValue::Exp(CInt(5)));
+        env.insert_variable("b".to_string(), EnvValue::Exp(CInt(3)));
+
+        let expr = Mul(
+            Box::new(Var(String::from("a"))),
+            Box::new(Add(Box::new(Var(String::from("b"))), Box::new(CInt(2)))),
+        );
+
+        assert_eq!(eval(expr, &env), Ok(EnvValue::Exp(CInt(25))));
+    }
+
+    #[test]
+    fn eval_nested_expressions() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let expr = Add(
+            Box::new(Mul(Box::new(CInt(2)), Box::new(CInt(3)))),
+            Box::new(Sub(Box::new(CInt(10)), Box::new(CInt(4)))),
+        );
+
+        assert_eq!(eval(expr, &env), Ok(EnvValue::Exp(CInt(12))));
+    }
+
+    #[test]
+    fn execute_assignment() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let assign_stmt = Assignment(String::from("x"), Box::new(CInt(42)), Some(TInteger));
+
+        match execute(assign_stmt, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("x".to_string()),
+                Some(&EnvValue::Exp(CInt(42)))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_summation() {
+        /*
+         * (a test case for the following program)
+         *
+         * > x: TInteger = 10
+         * > y: TInteger = 0
+         * > while x >= 0:
+         * >   y = y + x
+         * >   x = x - 1
+         *
+         * After executing this program, 'x' must be zero and
+         * 'y' must be 55.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let a1 = Assignment(String::from("x"), Box::new(CInt(10)), Some(TInteger));
+        let a2 = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
+        let a3 = Assignment(
+            String::from("y"),
+            Box::new(Add(
+                Box::new(Var(String::from("y"))),
+                Box::new(Var(String::from("x"))),
+            )),
+            None,
+        );
+        let a4 = Assignment(
+            String::from("x"),
+            Box::new(Sub(Box::new(Var(String::from("x"))), Box::new(CInt(1)))),
+            None,
+        );
+
+        let seq1 = Sequence(Box::new(a3), Box::new(a4));
+
+        let while_statement = While(
+            Box::new(GT(Box::new(Var(String::from("x"))), Box::new(CInt(0)))),
+            Box::new(seq1),
+        );
+
+        let seq2 = Sequence(Box::new(a2), Box::new(while_statement));
+        let program = Sequence(Box::new(a1), Box::new(seq2));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("y".to_string()),
+                    Some(&EnvValue::Exp(CInt(55)))
+                );
+                assert_eq!(
+                    new_env.search_frame("x".to_string()),
+                    Some(&EnvValue::Exp(CInt(0)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_summation_augmented_assignment() {
+        /*
+         * (the same program as 'eval_summation', using augmented assignments)
+         *
+         * > x: TInteger = 10
+         * > y: TInteger = 0
+         * > while x > 0:
+         * >   y += x
+         * >   x -= 1
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let a1 = Assignment(String::from("x"), Box::new(CInt(10)), Some(TInteger));
+        let a2 = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
+        let a3 = AddAssignment(String::from("y"), Box::new(Var(String::from("x"))));
+        let a4 = SubAssignment(String::from("x"), Box::new(CInt(1)));
+
+        let while_statement = While(
+            Box::new(GT(Box::new(Var(String::from("x"))), Box::new(CInt(0)))),
+            Box::new(Sequence(Box::new(a3), Box::new(a4))),
+        );
+
+        let seq = Sequence(Box::new(a2), Box::new(while_statement));
+        let program = Sequence(Box::new(a1), Box::new(seq));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("y".to_string()),
+                    Some(&EnvValue::Exp(CInt(55)))
+                );
+                assert_eq!(
+                    new_env.search_frame("x".to_string()),
+                    Some(&EnvValue::Exp(CInt(0)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_mul_and_div_assignment() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let program = Sequence(
+            Box::new(Assignment(
+                String::from("x"),
+                Box::new(CInt(6)),
+                Some(TInteger),
+            )),
+            Box::new(Sequence(
+                Box::new(MulAssignment(String::from("x"), Box::new(CInt(7)))),
+                Box::new(DivAssignment(String::from("x"), Box::new(CInt(2)))),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("x".to_string()),
+                Some(&EnvValue::Exp(CInt(21)))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+
+        let mut env: Environment<EnvValue> = Environment::new();
+        env.insert_variable("x".to_string(), EnvValue::Exp(CInt(1)));
+
+        assert!(execute(DivAssignment(String::from("x"), Box::new(CInt(0))), &env).is_err());
+    }
+
+    #[test]
+    fn eval_chained_assignment() {
+        /*
+         * > a = b = 2 * 3
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let program = ChainedAssignment(
+            vec![String::from("a"), String::from("b")],
+            Box::new(Mul(Box::new(CInt(2)), Box::new(CInt(3)))),
+            Some(TInteger),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("a".to_string()),
+                    Some(&EnvValue::Exp(CInt(6)))
+                );
+                assert_eq!(
+                    new_env.search_frame("b".to_string()),
+                    Some(&EnvValue::Exp(CInt(6)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_simple_if_then_else() {
+        /*
+         * Test for simple if-then-else statement
+         *
+         * > x: TInteger = 10
+         * > if x > 5:
+         * >   y: TInteger = 1
+         * > else:
+         * >   y: TInteger = 0
+         *
+         * After executing, 'y' should be 1.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let condition = GT(Box::new(Var(String::from("x"))), Box::new(CInt(5)));
+        let then_stmt = Assignment(String::from("y"), Box::new(CInt(1)), Some(TInteger));
+        let else_stmt = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
+
+        let if_statement = IfThenElse(
+            Box::new(condition),
+            Box::new(then_stmt),
+            Some(Box::new(else_stmt)),
+        );
+
+        let setup_stmt = Assignment(String::from("x"), Box::new(CInt(10)), Some(TInteger));
+        let program = Sequence(Box::new(setup_stmt), Box::new(if_statement));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("y".to_string()),
+                Some(&EnvValue::Exp(CInt(1)))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_if_then_optional_else() {
+        /*
+         * Test for simple if-then-else statement
+         *
+         * > x: TInteger = 1
+         * > y: TInteger = 0
+         * > if x == y:
+         * >   y = 1
+         * > else:
+         * >    y = 2
+         * >    if x < 0:
+         * >        y = 5
+         *
+         * After executing, 'y' should be 2.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let second_condition = LT(Box::new(Var(String::from("x"))), Box::new(CInt(0)));
+        let second_then_stmt = Assignment(String::from("y"), Box::new(CInt(5)), None);
+
+        let second_if_stmt =
+            IfThenElse(Box::new(second_condition), Box::new(second_then_stmt), None);
+
+        let else_setup_stmt = Assignment(String::from("y"), Box::new(CInt(2)), None);
+        let else_stmt = Sequence(Box::new(else_setup_stmt), Box::new(second_if_stmt));
+
+        let first_condition = EQ(
+            Box::new(Var(String::from("x"))),
+            Box::new(Var(String::from("y"))),
+        );
+        let first_then_stmt = Assignment(String::from("y"), Box::new(CInt(1)), None);
+
+        let first_if_stmt = IfThenElse(
+            Box::new(first_condition),
+            Box::new(first_then_stmt),
+            Some(Box::new(else_stmt)),
+        );
+
+        let second_assignment = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
+        let setup_stmt = Sequence(Box::new(second_assignment), Box::new(first_if_stmt));
+
+        let first_assignment = Assignment(String::from("x"), Box::new(CInt(1)), Some(TInteger));
+        let program = Sequence(Box::new(first_assignment), Box::new(setup_stmt));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("y".to_string()),
+                Some(&EnvValue::Exp(CInt(2)))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    // #[test]
+    // fn eval_while_loop_decrement() {
+    //     /*
+    //      * Test for while loop that decrements a variable
+    //      *
+    //      * > x = 3
+    //      * > y = 10
+    //      * > while x:
+    //      * >   y = y - 1
+    //      * >   x = x - 1
+    //      *
+    //      * After executing, 'y' should be 7 and 'x' should be 0.
+    //      */
+    //     let env = HashMap::new();
+
+    //     let a1 = Assignment(String::from("x"), Box::new(CInt(3))); -> corrigido parenteses extras.
+    //     let a2 = Assignment(String::from("y")), Box:new(CInt(10)));
+    //     let a3 = Assignment(
+    //         String::from("y")),
+    //         Box::new(Sub(
+    //             Box::new(Var(String::from("y"))),
+    //             Box::new(CInt(1)),
+    //         )),
+    //     );
+    //     let a4 = Assignment(
+    //         String::from("x")),
+    //         Box::new(Sub(
+    //             Box::new(Var(String::from("x"))),
+    //             Box::new(CInt(1)),
+    //         )),
+    //     );
+
+    //     let seq1 = Sequence(Box::new(a3), Box::new(a4));
+    //     let while_statement =
+    //         While(Box::new(Var(String::from("x"))), Box::new(seq1));
+    //     let program = Sequence(
+    //         Box::new(a1),
+    //         Box::new(Sequence(Box::new(a2), Box::new(while_statement))),
+    //     );
+
+    //     match execute(&program, env) {
+    //         Ok(new_env) => {
+    //             assert_eq!(new_env.get("y"), Some(&7));
+    //             assert_eq!(new_env.get("x"), Some(&0));
+    //         }
+    //         Err(s) => assert!(false, "{}", s),
+    //     }
+    // }
+
+    // #[test]
+    // fn eval_nested_if_statements() {
+    //     /*
+    //      * Test for nested if-then-else statements
+    //      *
+    //      * > x = 10
+    //      * > if x > 5:
+    //      * >   if x > 8:
+    //      * >     y = 1
+    //      * >   else:
+    //      * >     y = 2
+    //      * > else:
+    //      * >   y = 0
+    //      *
+    //      * After executing, 'y' should be 1.
+    //      */
+    //     let env = HashMap::new();
+
+    //     let inner_then_stmt =
+    //         Assignment(String::from("y")), Box:new(CInt(1)));
+    //     let inner_else_stmt =
+    //         Assignment(String::from("y")), Box:new(CInt(2)));
+    //     let inner_if_statement = Statement::IfThenElse(
+    //         Box::new(Var(String::from("x"))),
+    //         Box::new(inner_then_stmt),
+    //         Box::new(inner_else_stmt),
+    //     );
+
+    //     let outer_else_stmt =
+    //         Assignment(String::from("y")), Box:new(CInt(0)));
+    //     let outer_if_statement = Statement::IfThenElse(
+    //         Box::new(Var(String::from("x"))),
+    //         Box::new(inner_if_statement),
+    //         Box::new(outer_else_stmt),
+    //     );
+
+    //     let setup_stmt =
+    //         Assignment(String::from("x")), Box:new(CInt(10)));
+    //     let program = Sequence(Box::new(setup_stmt), Box::new(outer_if_statement));
+
+    //     match execute(&program, env) {
+    //         Ok(new_env) => assert_eq!(new_env.get("y"), Some(&1)),
+    //         Err(s) => assert!(false, "{}", s),
+    //     }
+    // }
+
+    #[test]
+    fn eval_complex_sequence() {
+        /*
+         * Sequence with multiple assignments and expressions
+         *
+         * > x: TInteger = 5
+         * > y: TInteger = 0
+         * > z: TInteger = 2 * x + 3
+         *
+         * After executing, 'x' should be 5, 'y' should be 0, and 'z' should be 13.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let a1 = Assignment(String::from("x"), Box::new(CInt(5)), Some(TInteger));
+        let a2 = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
+        let a3 = Assignment(
+            String::from("z"),
+            Box::new(Add(
+                Box::new(Mul(Box::new(CInt(2)), Box::new(Var(String::from("x"))))),
+                Box::new(CInt(3)),
+            )),
+            Some(TInteger),
+        );
+
+        let program = Sequence(Box::new(a1), Box::new(Sequence(Box::new(a2), Box::new(a3))));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("x".to_string()),
+                    Some(&EnvValue::Exp(CInt(5)))
+                );
+                assert_eq!(
+                    new_env.search_frame("y".to_string()),
+                    Some(&EnvValue::Exp(CInt(0)))
+                );
+                assert_eq!(
+                    new_env.search_frame("z".to_string()),
+                    Some(&EnvValue::Exp(CInt(13)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_try_except_raise() {
+        /*
+         * > try:
+         * >   raise ValueError("bad value")
+         * > except ValueError as e:
+         * >   msg: TString = e
+         * > finally:
+         * >   done: TBool = True
+         *
+         * After executing, 'msg' should be "bad value" and 'done' should be True.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let program = TryExcept(
+            Box::new(Raise(
+                "ValueError".to_string(),
+                Box::new(CString("bad value".to_string())),
+            )),
+            vec![
+                (
+                    "TypeError".to_string(),
+                    None,
+                    Assignment(
+                        "msg".to_string(),
+                        Box::new(CString("wrong".to_string())),
+                        None,
+                    ),
+                ),
+                (
+                    "ValueError".to_string(),
+                    Some("e".to_string()),
+                    Assignment(
+                        "msg".to_string(),
+                        Box::new(Var("e".to_string())),
+                        Some(TString),
+                    ),
+                ),
+            ],
+            Some(Box::new(Assignment(
+                "done".to_string(),
+                Box::new(CTrue),
+                Some(TBool),
+            ))),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("msg".to_string()),
+                    Some(&EnvValue::Exp(CString("bad value".to_string())))
+                );
+                assert_eq!(
+                    new_env.search_frame("done".to_string()),
+                    Some(&EnvValue::Exp(CTrue))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_uncaught_exception() {
+        /*
+         * > x: TInteger = 0
+         * > while True:
+         * >   try:
+         * >     raise KeyError("missing")
+         * >   except ValueError:
+         * >     x = 1
+         * >   finally:
+         * >     x = 2
+         *
+         * The 'KeyError' is not handled and must escape the loop.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let try_stmt = TryExcept(
+            Box::new(Raise(
+                "KeyError".to_string(),
+                Box::new(CString("missing".to_string())),
+            )),
+            vec![(
+                "ValueError".to_string(),
+                None,
+                Assignment("x".to_string(), Box::new(CInt(1)), None),
+            )],
+            Some(Box::new(Assignment(
+                "x".to_string(),
+                Box::new(CInt(2)),
+                None,
+            ))),
+        );
+        let program = Sequence(
+            Box::new(Assignment(
+                "x".to_string(),
+                Box::new(CInt(0)),
+                Some(TInteger),
+            )),
+            Box::new(While(Box::new(CTrue), Box::new(try_stmt))),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Raise(exception)) => assert_eq!(
+                exception,
+                Exception::new("KeyError", String::from("missing"))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_exception_from_function_call() {
+        /*
+         * > def check(n: TInteger) -> TInteger:
+         * >   if n < 0:
+         * >     raise ValueError("negative")
+         * >   return n
+         * >
+         * > try:
+         * >   r: TInteger = check(-1)
+         * > except Exception as e:
+         * >   r: TInteger = 0
+         *
+         * After executing, 'r' should be 0 and the scope restored to '__main__'.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let func = FuncDef(Function {
+            name: "check".to_string(),
+            kind: Some(TInteger),
+            params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Sequence(
+                Box::new(IfThenElse(
+                    Box::new(LT(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
+                    Box::new(Raise(
+                        "ValueError".to_string(),
+                        Box::new(CString("negative".to_string())),
+                    )),
+                    None,
+                )),
+                Box::new(Return(Box::new(Var("n".to_string())))),
+            ))),
+        });
+        let try_stmt = TryExcept(
+            Box::new(Assignment(
+                "r".to_string(),
+                Box::new(FuncCall("check".to_string(), vec![CInt(-1)])),
+                Some(TInteger),
+            )),
+            vec![(
+                "Exception".to_string(),
+                Some("e".to_string()),
+                Assignment("r".to_string(), Box::new(CInt(0)), Some(TInteger)),
+            )],
+            None,
+        );
+        let program = Sequence(Box::new(func), Box::new(try_stmt));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.scope_name(), "__main__");
+                assert_eq!(
+                    new_env.search_frame("r".to_string()),
+                    Some(&EnvValue::Exp(CInt(0)))
+                );
+                assert_eq!(
+                    new_env.search_frame("e".to_string()),
+                    Some(&EnvValue::Exp(CString("negative".to_string())))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_catch_builtin_errors() {
+        /*
+         * > try:
+         * >   x: TInteger = 1 / 0
+         * > except ZeroDivisionError:
+         * >   x: TInteger = -1
+         * > try:
+         * >   s: TString = read_file("does/not/exist.txt")
+         * > except FileNotFoundError:
+         * >   s: TString = ""
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let try_div = TryExcept(
+            Box::new(Assignment(
+                "x".to_string(),
+                Box::new(Div(Box::new(CInt(1)), Box::new(CInt(0)))),
+                Some(TInteger),
+            )),
+            vec![(
+                "ZeroDivisionError".to_string(),
+                None,
+                Assignment("x".to_string(), Box::new(CInt(-1)), Some(TInteger)),
+            )],
+            None,
+        );
+        let try_read = TryExcept(
+            Box::new(Assignment(
+                "s".to_string(),
+                Box::new(ReadFile(Box::new(CString(
+                    "does/not/exist.txt".to_string(),
+                )))),
+                Some(TString),
+            )),
+            vec![(
+                "FileNotFoundError".to_string(),
+                None,
+                Assignment(
+                    "s".to_string(),
+                    Box::new(CString("".to_string())),
+                    Some(TString),
+                ),
+            )],
+            None,
+        );
+        let program = Sequence(Box::new(try_div), Box::new(try_read));
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("x".to_string()),
+                    Some(&EnvValue::Exp(CInt(-1)))
+                );
+                assert_eq!(
+                    new_env.search_frame("s".to_string()),
+                    Some(&EnvValue::Exp(CString("".to_string())))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_assert() {
+        let env: Environment<EnvValue> = Environment::new();
+
+        let assert1 = Assert(
+            Box::new(EQ(Box::new(CInt(1)), Box::new(CInt(1)))),
+            Box::new(CString("1 == 1".to_string())),
+        );
+        let assert2 = Assert(
+            Box::new(EQ(Box::new(CInt(1)), Box::new(CInt(2)))),
+            Box::new(CString("1 == 2".to_string())),
+        );
+
+        match execute(assert1, &env) {
+            Ok(ControlFlow::Continue(_)) => (),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+        match execute(assert2, &env) {
+            Ok(ControlFlow::Raise(exception)) => assert_eq!(
+                exception,
+                Exception::new("AssertionError", String::from("1 == 2"))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_finally_return_overrides() {
+        /*
+         * > def f() -> TInteger:
+         * >   try:
+         * >     return 1
+         * >   finally:
+         * >     return 2
+         * >
+         * > r: TInteger = f()
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let func = FuncDef(Function {
+            name: "f".to_string(),
+            kind: Some(TInteger),
+            params: None,
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(TryExcept(
+                Box::new(Return(Box::new(CInt(1)))),
+                vec![],
+                Some(Box::new(Return(Box::new(CInt(2))))),
+            ))),
+        });
+        let program = Sequence(
+            Box::new(func),
+            Box::new(Assignment(
+                "r".to_string(),
+                Box::new(FuncCall("f".to_string(), vec![])),
+                Some(TInteger),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("r".to_string()),
+                Some(&EnvValue::Exp(CInt(2)))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_record_construction_and_access() {
+        /*
+         * > record Point:
+         * >   x: TInteger
+         * >   y: TInteger
+         * >
+         * > p: Point = Point(y = 2, x = 1)
+         * > p.x = p.x + p.y
+         * > s: TInteger = p.x
+         *
+         * After executing, 'p' should be Point(x=3, y=2) and 's' should be 3.
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let record_def = RecordDef(
+            "Point".to_string(),
+            vec![("x".to_string(), TInteger), ("y".to_string(), TInteger)],
+        );
+        let a1 = Assignment(
+            "p".to_string(),
+            Box::new(Record(
+                "Point".to_string(),
+                vec![("y".to_string(), CInt(2)), ("x".to_string(), CInt(1))],
+            )),
+            Some(TRecord("Point".to_string())),
+        );
+        let a2 = FieldAssignment(
+            "p".to_string(),
+            "x".to_string(),
+            Box::new(Add(
+                Box::new(FieldAccess(Box::new(Var("p".to_string())), "x".to_string())),
+                Box::new(FieldAccess(Box::new(Var("p".to_string())), "y".to_string())),
+            )),
+        );
+        let a3 = Assignment(
+            "s".to_string(),
+            Box::new(FieldAccess(Box::new(Var("p".to_string())), "x".to_string())),
+            Some(TInteger),
+        );
+        let program = Sequence(
+            Box::new(record_def),
+            Box::new(Sequence(
+                Box::new(a1),
+                Box::new(Sequence(Box::new(a2), Box::new(a3))),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                let point = Record(
+                    "Point".to_string(),
+                    vec![("x".to_string(), CInt(3)), ("y".to_string(), CInt(2))],
+                );
+
+                assert_eq!(
+                    new_env.search_frame("p".to_string()),
+                    Some(&EnvValue::Exp(point.clone()))
+                );
+                assert_eq!(
+                    new_env.search_frame("s".to_string()),
+                    Some(&EnvValue::Exp(CInt(3)))
+                );
+                assert_eq!(
+                    value_to_string(&point),
+                    Some(String::from("Point(x=3, y=2)"))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_record_errors() {
+        let mut env: Environment<EnvValue> = Environment::new();
+        env.insert_definition(
+            "Person".to_string(),
+            TypeDefinition::Record(vec![
+                ("name".to_string(), TString),
+                ("age".to_string(), TInteger),
+            ]),
+        );
+
+        let missing = Record(
+            "Person".to_string(),
+            vec![("name".to_string(), CString("Ana".to_string()))],
+        );
+        let unknown = Record(
+            "Person".to_string(),
+            vec![
+                ("name".to_string(), CString("Ana".to_string())),
+                ("age".to_string(), CInt(30)),
+                ("email".to_string(), CString("ana@mail".to_string())),
+            ],
+        );
+        let person = Record(
+            "Person".to_string(),
+            vec![
+                ("name".to_string(), CString("Ana".to_string())),
+                ("age".to_string(), CInt(30)),
+            ],
+        );
+
+        assert_eq!(
+            eval(missing, &env),
+            Err(Exception::new(
+                "TypeError",
+                String::from("record 'Person' is missing field 'age'.")
+            ))
+        );
+        assert_eq!(
+            eval(unknown, &env),
+            Err(Exception::new(
+                "AttributeError",
+                String::from("record 'Person' has no field 'email'.")
+            ))
+        );
+        assert_eq!(
+            eval(EQ(Box::new(person.clone()), Box::new(person.clone())), &env),
+            Ok(EnvValue::Exp(CTrue))
+        );
+        assert_eq!(
+            value_to_string(&person),
+            Some(String::from("Person(name=\"Ana\", age=30)"))
+        );
+    }
+
+    fn shape_def() -> Statement {
+        AdtDef(
+            "Shape".to_string(),
+            vec![
+                ("Circle".to_string(), vec![TReal]),
+                ("Rect".to_string(), vec![TReal, TReal]),
+            ],
+        )
+    }
+
+    #[test]
+    fn eval_match_expression() {
+        /*
+         * > type Shape = Circle(TReal) | Rect(TReal, TReal)
+         * >
+         * > def area(s: Shape) -> TReal:
+         * >   return match s:
+         * >     case Circle(r) => 3.0 * r * r
+         * >     case Rect(w, h) => w * h
+         * >
+         * > a1: TReal = area(Circle(1.0))
+         * > a2: TReal = area(Rect(2.0, 3.5))
+         */
+
+        let env: Environment<EnvValue> = Environment::new();
+
+        let area = FuncDef(Function {
+            name: "area".to_string(),
+            kind: Some(TReal),
+            params: Some(vec![("s".to_string(), TAdt("Shape".to_string()))]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(MatchExp(
+                Box::new(Var("s".to_string())),
+                vec![
+                    (
+                        Pattern::Constructor(
+                            "Circle".to_string(),
+                            vec![Pattern::Binding("r".to_string())],
+                        ),
+                        Mul(
+                            Box::new(CReal(3.0)),
+                            Box::new(Mul(
+                                Box::new(Var("r".to_string())),
+                                Box::new(Var("r".to_string())),
+                            )),
+                        ),
+                    ),
+                    (
+                        Pattern::Constructor(
+                            "Rect".to_string(),
+                            vec![
+                                Pattern::Binding("w".to_string()),
+                                Pattern::Binding("h".to_string()),
+                            ],
+                        ),
+                        Mul(
+                            Box::new(Var("w".to_string())),
+                            Box::new(Var("h".to_string())),
+                        ),
+                    ),
+                ],
+            ))))),
+        });
+        let a1 = Assignment(
+            "a1".to_string(),
+            Box::new(FuncCall(
+                "area".to_string(),
+                vec![Constructor("Circle".to_string(), vec![CReal(1.0)])],
+            )),
+            Some(TReal),
+        );
+        let a2 = Assignment(
+            "a2".to_string(),
+            Box::new(FuncCall(
+                "area".to_string(),
+                vec![Constructor(
+                    "Rect".to_string(),
+                    vec![CReal(2.0), CReal(3.5)],
+                )],
+            )),
+            Some(TReal),
+        );
+        let program = Sequence(
+            Box::new(shape_def()),
+            Box::new(Sequence(
+                Box::new(area),
+                Box::new(Sequence(Box::new(a1), Box::new(a2))),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("a1".to_string()),
+                    Some(&EnvValue::Exp(CReal(3.0)))
+                );
+                assert_eq!(
+                    new_env.search_frame("a2".to_string()),
+                    Some(&EnvValue::Exp(CReal(7.0)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_match_statement() {
+        /*
+         * > match Rect(2.0, 2.0):
+         * >   case Rect(w, 2.0):
+         * >     kind: TString = "wide"
+         * >   case _:
+         * >     kind: TString = "other"
+         */
+
+        let mut env: Environment<EnvValue> = Environment::new();
+
+        if let Ok(ControlFlow::Continue(new_env)) = execute(shape_def(), &env) {
+            env = new_env;
+        }
+
+        let program = Match(
+            Box::new(Constructor(
+                "Rect".to_string(),
+                vec![CReal(2.0), CReal(2.0)],
+            )),
+            vec![
+                (
+                    Pattern::Constructor(
+                        "Rect".to_string(),
+                        vec![
+                            Pattern::Binding("w".to_string()),
+                            Pattern::Literal(CReal(2.0)),
+                        ],
+                    ),
+                    Assignment(
+                        "kind".to_string(),
+                        Box::new(CString("wide".to_string())),
+                        Some(TString),
+                    ),
+                ),
+                (
+                    Pattern::Wildcard,
+                    Assignment(
+                        "kind".to_string(),
+                        Box::new(CString("other".to_string())),
+                        Some(TString),
+                    ),
+                ),
+            ],
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("kind".to_string()),
+                    Some(&EnvValue::Exp(CString("wide".to_string())))
+                );
+                assert_eq!(
+                    new_env.search_frame("w".to_string()),
+                    Some(&EnvValue::Exp(CReal(2.0)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
 
-        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CInt(3))));
+        let no_match = MatchExp(
+            Box::new(Constructor("Circle".to_string(), vec![CReal(1.5)])),
+            vec![(Pattern::Literal(CInt(1)), CInt(1))],
+        );
+
+        assert_eq!(
+            eval(no_match, &env),
+            Err(Exception::new(
+                "MatchError",
+                String::from("no pattern matches 'Circle(1.5)'.")
+            ))
+        );
     }
 
     #[test]
-    fn eval_div_expression3() {
+    fn eval_match_wrapped_values() {
         let env: Environment<EnvValue> = Environment::new();
 
-        let c3 = CInt(3);
-        let c21 = CInt(21);
-        let div1 = Div(Box::new(c21), Box::new(c3));
+        let unwrap = |value: Expression| {
+            MatchExp(
+                Box::new(value),
+                vec![
+                    (
+                        Pattern::Constructor(
+                            "Just".to_string(),
+                            vec![Pattern::Binding("n".to_string())],
+                        ),
+                        Var("n".to_string()),
+                    ),
+                    (Pattern::Constructor("Nothing".to_string(), vec![]), CInt(0)),
+                    (
+                        Pattern::Constructor(
+                            "Err".to_string(),
+                            vec![Pattern::Binding("e".to_string())],
+                        ),
+                        Var("e".to_string()),
+                    ),
+                ],
+            )
+        };
+
+        assert_eq!(
+            eval(unwrap(CJust(Box::new(CInt(3)))), &env),
+            Ok(EnvValue::Exp(CInt(3)))
+        );
+        assert_eq!(eval(unwrap(CNothing), &env), Ok(EnvValue::Exp(CInt(0))));
+        assert_eq!(
+            eval(unwrap(CErr(Box::new(CInt(7)))), &env),
+            Ok(EnvValue::Exp(CInt(7)))
+        );
+        assert_eq!(
+            eval(unwrap(COk(Box::new(CInt(1)))), &env),
+            Err(Exception::new(
+                "MatchError",
+                String::from("no pattern matches 'Ok(1)'.")
+            ))
+        );
+    }
 
-        assert_eq!(eval(div1, &env), Ok(EnvValue::Exp(CInt(7))));
+    #[test]
+    fn eval_numeric_widening() {
+        let env = Environment::new();
+
+        /*
+         * > def half(x: real) -> real:
+         * >     return x / 2
+         * >
+         * > r: real = 1
+         * > r = 2
+         * > h = half(3)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "half".to_string(),
+                kind: Some(TReal),
+                params: Some(vec![("x".to_string(), TReal)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Div(
+                    Box::new(Var("x".to_string())),
+                    Box::new(CInt(2)),
+                ))))),
+            })),
+            Box::new(Sequence(
+                Box::new(Assignment("r".to_string(), Box::new(CInt(1)), Some(TReal))),
+                Box::new(Sequence(
+                    Box::new(Assignment("r".to_string(), Box::new(CInt(2)), None)),
+                    Box::new(Assignment(
+                        "h".to_string(),
+                        Box::new(FuncCall("half".to_string(), vec![CInt(3)])),
+                        None,
+                    )),
+                )),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("r".to_string()),
+                    Some(&EnvValue::Exp(CReal(2.0)))
+                );
+                assert_eq!(
+                    new_env.search_frame("h".to_string()),
+                    Some(&EnvValue::Exp(CReal(1.5)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
     }
 
     #[test]
-    fn eval_div_expression4() {
-        let env: Environment<EnvValue> = Environment::new();
+    fn eval_any_runtime_checks() {
+        let env = Environment::new();
 
-        let c10 = CInt(10);
-        let c3 = CReal(3.0);
-        let div1 = Div(Box::new(c10), Box::new(c3));
-        let res = eval(div1, &env);
+        /*
+         * > def twice(n: int) -> int:
+         * >     return n * 2
+         * >
+         * > x: Any = 21
+         * > y = twice(x)
+         * > x = "21"
+         * > z = twice(x)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "twice".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Mul(
+                    Box::new(Var("n".to_string())),
+                    Box::new(CInt(2)),
+                ))))),
+            })),
+            Box::new(Sequence(
+                Box::new(Assignment("x".to_string(), Box::new(CInt(21)), Some(TAny))),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "y".to_string(),
+                        Box::new(FuncCall("twice".to_string(), vec![Var("x".to_string())])),
+                        None,
+                    )),
+                    Box::new(Sequence(
+                        Box::new(Assignment(
+                            "x".to_string(),
+                            Box::new(CString("21".to_string())),
+                            None,
+                        )),
+                        Box::new(Assignment(
+                            "z".to_string(),
+                            Box::new(FuncCall("twice".to_string(), vec![Var("x".to_string())])),
+                            None,
+                        )),
+                    )),
+                )),
+            )),
+        );
 
-        match res {
-            Ok(EnvValue::Exp(Expression::CReal(v))) => {
-                assert!(relative_eq!(v, 3.3333333333333335, epsilon = f64::EPSILON))
+        match execute(program, &env) {
+            Ok(_) => assert!(false),
+            Err(e) => assert_eq!(
+                e,
+                Exception::new(
+                    "TypeError",
+                    String::from(
+                        "'twice()' argument 'n' expected 'TInteger', found a value of type 'str'."
+                    )
+                )
+            ),
+        }
+    }
+
+    #[test]
+    fn eval_undefined_names() {
+        let env = Environment::new();
+
+        /* > y = x */
+        match execute(
+            Assignment("y".to_string(), Box::new(Var("x".to_string())), None),
+            &env,
+        ) {
+            Ok(_) => assert!(false),
+            Err(e) => assert_eq!(
+                e,
+                Exception::new("NameError", String::from("'x' is not defined."))
+            ),
+        }
+
+        /*
+         * > x = 1
+         * > y = x()
+         */
+        let program = Sequence(
+            Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+            Box::new(Assignment(
+                "y".to_string(),
+                Box::new(FuncCall("x".to_string(), vec![])),
+                None,
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(_) => assert!(false),
+            Err(e) => assert_eq!(
+                e,
+                Exception::new("TypeError", String::from("'int' object is not callable."))
+            ),
+        }
+    }
+
+    #[test]
+    fn eval_missing_return() {
+        let env = Environment::new();
+
+        /*
+         * > def sign(n: int) -> int:
+         * >     if n > 0:
+         * >         return 1
+         * >
+         * > x = sign(0)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "sign".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(IfThenElse(
+                    Box::new(GT(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
+                    Box::new(Return(Box::new(CInt(1)))),
+                    None,
+                ))),
+            })),
+            Box::new(Assignment(
+                "x".to_string(),
+                Box::new(FuncCall("sign".to_string(), vec![CInt(0)])),
+                None,
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(_) => assert!(false),
+            Err(e) => assert_eq!(
+                e,
+                Exception::new(
+                    "TypeError",
+                    String::from("'sign()' did not return a value.")
+                )
+            ),
+        }
+    }
+
+    #[test]
+    fn eval_procedure_calls() {
+        let env = Environment::new();
+
+        /*
+         * > def log(n: int):
+         * >     print(n)
+         * >
+         * > log(1)
+         * > x = log(2)
+         * > b = x == None
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "log".to_string(),
+                kind: None,
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Print(Box::new(Var("n".to_string()))))),
+            })),
+            Box::new(Sequence(
+                Box::new(ExprStmt(Box::new(FuncCall(
+                    "log".to_string(),
+                    vec![CInt(1)],
+                )))),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "x".to_string(),
+                        Box::new(FuncCall("log".to_string(), vec![CInt(2)])),
+                        None,
+                    )),
+                    Box::new(Assignment(
+                        "b".to_string(),
+                        Box::new(EQ(Box::new(Var("x".to_string())), Box::new(CNone))),
+                        None,
+                    )),
+                )),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("x".to_string()),
+                    Some(&EnvValue::Exp(CNone))
+                );
+                assert_eq!(
+                    new_env.search_frame("b".to_string()),
+                    Some(&EnvValue::Exp(CTrue))
+                );
             }
-            Err(msg) => assert!(false, "{}", msg),
-            _ => assert!(false, "Not expected."),
+            Ok(_) => assert!(false),
+            Err(e) => assert!(false, "{}", e),
         }
     }
 
     #[test]
-    fn eval_variable() {
+    fn eval_default_keyword_and_variadic_arguments() {
+        let env = Environment::new();
+
+        /*
+         * > base = 10
+         * >
+         * > def offset(x: int, by: int = base) -> int:
+         * >     return x + by
+         * >
+         * > def tail(first: int, *rest: int) -> list[int]:
+         * >     return rest
+         * >
+         * > base = 0
+         * > a = offset(1)
+         * > b = offset(1, by=2)
+         * > c = tail(1, 2, 3)
+         * > d = tail(first=1)
+         */
+        let program = Sequence(
+            Box::new(Assignment("base".to_string(), Box::new(CInt(10)), None)),
+            Box::new(Sequence(
+                Box::new(FuncDef(Function {
+                    name: "offset".to_string(),
+                    kind: Some(TInteger),
+                    params: Some(vec![
+                        ("x".to_string(), TInteger),
+                        ("by".to_string(), TInteger),
+                    ]),
+                    defaults: vec![("by".to_string(), Var("base".to_string()))],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(Add(
+                        Box::new(Var("x".to_string())),
+                        Box::new(Var("by".to_string())),
+                    ))))),
+                })),
+                Box::new(Sequence(
+                    Box::new(FuncDef(Function {
+                        name: "tail".to_string(),
+                        kind: Some(TList(Box::new(TInteger))),
+                        params: Some(vec![("first".to_string(), TInteger)]),
+                        defaults: vec![],
+                        variadic: Some(("rest".to_string(), TInteger)),
+                        module: None,
+                        body: Some(Box::new(Return(Box::new(Var("rest".to_string()))))),
+                    })),
+                    Box::new(Sequence(
+                        Box::new(Assignment("base".to_string(), Box::new(CInt(0)), None)),
+                        Box::new(Sequence(
+                            Box::new(Assignment(
+                                "a".to_string(),
+                                Box::new(FuncCall("offset".to_string(), vec![CInt(1)])),
+                                None,
+                            )),
+                            Box::new(Sequence(
+                                Box::new(Assignment(
+                                    "b".to_string(),
+                                    Box::new(FuncCall(
+                                        "offset".to_string(),
+                                        vec![
+                                            CInt(1),
+                                            KeywordArg("by".to_string(), Box::new(CInt(2))),
+                                        ],
+                                    )),
+                                    None,
+                                )),
+                                Box::new(Sequence(
+                                    Box::new(Assignment(
+                                        "c".to_string(),
+                                        Box::new(FuncCall(
+                                            "tail".to_string(),
+                                            vec![CInt(1), CInt(2), CInt(3)],
+                                        )),
+                                        None,
+                                    )),
+                                    Box::new(Assignment(
+                                        "d".to_string(),
+                                        Box::new(FuncCall(
+                                            "tail".to_string(),
+                                            vec![KeywordArg(
+                                                "first".to_string(),
+                                                Box::new(CInt(1)),
+                                            )],
+                                        )),
+                                        None,
+                                    )),
+                                )),
+                            )),
+                        )),
+                    )),
+                )),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                let value = |name: &str| new_env.search_frame(name.to_string()).cloned();
+
+                assert_eq!(value("a"), Some(EnvValue::Exp(CInt(11))));
+                assert_eq!(value("b"), Some(EnvValue::Exp(CInt(3))));
+                assert_eq!(
+                    value("c"),
+                    Some(EnvValue::Exp(CList(vec![CInt(2), CInt(3)])))
+                );
+                assert_eq!(value("d"), Some(EnvValue::Exp(CList(vec![]))));
+
+                /* > e = offset(1, 2, 3) */
+                match execute(
+                    Assignment(
+                        "e".to_string(),
+                        Box::new(FuncCall(
+                            "offset".to_string(),
+                            vec![CInt(1), CInt(2), CInt(3)],
+                        )),
+                        None,
+                    ),
+                    &new_env,
+                ) {
+                    Ok(_) => assert!(false),
+                    Err(e) => assert_eq!(
+                        e,
+                        Exception::new(
+                            "TypeError",
+                            String::from("'offset()' expected at most 2 arguments, found 3.")
+                        )
+                    ),
+                }
+            }
+            Ok(_) => assert!(false),
+            Err(e) => assert!(false, "{}", e),
+        }
+    }
+
+    fn geometry_module() -> Statement {
+        /*
+         * > _scale = 2
+         * > origin = 0
+         * >
+         * > def _double(x: int) -> int:
+         * >     return x * _scale
+         * >
+         * > def area(w: int, h: int = 1) -> int:
+         * >     return _double(w * h)
+         */
+        Sequence(
+            Box::new(Assignment("_scale".to_string(), Box::new(CInt(2)), None)),
+            Box::new(Sequence(
+                Box::new(Assignment("origin".to_string(), Box::new(CInt(0)), None)),
+                Box::new(Sequence(
+                    Box::new(FuncDef(Function {
+                        name: "_double".to_string(),
+                        kind: Some(TInteger),
+                        params: Some(vec![("x".to_string(), TInteger)]),
+                        defaults: vec![],
+                        variadic: None,
+                        module: None,
+                        body: Some(Box::new(Return(Box::new(Mul(
+                            Box::new(Var("x".to_string())),
+                            Box::new(Var("_scale".to_string())),
+                        ))))),
+                    })),
+                    Box::new(FuncDef(Function {
+                        name: "area".to_string(),
+                        kind: Some(TInteger),
+                        params: Some(vec![
+                            ("w".to_string(), TInteger),
+                            ("h".to_string(), TInteger),
+                        ]),
+                        defaults: vec![("h".to_string(), CInt(1))],
+                        variadic: None,
+                        module: None,
+                        body: Some(Box::new(Return(Box::new(FuncCall(
+                            "_double".to_string(),
+                            vec![Mul(
+                                Box::new(Var("w".to_string())),
+                                Box::new(Var("h".to_string())),
+                            )],
+                        ))))),
+                    })),
+                )),
+            )),
+        )
+    }
+
+    /* counts how many times modules are loaded, to show each one executes once */
+    struct CountingLoader {
+        modules: MemoryLoader,
+        loads: Rc<Cell<usize>>,
+    }
+
+    impl ModuleLoader for CountingLoader {
+        fn load(&self, name: &Name, sandbox: &Sandbox) -> Result<Option<Statement>, String> {
+            self.loads.set(self.loads.get() + 1);
+            self.modules.load(name, sandbox)
+        }
+    }
+
+    fn modules_env(modules: Vec<(&str, Statement)>) -> (Environment<EnvValue>, Rc<Cell<usize>>) {
+        let mut loader = CountingLoader {
+            modules: MemoryLoader::new(),
+            loads: Rc::new(Cell::new(0)),
+        };
+
+        for (name, program) in modules {
+            loader.modules.insert(name, program);
+        }
+
+        let loads = loader.loads.clone();
         let mut env = Environment::new();
-        env.insert_variable("x".to_string(), EnvValue::Exp(CInt(10)));
-        env.insert_variable("y".to_string(), EnvValue::Exp(CInt(20)));
+        env.modules = Modules::new(Rc::new(loader));
+        (env, loads)
+    }
 
-        let v1 = Var(String::from("x"));
-        let v2 = Var(String::from("y"));
+    #[test]
+    fn eval_imports() {
+        /*
+         * > # shapes
+         * > from geometry import area
+         * > unit = area(1)
+         */
+        let shapes = Sequence(
+            Box::new(FromImport("geometry".to_string(), vec!["area".to_string()])),
+            Box::new(Assignment(
+                "unit".to_string(),
+                Box::new(FuncCall("area".to_string(), vec![CInt(1)])),
+                None,
+            )),
+        );
+        let (env, loads) = modules_env(vec![("geometry", geometry_module()), ("shapes", shapes)]);
 
-        assert_eq!(eval(v1, &env), Ok(EnvValue::Exp(CInt(10))));
-        assert_eq!(eval(v2, &env), Ok(EnvValue::Exp(CInt(20))));
+        /*
+         * > import geometry
+         * > import shapes
+         * > from geometry import area
+         * >
+         * > a = geometry.area(3, 4)
+         * > b = area(3, h=2)
+         * > c = shapes.unit
+         */
+        let program = Sequence(
+            Box::new(Import("geometry".to_string())),
+            Box::new(Sequence(
+                Box::new(Import("shapes".to_string())),
+                Box::new(Sequence(
+                    Box::new(FromImport("geometry".to_string(), vec!["area".to_string()])),
+                    Box::new(Sequence(
+                        Box::new(Assignment(
+                            "a".to_string(),
+                            Box::new(MethodCall(
+                                Box::new(Var("geometry".to_string())),
+                                "area".to_string(),
+                                vec![CInt(3), CInt(4)],
+                            )),
+                            None,
+                        )),
+                        Box::new(Sequence(
+                            Box::new(Assignment(
+                                "b".to_string(),
+                                Box::new(FuncCall(
+                                    "area".to_string(),
+                                    vec![CInt(3), KeywordArg("h".to_string(), Box::new(CInt(2)))],
+                                )),
+                                None,
+                            )),
+                            Box::new(Assignment(
+                                "c".to_string(),
+                                Box::new(FieldAccess(
+                                    Box::new(Var("shapes".to_string())),
+                                    "unit".to_string(),
+                                )),
+                                None,
+                            )),
+                        )),
+                    )),
+                )),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                let value = |name: &str| new_env.search_frame(name.to_string()).cloned();
+
+                assert_eq!(value("a"), Some(EnvValue::Exp(CInt(24))));
+                assert_eq!(value("b"), Some(EnvValue::Exp(CInt(12))));
+                assert_eq!(value("c"), Some(EnvValue::Exp(CInt(2))));
+                assert_eq!(
+                    value("geometry"),
+                    Some(EnvValue::Module("geometry".to_string()))
+                );
+                /* 'geometry' is imported three times but loaded and executed once */
+                assert_eq!(loads.get(), 2);
+            }
+            Ok(_) => assert!(false),
+            Err(e) => assert!(false, "{}", e),
+        }
     }
 
     #[test]
-    fn eval_expression_with_variables() {
-        let mut env = Environment::new();
-        env.insert_variable("a".to_string(), EnvValue::Exp(CInt(5)));
-        env.insert_variable("b".to_string(), EnvValue::Exp(CInt(3)));
+    fn eval_methods_of_imported_classes() {
+        /*
+         * > # m
+         * > def twice(n: int) -> int:
+         * >     return n * 2
+         * >
+         * > class C:
+         * >     def get(self) -> int:
+         * >         return twice(1)
+         */
+        let module = Sequence(
+            Box::new(FuncDef(Function {
+                name: "twice".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Mul(
+                    Box::new(Var("n".to_string())),
+                    Box::new(CInt(2)),
+                ))))),
+            })),
+            Box::new(ClassDef(Class {
+                name: "C".to_string(),
+                parent: None,
+                fields: vec![],
+                methods: vec![Function {
+                    name: "get".to_string(),
+                    kind: Some(TInteger),
+                    params: Some(vec![("self".to_string(), TClass("C".to_string()))]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(FuncCall(
+                        "twice".to_string(),
+                        vec![CInt(1)],
+                    ))))),
+                }],
+            })),
+        );
+        let (env, _) = modules_env(vec![("m", module)]);
 
-        let expr = Mul(
-            Box::new(Var(String::from("a"))),
-            Box::new(Add(Box::new(Var(String::from("b"))), Box::new(CInt(2)))),
+        /*
+         * > import m
+         * > n = C().get()
+         */
+        let program = Sequence(
+            Box::new(Import("m".to_string())),
+            Box::new(Assignment(
+                "n".to_string(),
+                Box::new(MethodCall(
+                    Box::new(New("C".to_string(), vec![])),
+                    "get".to_string(),
+                    vec![],
+                )),
+                None,
+            )),
         );
 
-        assert_eq!(eval(expr, &env), Ok(EnvValue::Exp(CInt(25))));
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("n".to_string()),
+                Some(&EnvValue::Exp(CInt(2)))
+            ),
+            Ok(_) => panic!("the program should run to the end"),
+            Err(e) => panic!("{}", e),
+        }
     }
 
     #[test]
-    fn eval_nested_expressions() {
-        let env: Environment<EnvValue> = Environment::new();
+    fn eval_builtin_calls() {
+        let env = interpreter_env();
+        let text = |s: &str| CString(s.to_string());
+        let call = |name: &str, args: Vec<Expression>| FuncCall(name.to_string(), args);
+
+        let calls = vec![
+            (call("abs", vec![CInt(-3)]), CInt(3)),
+            (call("sqrt", vec![CInt(9)]), CReal(3.0)),
+            (call("floor", vec![CReal(-2.5)]), CInt(-3)),
+            (call("min", vec![CInt(3), CReal(1.5), CInt(2)]), CReal(1.5)),
+            (call("max", vec![CInt(4), CInt(7), CInt(5)]), CInt(7)),
+            (call("int", vec![text(" 42 ")]), CInt(42)),
+            (call("int", vec![CReal(-2.9)]), CInt(-2)),
+            (call("real", vec![text("2.5")]), CReal(2.5)),
+            (
+                call("str", vec![CList(vec![CInt(1), text("a")])]),
+                text("[1, \"a\"]"),
+            ),
+            (call("len", vec![text("héllo")]), CInt(5)),
+            (call("upper", vec![text("abc")]), text("ABC")),
+            (call("strip", vec![text("  abc ")]), text("abc")),
+            (
+                call(
+                    "join",
+                    vec![text("+"), call("split", vec![text("a b c"), text(" ")])],
+                ),
+                text("a+b+c"),
+            ),
+        ];
 
-        let expr = Add(
-            Box::new(Mul(Box::new(CInt(2)), Box::new(CInt(3)))),
-            Box::new(Sub(Box::new(CInt(10)), Box::new(CInt(4)))),
+        for (exp, value) in calls {
+            assert_eq!(eval(exp, &env), Ok(EnvValue::Exp(value)));
+        }
+
+        let errors = vec![
+            (
+                call("sqrt", vec![CInt(-1)]),
+                Exception::new(
+                    "ValueError",
+                    String::from("'sqrt()' is not defined for negative numbers."),
+                ),
+            ),
+            (
+                call("int", vec![text("4x")]),
+                Exception::new(
+                    "ValueError",
+                    String::from("invalid literal for 'int()': '4x'."),
+                ),
+            ),
+            (
+                call("len", vec![CInt(4)]),
+                Exception::new(
+                    "TypeError",
+                    String::from(
+                        "'len()' expects a string or a list, found a value of type 'int'.",
+                    ),
+                ),
+            ),
+        ];
+
+        for (exp, error) in errors {
+            assert_eq!(eval(exp, &env), Err(error));
+        }
+    }
+
+    #[test]
+    fn eval_builtins_in_modules() {
+        let mut loader = MemoryLoader::new();
+
+        /* > # measures
+         * > size = len("four")
+         */
+        loader.insert(
+            "measures",
+            Assignment(
+                "size".to_string(),
+                Box::new(FuncCall(
+                    "len".to_string(),
+                    vec![CString("four".to_string())],
+                )),
+                None,
+            ),
         );
 
-        assert_eq!(eval(expr, &env), Ok(EnvValue::Exp(CInt(12))));
+        let mut env = interpreter_env();
+        env.modules = Modules::new(Rc::new(loader));
+
+        /*
+         * > from measures import size
+         * > root = sqrt(size)
+         */
+        let program = Sequence(
+            Box::new(FromImport("measures".to_string(), vec!["size".to_string()])),
+            Box::new(Assignment(
+                "root".to_string(),
+                Box::new(FuncCall("sqrt".to_string(), vec![Var("size".to_string())])),
+                None,
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("root".to_string()),
+                Some(&EnvValue::Exp(CReal(2.0)))
+            ),
+            Ok(_) => assert!(false),
+            Err(e) => assert!(false, "{}", e),
+        }
     }
 
     #[test]
-    fn execute_assignment() {
-        let env: Environment<EnvValue> = Environment::new();
-
-        let assign_stmt = Assignment(String::from("x"), Box::new(CInt(42)), Some(TInteger));
-
-        match execute(assign_stmt, &env) {
-            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
-                new_env.search_frame("x".to_string()),
-                Some(&EnvValue::Exp(CInt(42)))
+    fn eval_import_errors() {
+        let (env, _) = modules_env(vec![
+            ("geometry", geometry_module()),
+            ("a", Import("b".to_string())),
+            ("b", Import("a".to_string())),
+        ]);
+
+        let errors = vec![
+            (
+                Import("missing".to_string()),
+                Exception::new(
+                    "ModuleNotFoundError",
+                    String::from("no module named 'missing'."),
+                ),
             ),
-            Ok(ControlFlow::Return(_)) => assert!(false),
-            Err(s) => assert!(false, "{}", s),
+            (
+                FromImport("geometry".to_string(), vec!["_scale".to_string()]),
+                Exception::new(
+                    "ImportError",
+                    String::from("cannot import name '_scale' from module 'geometry'."),
+                ),
+            ),
+            (
+                Sequence(
+                    Box::new(Import("geometry".to_string())),
+                    Box::new(ExprStmt(Box::new(MethodCall(
+                        Box::new(Var("geometry".to_string())),
+                        "volume".to_string(),
+                        vec![],
+                    )))),
+                ),
+                Exception::new(
+                    "AttributeError",
+                    String::from("module 'geometry' has no attribute 'volume'."),
+                ),
+            ),
+            (
+                Import("a".to_string()),
+                Exception::new("ImportError", String::from("circular import: a -> b -> a.")),
+            ),
+        ];
+
+        for (program, error) in errors {
+            match execute(program, &env) {
+                Ok(_) => assert!(false),
+                Err(e) => assert_eq!(e, error),
+            }
         }
     }
 
     #[test]
-    fn eval_summation() {
+    fn eval_mutual_recursion() {
+        let env = Environment::new();
+
         /*
-         * (a test case for the following program)
-         *
-         * > x: TInteger = 10
-         * > y: TInteger = 0
-         * > while x >= 0:
-         * >   y = y + x
-         * >   x = x - 1
-         *
-         * After executing this program, 'x' must be zero and
-         * 'y' must be 55.
+         * > def is_even(n: int) -> bool:
+         * >     if n == 0:
+         * >         return True
+         * >     else:
+         * >         return is_odd(n - 1)
+         * >
+         * > def is_odd(n: int) -> bool:
+         * >     if n == 0:
+         * >         return False
+         * >     else:
+         * >         return is_even(n - 1)
+         * >
+         * > a = is_even(10)
+         * > b = is_odd(10)
          */
+        let parity = |name: &str, base: Expression, other: &str| {
+            FuncDef(Function {
+                name: name.to_string(),
+                kind: Some(TBool),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(IfThenElse(
+                    Box::new(EQ(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
+                    Box::new(Return(Box::new(base))),
+                    Some(Box::new(Return(Box::new(FuncCall(
+                        other.to_string(),
+                        vec![Sub(Box::new(Var("n".to_string())), Box::new(CInt(1)))],
+                    ))))),
+                ))),
+            })
+        };
 
-        let env: Environment<EnvValue> = Environment::new();
-
-        let a1 = Assignment(String::from("x"), Box::new(CInt(10)), Some(TInteger));
-        let a2 = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
-        let a3 = Assignment(
-            String::from("y"),
-            Box::new(Add(
-                Box::new(Var(String::from("y"))),
-                Box::new(Var(String::from("x"))),
+        let program = Sequence(
+            Box::new(parity("is_even", CTrue, "is_odd")),
+            Box::new(Sequence(
+                Box::new(parity("is_odd", CFalse, "is_even")),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "a".to_string(),
+                        Box::new(FuncCall("is_even".to_string(), vec![CInt(10)])),
+                        None,
+                    )),
+                    Box::new(Assignment(
+                        "b".to_string(),
+                        Box::new(FuncCall("is_odd".to_string(), vec![CInt(10)])),
+                        None,
+                    )),
+                )),
             )),
-            None,
-        );
-        let a4 = Assignment(
-            String::from("x"),
-            Box::new(Sub(Box::new(Var(String::from("x"))), Box::new(CInt(1)))),
-            None,
         );
 
-        let seq1 = Sequence(Box::new(a3), Box::new(a4));
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("a".to_string()),
+                    Some(&EnvValue::Exp(CTrue))
+                );
+                assert_eq!(
+                    new_env.search_frame("b".to_string()),
+                    Some(&EnvValue::Exp(CFalse))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(e) => assert!(false, "{}", e),
+        }
+    }
 
-        let while_statement = While(
-            Box::new(GT(Box::new(Var(String::from("x"))), Box::new(CInt(0)))),
-            Box::new(seq1),
-        );
+    #[test]
+    fn eval_type_alias() {
+        let env = Environment::new();
 
-        let seq2 = Sequence(Box::new(a2), Box::new(while_statement));
-        let program = Sequence(Box::new(a1), Box::new(seq2));
+        /*
+         * > Callback = (int) -> int
+         * > Score = real
+         * >
+         * > def apply(f: Callback, x: int) -> int:
+         * >     return f(x)
+         * >
+         * > def inc(n: int) -> int:
+         * >     return n + 1
+         * >
+         * > r = apply(inc, 41)
+         * > s: Score = 1
+         */
+        let program = Sequence(
+            Box::new(TypeAlias(
+                "Callback".to_string(),
+                TFunction(Box::new(Some(TInteger)), vec![TInteger]),
+            )),
+            Box::new(Sequence(
+                Box::new(TypeAlias("Score".to_string(), TReal)),
+                Box::new(Sequence(
+                    Box::new(FuncDef(Function {
+                        name: "apply".to_string(),
+                        kind: Some(TInteger),
+                        params: Some(vec![
+                            ("f".to_string(), TAlias("Callback".to_string())),
+                            ("x".to_string(), TInteger),
+                        ]),
+                        defaults: vec![],
+                        variadic: None,
+                        module: None,
+                        body: Some(Box::new(Return(Box::new(FuncCall(
+                            "f".to_string(),
+                            vec![Var("x".to_string())],
+                        ))))),
+                    })),
+                    Box::new(Sequence(
+                        Box::new(FuncDef(Function {
+                            name: "inc".to_string(),
+                            kind: Some(TInteger),
+                            params: Some(vec![("n".to_string(), TInteger)]),
+                            defaults: vec![],
+                            variadic: None,
+                            module: None,
+                            body: Some(Box::new(Return(Box::new(Add(
+                                Box::new(Var("n".to_string())),
+                                Box::new(CInt(1)),
+                            ))))),
+                        })),
+                        Box::new(Sequence(
+                            Box::new(Assignment(
+                                "r".to_string(),
+                                Box::new(FuncCall(
+                                    "apply".to_string(),
+                                    vec![Var("inc".to_string()), CInt(41)],
+                                )),
+                                None,
+                            )),
+                            Box::new(Assignment(
+                                "s".to_string(),
+                                Box::new(CInt(1)),
+                                Some(TAlias("Score".to_string())),
+                            )),
+                        )),
+                    )),
+                )),
+            )),
+        );
 
         match execute(program, &env) {
             Ok(ControlFlow::Continue(new_env)) => {
                 assert_eq!(
-                    new_env.search_frame("y".to_string()),
-                    Some(&EnvValue::Exp(CInt(55)))
+                    new_env.search_frame("r".to_string()),
+                    Some(&EnvValue::Exp(CInt(42)))
                 );
                 assert_eq!(
-                    new_env.search_frame("x".to_string()),
-                    Some(&EnvValue::Exp(CInt(0)))
+                    new_env.search_frame("s".to_string()),
+                    Some(&EnvValue::Exp(CReal(1.0)))
                 );
+
+                /* > f: Callback = 3 */
+                match execute(
+                    Assignment(
+                        "f".to_string(),
+                        Box::new(CInt(3)),
+                        Some(TAlias("Callback".to_string())),
+                    ),
+                    &new_env,
+                ) {
+                    Ok(_) => assert!(false),
+                    Err(e) => assert_eq!(
+                        e,
+                        Exception::new(
+                            "TypeError",
+                            String::from(
+                                "'f' expected 'TAlias(\"Callback\")', found a value of type 'int'."
+                            )
+                        )
+                    ),
+                }
             }
-            Ok(ControlFlow::Return(_)) => assert!(false),
-            Err(s) => assert!(false, "{}", s),
+            Ok(_) => assert!(false),
+            Err(e) => assert!(false, "{}", e),
         }
     }
 
     #[test]
-    fn eval_simple_if_then_else() {
+    fn eval_annotated_functions_and_tuples() {
+        let env = Environment::new();
+
         /*
-         * Test for simple if-then-else statement
-         *
-         * > x: TInteger = 10
-         * > if x > 5:
-         * >   y: TInteger = 1
-         * > else:
-         * >   y: TInteger = 0
-         *
-         * After executing, 'y' should be 1.
+         * > def inc(n: int) -> int:
+         * >     return n + 1
+         * >
+         * > f: (int) -> int = inc
+         * > t: (int, str) = [1, "a"]
          */
+        let inc = FuncDef(Function {
+            name: "inc".to_string(),
+            kind: Some(TInteger),
+            params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(Add(
+                Box::new(Var("n".to_string())),
+                Box::new(CInt(1)),
+            ))))),
+        });
+        let callback = |param: Type| TFunction(Box::new(Some(TInteger)), vec![param]);
+        let pair = TTuple(vec![TInteger, TString]);
 
-        let env: Environment<EnvValue> = Environment::new();
-
-        let condition = GT(Box::new(Var(String::from("x"))), Box::new(CInt(5)));
-        let then_stmt = Assignment(String::from("y"), Box::new(CInt(1)), Some(TInteger));
-        let else_stmt = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
-
-        let if_statement = IfThenElse(
-            Box::new(condition),
-            Box::new(then_stmt),
-            Some(Box::new(else_stmt)),
+        let program = Sequence(
+            Box::new(inc),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "f".to_string(),
+                    Box::new(Var("inc".to_string())),
+                    Some(callback(TInteger)),
+                )),
+                Box::new(Assignment(
+                    "t".to_string(),
+                    Box::new(CList(vec![CInt(1), CString("a".to_string())])),
+                    Some(pair.clone()),
+                )),
+            )),
         );
 
-        let setup_stmt = Assignment(String::from("x"), Box::new(CInt(10)), Some(TInteger));
-        let program = Sequence(Box::new(setup_stmt), Box::new(if_statement));
+        let new_env = match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("the program should run to the end"),
+            Err(e) => panic!("{}", e),
+        };
+
+        /* > g: (str) -> int = inc */
+        assert_eq!(
+            execute(
+                Assignment(
+                    "g".to_string(),
+                    Box::new(Var("inc".to_string())),
+                    Some(callback(TString)),
+                ),
+                &new_env,
+            )
+            .err(),
+            Some(Exception::new(
+                "TypeError",
+                String::from(
+                    "'g' expected 'TFunction(Some(TInteger), [TString])', found a value of type 'function'."
+                )
+            ))
+        );
 
-        match execute(program, &env) {
-            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
-                new_env.search_frame("y".to_string()),
-                Some(&EnvValue::Exp(CInt(1)))
-            ),
-            Ok(ControlFlow::Return(_)) => assert!(false),
-            Err(s) => assert!(false, "{}", s),
-        }
+        /* > u: (int, str) = [1, 2] */
+        assert_eq!(
+            execute(
+                Assignment(
+                    "u".to_string(),
+                    Box::new(CList(vec![CInt(1), CInt(2)])),
+                    Some(pair),
+                ),
+                &new_env,
+            )
+            .err(),
+            Some(Exception::new(
+                "TypeError",
+                String::from(
+                    "'u' expected 'TTuple([TInteger, TString])', found a value of type 'list'."
+                )
+            ))
+        );
     }
 
-    #[test]
-    fn eval_if_then_optional_else() {
+    fn animal_classes() -> Statement {
         /*
-         * Test for simple if-then-else statement
-         *
-         * > x: TInteger = 1
-         * > y: TInteger = 0
-         * > if x == y:
-         * >   y = 1
-         * > else:
-         * >    y = 2
-         * >    if x < 0:
-         * >        y = 5
-         *
-         * After executing, 'y' should be 2.
+         * > class Animal:
+         * >     name: str
+         * >     def __init__(self, name: str):
+         * >         self.name = name
+         * >     def speak(self) -> str:
+         * >         return "..."
+         * >     def describe(self) -> str:
+         * >         return self.speak()
+         * >
+         * > class Dog(Animal):
+         * >     def speak(self) -> str:
+         * >         return "woof"
          */
+        let animal = ClassDef(Class {
+            name: "Animal".to_string(),
+            parent: None,
+            fields: vec![("name".to_string(), TString)],
+            methods: vec![
+                Function {
+                    name: "__init__".to_string(),
+                    kind: None,
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Animal".to_string())),
+                        ("name".to_string(), TString),
+                    ]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(FieldAssignment(
+                        "self".to_string(),
+                        "name".to_string(),
+                        Box::new(Var("name".to_string())),
+                    ))),
+                },
+                Function {
+                    name: "speak".to_string(),
+                    kind: Some(TString),
+                    params: Some(vec![("self".to_string(), TClass("Animal".to_string()))]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(CString("...".to_string()))))),
+                },
+                Function {
+                    name: "describe".to_string(),
+                    kind: Some(TString),
+                    params: Some(vec![("self".to_string(), TClass("Animal".to_string()))]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(MethodCall(
+                        Box::new(Var("self".to_string())),
+                        "speak".to_string(),
+                        vec![],
+                    ))))),
+                },
+            ],
+        });
 
-        let env: Environment<EnvValue> = Environment::new();
-
-        let second_condition = LT(Box::new(Var(String::from("x"))), Box::new(CInt(0)));
-        let second_then_stmt = Assignment(String::from("y"), Box::new(CInt(5)), None);
+        let dog = ClassDef(Class {
+            name: "Dog".to_string(),
+            parent: Some("Animal".to_string()),
+            fields: vec![],
+            methods: vec![Function {
+                name: "speak".to_string(),
+                kind: Some(TString),
+                params: Some(vec![("self".to_string(), TClass("Dog".to_string()))]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(CString("woof".to_string()))))),
+            }],
+        });
 
-        let second_if_stmt =
-            IfThenElse(Box::new(second_condition), Box::new(second_then_stmt), None);
+        Sequence(Box::new(animal), Box::new(dog))
+    }
 
-        let else_setup_stmt = Assignment(String::from("y"), Box::new(CInt(2)), None);
-        let else_stmt = Sequence(Box::new(else_setup_stmt), Box::new(second_if_stmt));
+    #[test]
+    fn eval_method_dispatch() {
+        let env = Environment::new();
 
-        let first_condition = EQ(
-            Box::new(Var(String::from("x"))),
-            Box::new(Var(String::from("y"))),
+        /*
+         * > a: Animal = Dog("Rex")
+         * > sound = a.describe()
+         * > name = a.name
+         */
+        let program = Sequence(
+            Box::new(animal_classes()),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "a".to_string(),
+                    Box::new(New("Dog".to_string(), vec![CString("Rex".to_string())])),
+                    Some(TClass("Animal".to_string())),
+                )),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "sound".to_string(),
+                        Box::new(MethodCall(
+                            Box::new(Var("a".to_string())),
+                            "describe".to_string(),
+                            vec![],
+                        )),
+                        Some(TString),
+                    )),
+                    Box::new(Assignment(
+                        "name".to_string(),
+                        Box::new(FieldAccess(
+                            Box::new(Var("a".to_string())),
+                            "name".to_string(),
+                        )),
+                        Some(TString),
+                    )),
+                )),
+            )),
         );
-        let first_then_stmt = Assignment(String::from("y"), Box::new(CInt(1)), None);
 
-        let first_if_stmt = IfThenElse(
-            Box::new(first_condition),
-            Box::new(first_then_stmt),
-            Some(Box::new(else_stmt)),
-        );
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("sound".to_string()),
+                    Some(&EnvValue::Exp(CString("woof".to_string())))
+                );
+                assert_eq!(
+                    new_env.search_frame("name".to_string()),
+                    Some(&EnvValue::Exp(CString("Rex".to_string())))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
 
-        let second_assignment = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
-        let setup_stmt = Sequence(Box::new(second_assignment), Box::new(first_if_stmt));
+    #[test]
+    fn eval_method_arguments() {
+        let env = Environment::new();
 
-        let first_assignment = Assignment(String::from("x"), Box::new(CInt(1)), Some(TInteger));
-        let program = Sequence(Box::new(first_assignment), Box::new(setup_stmt));
+        /*
+         * > class Greeter:
+         * >     greeting: str
+         * >     def __init__(self, greeting: str = "hello"):
+         * >         self.greeting = greeting
+         * >     def greet(self, name: str, mark: str = "!") -> list[str]:
+         * >         return [self.greeting, name, mark]
+         * >     def rest(self, first: str, *others: str) -> list[str]:
+         * >         return others
+         */
+        let greeter = ClassDef(Class {
+            name: "Greeter".to_string(),
+            parent: None,
+            fields: vec![("greeting".to_string(), TString)],
+            methods: vec![
+                Function {
+                    name: "__init__".to_string(),
+                    kind: None,
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Greeter".to_string())),
+                        ("greeting".to_string(), TString),
+                    ]),
+                    defaults: vec![("greeting".to_string(), CString("hello".to_string()))],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(FieldAssignment(
+                        "self".to_string(),
+                        "greeting".to_string(),
+                        Box::new(Var("greeting".to_string())),
+                    ))),
+                },
+                Function {
+                    name: "greet".to_string(),
+                    kind: Some(TList(Box::new(TString))),
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Greeter".to_string())),
+                        ("name".to_string(), TString),
+                        ("mark".to_string(), TString),
+                    ]),
+                    defaults: vec![("mark".to_string(), CString("!".to_string()))],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(CList(vec![
+                        FieldAccess(Box::new(Var("self".to_string())), "greeting".to_string()),
+                        Var("name".to_string()),
+                        Var("mark".to_string()),
+                    ]))))),
+                },
+                Function {
+                    name: "rest".to_string(),
+                    kind: Some(TList(Box::new(TString))),
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Greeter".to_string())),
+                        ("first".to_string(), TString),
+                    ]),
+                    defaults: vec![],
+                    variadic: Some(("others".to_string(), TString)),
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(Var("others".to_string()))))),
+                },
+            ],
+        });
+
+        /*
+         * > g = Greeter()
+         * > a = g.greet("Ann")
+         * > b = g.greet(mark="?", name="Bo")
+         * > c = g.rest("x", "y", "z")
+         */
+        let g = || Box::new(Var("g".to_string()));
+        let program = Sequence(
+            Box::new(greeter),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "g".to_string(),
+                    Box::new(New("Greeter".to_string(), vec![])),
+                    None,
+                )),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "a".to_string(),
+                        Box::new(MethodCall(
+                            g(),
+                            "greet".to_string(),
+                            vec![CString("Ann".to_string())],
+                        )),
+                        None,
+                    )),
+                    Box::new(Sequence(
+                        Box::new(Assignment(
+                            "b".to_string(),
+                            Box::new(MethodCall(
+                                g(),
+                                "greet".to_string(),
+                                vec![
+                                    KeywordArg(
+                                        "mark".to_string(),
+                                        Box::new(CString("?".to_string())),
+                                    ),
+                                    KeywordArg(
+                                        "name".to_string(),
+                                        Box::new(CString("Bo".to_string())),
+                                    ),
+                                ],
+                            )),
+                            None,
+                        )),
+                        Box::new(Assignment(
+                            "c".to_string(),
+                            Box::new(MethodCall(
+                                g(),
+                                "rest".to_string(),
+                                vec![
+                                    CString("x".to_string()),
+                                    CString("y".to_string()),
+                                    CString("z".to_string()),
+                                ],
+                            )),
+                            None,
+                        )),
+                    )),
+                )),
+            )),
+        );
 
         match execute(program, &env) {
-            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
-                new_env.search_frame("y".to_string()),
-                Some(&EnvValue::Exp(CInt(2)))
-            ),
-            Ok(ControlFlow::Return(_)) => assert!(false),
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("a".to_string()),
+                    Some(&EnvValue::Exp(CList(vec![
+                        CString("hello".to_string()),
+                        CString("Ann".to_string()),
+                        CString("!".to_string())
+                    ])))
+                );
+                assert_eq!(
+                    new_env.search_frame("b".to_string()),
+                    Some(&EnvValue::Exp(CList(vec![
+                        CString("hello".to_string()),
+                        CString("Bo".to_string()),
+                        CString("?".to_string())
+                    ])))
+                );
+                assert_eq!(
+                    new_env.search_frame("c".to_string()),
+                    Some(&EnvValue::Exp(CList(vec![
+                        CString("y".to_string()),
+                        CString("z".to_string())
+                    ])))
+                );
+            }
+            Ok(_) => assert!(false),
             Err(s) => assert!(false, "{}", s),
         }
     }
 
-    // #[test]
-    // fn eval_while_loop_decrement() {
-    //     /*
-    //      * Test for while loop that decrements a variable
-    //      *
-    //      * > x = 3
-    //      * > y = 10
-    //      * > while x:
-    //      * >   y = y - 1
-    //      * >   x = x - 1
-    //      *
-    //      * After executing, 'y' should be 7 and 'x' should be 0.
-    //      */
-    //     let env = HashMap::new();
-
-    //     let a1 = Assignment(String::from("x"), Box::new(CInt(3))); -> corrigido parenteses extras.
-    //     let a2 = Assignment(String::from("y")), Box:new(CInt(10)));
-    //     let a3 = Assignment(
-    //         String::from("y")),
-    //         Box::new(Sub(
-    //             Box::new(Var(String::from("y"))),
-    //             Box::new(CInt(1)),
-    //         )),
-    //     );
-    //     let a4 = Assignment(
-    //         String::from("x")),
-    //         Box::new(Sub(
-    //             Box::new(Var(String::from("x"))),
-    //             Box::new(CInt(1)),
-    //         )),
-    //     );
+    #[test]
+    fn eval_objects_are_shared() {
+        let env = Environment::new();
 
-    //     let seq1 = Sequence(Box::new(a3), Box::new(a4));
-    //     let while_statement =
-    //         While(Box::new(Var(String::from("x"))), Box::new(seq1));
-    //     let program = Sequence(
-    //         Box::new(a1),
-    //         Box::new(Sequence(Box::new(a2), Box::new(while_statement))),
-    //     );
+        /*
+         * > class Counter:
+         * >     count: int
+         * >     def __init__(self):
+         * >         self.count = 0
+         * >     def increment(self) -> int:
+         * >         self.count = self.count + 1
+         * >         return self.count
+         * >
+         * > c = Counter()
+         * > alias = c
+         * > c.increment()
+         * > n = alias.increment()
+         */
+        let counter = ClassDef(Class {
+            name: "Counter".to_string(),
+            parent: None,
+            fields: vec![("count".to_string(), TInteger)],
+            methods: vec![
+                Function {
+                    name: "__init__".to_string(),
+                    kind: None,
+                    params: Some(vec![("self".to_string(), TClass("Counter".to_string()))]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(FieldAssignment(
+                        "self".to_string(),
+                        "count".to_string(),
+                        Box::new(CInt(0)),
+                    ))),
+                },
+                Function {
+                    name: "increment".to_string(),
+                    kind: Some(TInteger),
+                    params: Some(vec![("self".to_string(), TClass("Counter".to_string()))]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Sequence(
+                        Box::new(FieldAssignment(
+                            "self".to_string(),
+                            "count".to_string(),
+                            Box::new(Add(
+                                Box::new(FieldAccess(
+                                    Box::new(Var("self".to_string())),
+                                    "count".to_string(),
+                                )),
+                                Box::new(CInt(1)),
+                            )),
+                        )),
+                        Box::new(Return(Box::new(FieldAccess(
+                            Box::new(Var("self".to_string())),
+                            "count".to_string(),
+                        )))),
+                    ))),
+                },
+            ],
+        });
 
-    //     match execute(&program, env) {
-    //         Ok(new_env) => {
-    //             assert_eq!(new_env.get("y"), Some(&7));
-    //             assert_eq!(new_env.get("x"), Some(&0));
-    //         }
-    //         Err(s) => assert!(false, "{}", s),
-    //     }
-    // }
+        let increment = |name: &str| {
+            MethodCall(
+                Box::new(Var(name.to_string())),
+                "increment".to_string(),
+                vec![],
+            )
+        };
 
-    // #[test]
-    // fn eval_nested_if_statements() {
-    //     /*
-    //      * Test for nested if-then-else statements
-    //      *
-    //      * > x = 10
-    //      * > if x > 5:
-    //      * >   if x > 8:
-    //      * >     y = 1
-    //      * >   else:
-    //      * >     y = 2
-    //      * > else:
-    //      * >   y = 0
-    //      *
-    //      * After executing, 'y' should be 1.
-    //      */
-    //     let env = HashMap::new();
+        let program = Sequence(
+            Box::new(counter),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "c".to_string(),
+                    Box::new(New("Counter".to_string(), vec![])),
+                    Some(TClass("Counter".to_string())),
+                )),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "alias".to_string(),
+                        Box::new(Var("c".to_string())),
+                        Some(TClass("Counter".to_string())),
+                    )),
+                    Box::new(Sequence(
+                        Box::new(Assignment(
+                            "m".to_string(),
+                            Box::new(increment("c")),
+                            Some(TInteger),
+                        )),
+                        Box::new(Assignment(
+                            "n".to_string(),
+                            Box::new(increment("alias")),
+                            Some(TInteger),
+                        )),
+                    )),
+                )),
+            )),
+        );
 
-    //     let inner_then_stmt =
-    //         Assignment(String::from("y")), Box:new(CInt(1)));
-    //     let inner_else_stmt =
-    //         Assignment(String::from("y")), Box:new(CInt(2)));
-    //     let inner_if_statement = Statement::IfThenElse(
-    //         Box::new(Var(String::from("x"))),
-    //         Box::new(inner_then_stmt),
-    //         Box::new(inner_else_stmt),
-    //     );
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("n".to_string()),
+                    Some(&EnvValue::Exp(CInt(2)))
+                );
+                assert_eq!(
+                    eval(
+                        FieldAccess(Box::new(Var("c".to_string())), "count".to_string()),
+                        &new_env
+                    ),
+                    Ok(EnvValue::Exp(CInt(2)))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
 
-    //     let outer_else_stmt =
-    //         Assignment(String::from("y")), Box:new(CInt(0)));
-    //     let outer_if_statement = Statement::IfThenElse(
-    //         Box::new(Var(String::from("x"))),
-    //         Box::new(inner_if_statement),
-    //         Box::new(outer_else_stmt),
-    //     );
+    #[test]
+    fn eval_object_errors() {
+        let mut env = Environment::new();
 
-    //     let setup_stmt =
-    //         Assignment(String::from("x")), Box:new(CInt(10)));
-    //     let program = Sequence(Box::new(setup_stmt), Box::new(outer_if_statement));
+        if let Ok(ControlFlow::Continue(new_env)) = execute(animal_classes(), &env) {
+            env = new_env;
+        }
 
-    //     match execute(&program, env) {
-    //         Ok(new_env) => assert_eq!(new_env.get("y"), Some(&1)),
-    //         Err(s) => assert!(false, "{}", s),
-    //     }
-    // }
+        let dog = New("Dog".to_string(), vec![CString("Rex".to_string())]);
 
-    #[test]
-    fn eval_complex_sequence() {
+        assert_eq!(
+            eval(
+                MethodCall(Box::new(dog.clone()), "fetch".to_string(), vec![]),
+                &env
+            ),
+            Err(Exception::new(
+                "AttributeError",
+                String::from("'Dog' object has no method 'fetch()'.")
+            ))
+        );
+        assert_eq!(
+            eval(New("Dog".to_string(), vec![]), &env),
+            Err(Exception::new(
+                "TypeError",
+                String::from("'Dog.__init__()' expected 1 arguments, found 0.")
+            ))
+        );
+        assert_eq!(
+            eval(FieldAccess(Box::new(dog), "age".to_string()), &env),
+            Err(Exception::new(
+                "AttributeError",
+                String::from("'Dog' object has no attribute 'age'.")
+            ))
+        );
+    }
+
+    fn first_positive() -> Statement {
         /*
-         * Sequence with multiple assignments and expressions
-         *
-         * > x: TInteger = 5
-         * > y: TInteger = 0
-         * > z: TInteger = 2 * x + 3
-         *
-         * After executing, 'x' should be 5, 'y' should be 0, and 'z' should be 13.
+         * > def first_positive(m: Maybe[int]) -> Maybe[int]:
+         * >     n = m?
+         * >     try:
+         * >         assert n > 0, "not positive"
+         * >     except Exception:
+         * >         return Nothing
+         * >     return Just(n * 10)
          */
+        FuncDef(Function {
+            name: "first_positive".to_string(),
+            kind: Some(TMaybe(Box::new(TInteger))),
+            params: Some(vec![("m".to_string(), TMaybe(Box::new(TInteger)))]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Sequence(
+                Box::new(Assignment(
+                    "n".to_string(),
+                    Box::new(Propagate(Box::new(Var("m".to_string())))),
+                    Some(TInteger),
+                )),
+                Box::new(Sequence(
+                    Box::new(TryExcept(
+                        Box::new(Assert(
+                            Box::new(GT(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
+                            Box::new(CString("not positive".to_string())),
+                        )),
+                        vec![("Exception".to_string(), None, Return(Box::new(CNothing)))],
+                        None,
+                    )),
+                    Box::new(Return(Box::new(CJust(Box::new(Mul(
+                        Box::new(Var("n".to_string())),
+                        Box::new(CInt(10)),
+                    )))))),
+                )),
+            ))),
+        })
+    }
 
-        let env: Environment<EnvValue> = Environment::new();
+    #[test]
+    fn eval_propagate() {
+        let env = Environment::new();
 
-        let a1 = Assignment(String::from("x"), Box::new(CInt(5)), Some(TInteger));
-        let a2 = Assignment(String::from("y"), Box::new(CInt(0)), Some(TInteger));
-        let a3 = Assignment(
-            String::from("z"),
-            Box::new(Add(
-                Box::new(Mul(Box::new(CInt(2)), Box::new(Var(String::from("x"))))),
-                Box::new(CInt(3)),
+        let program = Sequence(
+            Box::new(first_positive()),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "a".to_string(),
+                    Box::new(FuncCall(
+                        "first_positive".to_string(),
+                        vec![CJust(Box::new(CInt(4)))],
+                    )),
+                    Some(TMaybe(Box::new(TInteger))),
+                )),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "b".to_string(),
+                        Box::new(FuncCall("first_positive".to_string(), vec![CNothing])),
+                        Some(TMaybe(Box::new(TInteger))),
+                    )),
+                    Box::new(Assignment(
+                        "c".to_string(),
+                        Box::new(FuncCall(
+                            "first_positive".to_string(),
+                            vec![CJust(Box::new(CInt(-1)))],
+                        )),
+                        Some(TMaybe(Box::new(TInteger))),
+                    )),
+                )),
             )),
-            Some(TInteger),
         );
 
-        let program = Sequence(Box::new(a1), Box::new(Sequence(Box::new(a2), Box::new(a3))));
-
         match execute(program, &env) {
             Ok(ControlFlow::Continue(new_env)) => {
                 assert_eq!(
-                    new_env.search_frame("x".to_string()),
-                    Some(&EnvValue::Exp(CInt(5)))
+                    new_env.search_frame("a".to_string()),
+                    Some(&EnvValue::Exp(CJust(Box::new(CInt(40)))))
                 );
                 assert_eq!(
-                    new_env.search_frame("y".to_string()),
-                    Some(&EnvValue::Exp(CInt(0)))
+                    new_env.search_frame("b".to_string()),
+                    Some(&EnvValue::Exp(CNothing))
                 );
                 assert_eq!(
-                    new_env.search_frame("z".to_string()),
-                    Some(&EnvValue::Exp(CInt(13)))
+                    new_env.search_frame("c".to_string()),
+                    Some(&EnvValue::Exp(CNothing))
                 );
             }
-            Ok(ControlFlow::Return(_)) => assert!(false),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_maybe_and_result_helpers() {
+        let env = Environment::new();
+        let err = CErr(Box::new(CString("bad input".to_string())));
+
+        assert_eq!(
+            eval(IsJust(Box::new(CJust(Box::new(CInt(1))))), &env),
+            Ok(EnvValue::Exp(CTrue))
+        );
+        assert_eq!(
+            eval(IsOk(Box::new(err.clone())), &env),
+            Ok(EnvValue::Exp(CFalse))
+        );
+        assert_eq!(
+            eval(UnwrapOr(Box::new(err.clone()), Box::new(CInt(7))), &env),
+            Ok(EnvValue::Exp(CInt(7)))
+        );
+        assert_eq!(
+            eval(Unwrap(Box::new(err.clone())), &env),
+            Err(Exception::new(
+                "UnwrapError",
+                String::from("called unwrap on 'Err(\"bad input\")'.")
+            ))
+        );
+        assert_eq!(
+            eval(
+                EQ(Box::new(CJust(Box::new(CInt(1)))), Box::new(CNothing)),
+                &env
+            ),
+            Ok(EnvValue::Exp(CFalse))
+        );
+        assert_eq!(
+            eval(Propagate(Box::new(err.clone())), &env).map_err(|e| e.to_string()),
+            Err(String::from("[SyntaxError] '?' used outside a function."))
+        );
+    }
+
+    #[test]
+    fn eval_wrapped_value_equality() {
+        let env: Environment<EnvValue> = Environment::new();
+        let just = |n| CJust(Box::new(CInt(n)));
+
+        assert_eq!(
+            eval(EQ(Box::new(CNothing), Box::new(CNothing)), &env),
+            Ok(EnvValue::Exp(CTrue))
+        );
+        assert_eq!(
+            eval(NEQ(Box::new(CNothing), Box::new(CNothing)), &env),
+            Ok(EnvValue::Exp(CFalse))
+        );
+        assert_eq!(
+            eval(
+                EQ(
+                    Box::new(CList(vec![CNothing, just(1)])),
+                    Box::new(CList(vec![CNothing, just(1)]))
+                ),
+                &env
+            ),
+            Ok(EnvValue::Exp(CTrue))
+        );
+        assert_eq!(
+            eval(
+                EQ(Box::new(CJust(Box::new(CNothing))), Box::new(just(1))),
+                &env
+            ),
+            Ok(EnvValue::Exp(CFalse))
+        );
+        assert_eq!(
+            eval(
+                EQ(
+                    Box::new(COk(Box::new(CReal(1.0)))),
+                    Box::new(COk(Box::new(CInt(1))))
+                ),
+                &env
+            ),
+            Ok(EnvValue::Exp(CTrue))
+        );
+        assert_eq!(
+            eval(
+                EQ(
+                    Box::new(COk(Box::new(CInt(1)))),
+                    Box::new(CErr(Box::new(CInt(1))))
+                ),
+                &env
+            ),
+            Ok(EnvValue::Exp(CFalse))
+        );
+        assert_eq!(
+            eval(EQ(Box::new(CInt(1)), Box::new(just(1))), &env),
+            Ok(EnvValue::Exp(CFalse))
+        );
+    }
+
+    #[test]
+    fn eval_propagate_is_not_caught() {
+        let env = Environment::new();
+
+        /*
+         * > def first(m: Maybe[int]) -> Maybe[int]:
+         * >     try:
+         * >         n = m?
+         * >     except Exception:
+         * >         return Just(0)
+         * >     return Just(n)
+         * >
+         * > r = first(Nothing)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "first".to_string(),
+                kind: Some(TMaybe(Box::new(TInteger))),
+                params: Some(vec![("m".to_string(), TMaybe(Box::new(TInteger)))]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Sequence(
+                    Box::new(TryExcept(
+                        Box::new(Assignment(
+                            "n".to_string(),
+                            Box::new(Propagate(Box::new(Var("m".to_string())))),
+                            None,
+                        )),
+                        vec![(
+                            "Exception".to_string(),
+                            None,
+                            Return(Box::new(CJust(Box::new(CInt(0))))),
+                        )],
+                        None,
+                    )),
+                    Box::new(Return(Box::new(CJust(Box::new(Var("n".to_string())))))),
+                ))),
+            })),
+            Box::new(Assignment(
+                "r".to_string(),
+                Box::new(FuncCall("first".to_string(), vec![CNothing])),
+                None,
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("r".to_string()),
+                Some(&EnvValue::Exp(CNothing))
+            ),
+            Ok(_) => assert!(false),
             Err(s) => assert!(false, "{}", s),
         }
     }
@@ -1046,6 +5350,9 @@ mod tests {
             name: "fibonacci".to_string(),
             kind: Some(TInteger),
             params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Sequence(
                 Box::new(IfThenElse(
                     Box::new(LT(Box::new(Var("n".to_string())), Box::new(CInt(1)))),
@@ -1089,8 +5396,249 @@ mod tests {
                 new_env.search_frame("fib".to_string()),
                 Some(&EnvValue::Exp(CInt(34)))
             ),
-            Ok(ControlFlow::Return(_)) => assert!(false),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn eval_deep_recursion_on_default_stack() {
+        /*
+         * > def depth(n: int) -> int:
+         * >     if n <= 0:
+         * >         return 0
+         * >     return depth(n - 1) + 1
+         * >
+         * > d: int = depth(40)
+         *
+         * Runs on a thread with the default 2 MiB stack of spawned threads.
+         */
+        let run = || {
+            let env: Environment<EnvValue> = Environment::new();
+            let n = || Box::new(Var("n".to_string()));
+
+            let func = FuncDef(Function {
+                name: "depth".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Sequence(
+                    Box::new(IfThenElse(
+                        Box::new(LTE(n(), Box::new(CInt(0)))),
+                        Box::new(Return(Box::new(CInt(0)))),
+                        None,
+                    )),
+                    Box::new(Return(Box::new(Add(
+                        Box::new(FuncCall(
+                            "depth".to_string(),
+                            vec![Sub(n(), Box::new(CInt(1)))],
+                        )),
+                        Box::new(CInt(1)),
+                    )))),
+                ))),
+            });
+            let program = Sequence(
+                Box::new(func),
+                Box::new(Assignment(
+                    "d".to_string(),
+                    Box::new(FuncCall("depth".to_string(), vec![CInt(40)])),
+                    Some(TInteger),
+                )),
+            );
+
+            match execute(program, &env) {
+                Ok(ControlFlow::Continue(new_env)) => match new_env.search_frame("d".to_string()) {
+                    Some(EnvValue::Exp(value)) => Some(value.clone()),
+                    _ => None,
+                },
+                _ => None,
+            }
+        };
+
+        let depth = std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(run)
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(depth, Some(CInt(40)));
+    }
+
+    #[test]
+    fn eval_reads_input() {
+        let mut env: Environment<EnvValue> = Environment::new();
+        env.io = Some(Rc::new(MemoryIo::with_input(vec![
+            "  ada  ", "42", "2.5", "x",
+        ])));
+
+        assert_eq!(
+            eval(ReadString, &env),
+            Ok(EnvValue::Exp(CString("ada".to_string())))
+        );
+        assert_eq!(eval(ReadInt, &env), Ok(EnvValue::Exp(CInt(42))));
+        assert_eq!(eval(ReadFloat, &env), Ok(EnvValue::Exp(CReal(2.5))));
+        assert_eq!(
+            eval(ReadInt, &env),
+            Err(Exception::new(
+                "ValueError",
+                String::from("invalid digit found in string")
+            ))
+        );
+        assert_eq!(
+            eval(ReadString, &env),
+            Err(Exception::new(
+                "EOFError",
+                String::from("end of input reached while reading a line.")
+            ))
+        );
+    }
+
+    #[test]
+    fn execute_prints_and_writes_through_io() {
+        let mut env: Environment<EnvValue> = Environment::new();
+        let io = Rc::new(MemoryIo::new());
+        io.insert_file("in.txt", "hello");
+        env.io = Some(io.clone());
+
+        /*
+         * > write_to_file("out.txt", read_file("in.txt"))
+         * > print(read_file("out.txt"))
+         * > print(3)
+         */
+        let program = Sequence(
+            Box::new(WriteToFile(
+                Box::new(CString("out.txt".to_string())),
+                Box::new(ReadFile(Box::new(CString("in.txt".to_string())))),
+            )),
+            Box::new(Sequence(
+                Box::new(Print(Box::new(ReadFile(Box::new(CString(
+                    "out.txt".to_string(),
+                )))))),
+                Box::new(Print(Box::new(CInt(3)))),
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(_)) => {
+                assert_eq!(io.output(), vec!["hello".to_string(), "3".to_string()]);
+                assert_eq!(io.file("out.txt"), Some("hello".to_string()));
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+
+        assert_eq!(
+            eval(ReadFile(Box::new(CString("missing.txt".to_string()))), &env),
+            Err(Exception::new(
+                "FileNotFoundError",
+                String::from("no such file: 'missing.txt'")
+            ))
+        );
+    }
+
+    #[test]
+    fn execute_denies_file_access_outside_policy() {
+        let mut env: Environment<EnvValue> = Environment::new();
+        let io = Rc::new(MemoryIo::new());
+        io.insert_file("/sandbox/in.txt", "data");
+        io.insert_file("/etc/passwd", "root");
+        env.io = Some(io.clone());
+        env.sandbox = Sandbox::with_policy(Policy {
+            roots: vec!["/sandbox".into()],
+            max_bytes_written: Some(8),
+            ..Policy::new()
+        });
+
+        let read = |path: &str| ReadFile(Box::new(CString(path.to_string())));
+        let write = |path: &str, content: &str| {
+            WriteToFile(
+                Box::new(CString(path.to_string())),
+                Box::new(CString(content.to_string())),
+            )
+        };
+
+        assert_eq!(
+            eval(read("/sandbox/in.txt"), &env),
+            Ok(EnvValue::Exp(CString("data".to_string())))
+        );
+        assert_eq!(
+            eval(read("/sandbox/../etc/passwd"), &env),
+            Err(Exception::new(
+                "SandboxError",
+                String::from("'/sandbox/../etc/passwd' is outside the allowed directories.")
+            ))
+        );
+
+        /*
+         * > write_to_file("/sandbox/out.txt", "hello")
+         * > try:
+         * >     s = read_file("/etc/passwd")
+         * > except SandboxError:
+         * >     s = "denied"
+         */
+        let program = Sequence(
+            Box::new(write("/sandbox/out.txt", "hello")),
+            Box::new(TryExcept(
+                Box::new(Assignment(
+                    "s".to_string(),
+                    Box::new(read("/etc/passwd")),
+                    None,
+                )),
+                vec![(
+                    "SandboxError".to_string(),
+                    None,
+                    Assignment(
+                        "s".to_string(),
+                        Box::new(CString("denied".to_string())),
+                        None,
+                    ),
+                )],
+                None,
+            )),
+        );
+
+        match execute(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("s".to_string()),
+                    Some(&EnvValue::Exp(CString("denied".to_string())))
+                );
+                assert_eq!(io.file("/sandbox/out.txt"), Some("hello".to_string()));
+                assert_eq!(new_env.sandbox.written(), 5);
+            }
+            Ok(_) => assert!(false),
             Err(s) => assert!(false, "{}", s),
         }
+
+        match execute(write("/sandbox/more.txt", "1234"), &env) {
+            Err(e) => assert_eq!(
+                e,
+                Exception::new(
+                    "SandboxError",
+                    String::from("writing 4 bytes to '/sandbox/more.txt' exceeds the limit of 8 bytes (5 already written).")
+                )
+            ),
+            Ok(_) => assert!(false),
+        }
+        assert_eq!(io.file("/sandbox/more.txt"), None);
+
+        env.sandbox = Sandbox::with_policy(Policy {
+            roots: vec!["/sandbox".into()],
+            read_only: true,
+            ..Policy::new()
+        });
+        match execute(write("/sandbox/out.txt", "bye"), &env) {
+            Err(e) => assert_eq!(
+                e,
+                Exception::new(
+                    "SandboxError",
+                    String::from("cannot write '/sandbox/out.txt' in read-only mode.")
+                )
+            ),
+            Ok(_) => assert!(false),
+        }
     }
 }
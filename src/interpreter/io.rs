@@ -0,0 +1,114 @@
+use crate::ir::ast::Environment;
+use crate::ir::io::Io;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+/*
+ * The backends of the interpreter's input, output and files. Programs run
+ * against the process by default; tests and hosts give them an in-memory
+ * backend instead.
+ */
+
+/* the backend an environment runs against, the process itself unless one was set */
+pub fn env_io<A>(env: &Environment<A>) -> Rc<dyn Io> {
+    env.io.clone().unwrap_or_else(|| Rc::new(StdIo))
+}
+
+/* the standard input and output of the process, and its filesystem */
+pub struct StdIo;
+
+impl Io for StdIo {
+    fn read_line(&self) -> std::io::Result<Option<String>> {
+        let mut line = String::new();
+
+        if std::io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end_matches(['\n', '\r']).to_string()))
+    }
+
+    fn write_line(&self, text: &str) -> std::io::Result<()> {
+        writeln!(std::io::stdout().lock(), "{}", text)
+    }
+
+    fn read_file(&self, path: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn write_file(&self, path: &str, content: &str) -> std::io::Result<()> {
+        std::fs::write(path, content)
+    }
+}
+
+/* scripted input lines, captured output lines and files kept by path */
+pub struct MemoryIo {
+    input: RefCell<VecDeque<String>>,
+    output: RefCell<Vec<String>>,
+    files: RefCell<HashMap<String, String>>,
+}
+
+impl Default for MemoryIo {
+    fn default() -> MemoryIo {
+        MemoryIo::new()
+    }
+}
+
+impl MemoryIo {
+    pub fn new() -> MemoryIo {
+        MemoryIo {
+            input: RefCell::new(VecDeque::new()),
+            output: RefCell::new(vec![]),
+            files: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_input(lines: Vec<&str>) -> MemoryIo {
+        let io = MemoryIo::new();
+        io.input
+            .borrow_mut()
+            .extend(lines.into_iter().map(String::from));
+        io
+    }
+
+    pub fn insert_file(&self, path: &str, content: &str) {
+        self.files
+            .borrow_mut()
+            .insert(path.to_string(), content.to_string());
+    }
+
+    /* the lines printed so far */
+    pub fn output(&self) -> Vec<String> {
+        self.output.borrow().clone()
+    }
+
+    pub fn file(&self, path: &str) -> Option<String> {
+        self.files.borrow().get(path).cloned()
+    }
+}
+
+impl Io for MemoryIo {
+    fn read_line(&self) -> std::io::Result<Option<String>> {
+        Ok(self.input.borrow_mut().pop_front())
+    }
+
+    fn write_line(&self, text: &str) -> std::io::Result<()> {
+        self.output.borrow_mut().push(text.to_string());
+        Ok(())
+    }
+
+    fn read_file(&self, path: &str) -> std::io::Result<String> {
+        self.file(path).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no such file: '{}'", path),
+            )
+        })
+    }
+
+    fn write_file(&self, path: &str, content: &str) -> std::io::Result<()> {
+        self.insert_file(path, content);
+        Ok(())
+    }
+}
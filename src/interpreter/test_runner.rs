@@ -0,0 +1,481 @@
+use crate::interpreter::interpreter::{execute, value_to_string, ControlFlow, EnvValue};
+use crate::ir::ast::{Environment, Expression, Function, Name, Statement};
+use crate::stdlib::builtins::interpreter_env;
+use std::fmt;
+
+pub struct TestReport {
+    pub outcomes: Vec<(Name, Result<(), String>)>,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> usize {
+        self.outcomes.iter().filter(|(_, res)| res.is_ok()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.outcomes.len() - self.passed()
+    }
+}
+
+impl fmt::Display for TestReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, outcome) in &self.outcomes {
+            match outcome {
+                Ok(()) => writeln!(f, "test {} ... ok", name)?,
+                Err(message) => writeln!(f, "test {} ... FAILED: {}", name, message)?,
+            }
+        }
+        write!(
+            f,
+            "test result: {} passed; {} failed",
+            self.passed(),
+            self.failed()
+        )
+    }
+}
+
+/* runs every top-level function named 'test_*'. The other top-level statements run again
+before each test, so no test sees the objects, output or modules another test touched */
+pub fn run_tests(program: Statement) -> TestReport {
+    let mut statements = vec![];
+    collect_statements(program, &mut statements);
+
+    let mut tests = vec![];
+    let mut setup = vec![];
+
+    for stmt in statements {
+        match stmt {
+            Statement::FuncDef(func) if func.name.starts_with("test_") => tests.push(func),
+            stmt => setup.push(stmt),
+        }
+    }
+
+    let outcomes = tests
+        .into_iter()
+        .map(|test| {
+            let outcome = match run_setup(setup.clone()) {
+                Ok(env) => run_test(test.clone(), env),
+                Err(message) => Err(message),
+            };
+            (test.name, outcome)
+        })
+        .collect();
+
+    TestReport { outcomes }
+}
+
+fn collect_statements(stmt: Statement, statements: &mut Vec<Statement>) {
+    match stmt {
+        Statement::Sequence(s1, s2) => {
+            collect_statements(*s1, statements);
+            collect_statements(*s2, statements);
+        }
+        stmt => statements.push(stmt),
+    }
+}
+
+fn run_setup(setup: Vec<Statement>) -> Result<Environment<EnvValue>, String> {
+    let mut env = interpreter_env();
+
+    for stmt in setup {
+        env = match execute(stmt, &env) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(ControlFlow::Raise(exception)) | Err(exception) => {
+                return Err(format!("setup failed: {}", exception))
+            }
+            Ok(ControlFlow::Return(_)) => {
+                return Err(String::from("setup failed: 'return' outside a function"))
+            }
+        };
+    }
+    Ok(env)
+}
+
+fn run_test(test: Function, mut env: Environment<EnvValue>) -> Result<(), String> {
+    if let Some(params) = &test.params {
+        if !params.is_empty() {
+            return Err(String::from("test functions must not take parameters"));
+        }
+    }
+
+    let body = match test.body.clone() {
+        Some(body) => body,
+        None => return Err(String::from("test function has no body")),
+    };
+
+    env.insert_frame(test);
+
+    /* a bare return ends a test, but returning a value, as '?' does, skips the rest of it */
+    match execute(*body, &env) {
+        Ok(ControlFlow::Raise(exception)) | Err(exception) => Err(exception.to_string()),
+        Ok(ControlFlow::Return(EnvValue::Exp(Expression::CNone)))
+        | Ok(ControlFlow::Continue(_)) => Ok(()),
+        Ok(ControlFlow::Return(value)) => Err(format!(
+            "test returned early with '{}'",
+            match value {
+                EnvValue::Exp(exp) => value_to_string(&exp).unwrap_or(format!("{:?}", exp)),
+                EnvValue::Func(func) => format!("{}()", func.name),
+                other => format!("{:?}", other),
+            }
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::ast::Class;
+    use crate::ir::ast::Expression::*;
+    use crate::ir::ast::Statement::*;
+    use crate::ir::ast::Type::*;
+
+    fn test_func(name: &str, body: Statement) -> Statement {
+        FuncDef(Function {
+            name: name.to_string(),
+            kind: None,
+            params: None,
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(body)),
+        })
+    }
+
+    #[test]
+    fn run_passing_and_failing_tests() {
+        /*
+         * > def double(n: TInteger) -> TInteger:
+         * >   return n * 2
+         * >
+         * > def test_double():
+         * >   assert double(2) == 4, "double(2) should be 4"
+         * >
+         * > def test_double_wrong():
+         * >   assert double(2) == 5, "double(2) should be 5"
+         * >
+         * > def helper():
+         * >   assert False, "not a test"
+         */
+
+        let double = FuncDef(Function {
+            name: "double".to_string(),
+            kind: Some(TInteger),
+            params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(Mul(
+                Box::new(Var("n".to_string())),
+                Box::new(CInt(2)),
+            ))))),
+        });
+        let test1 = test_func(
+            "test_double",
+            Assert(
+                Box::new(EQ(
+                    Box::new(FuncCall("double".to_string(), vec![CInt(2)])),
+                    Box::new(CInt(4)),
+                )),
+                Box::new(CString("double(2) should be 4".to_string())),
+            ),
+        );
+        let test2 = test_func(
+            "test_double_wrong",
+            Assert(
+                Box::new(EQ(
+                    Box::new(FuncCall("double".to_string(), vec![CInt(2)])),
+                    Box::new(CInt(5)),
+                )),
+                Box::new(CString("double(2) should be 5".to_string())),
+            ),
+        );
+        let helper = test_func(
+            "helper",
+            Assert(
+                Box::new(CFalse),
+                Box::new(CString("not a test".to_string())),
+            ),
+        );
+
+        let program = Sequence(
+            Box::new(double),
+            Box::new(Sequence(
+                Box::new(test1),
+                Box::new(Sequence(Box::new(test2), Box::new(helper))),
+            )),
+        );
+
+        let report = run_tests(program);
+
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.failed(), 1);
+        assert_eq!(
+            report.outcomes,
+            vec![
+                ("test_double".to_string(), Ok(())),
+                (
+                    "test_double_wrong".to_string(),
+                    Err("[AssertionError] double(2) should be 5".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "test test_double ... ok\n\
+             test test_double_wrong ... FAILED: [AssertionError] double(2) should be 5\n\
+             test result: 1 passed; 1 failed"
+        );
+    }
+
+    #[test]
+    fn run_tests_with_bare_return() {
+        /*
+         * > def test_early_return():
+         * >   if True:
+         * >     return
+         * >   assert False, "unreachable"
+         */
+
+        let test = test_func(
+            "test_early_return",
+            Sequence(
+                Box::new(IfThenElse(Box::new(CTrue), Box::new(BareReturn), None)),
+                Box::new(Assert(
+                    Box::new(CFalse),
+                    Box::new(CString("unreachable".to_string())),
+                )),
+            ),
+        );
+
+        let report = run_tests(test);
+
+        assert_eq!(
+            report.outcomes,
+            vec![("test_early_return".to_string(), Ok(()))]
+        );
+    }
+
+    #[test]
+    fn run_tests_with_runtime_errors() {
+        /*
+         * > def test_division():
+         * >   x: TInteger = 1 / 0
+         * >
+         * > def test_with_params(n: TInteger):
+         * >   assert True, "unreachable"
+         */
+
+        let test1 = test_func(
+            "test_division",
+            Assignment(
+                "x".to_string(),
+                Box::new(Div(Box::new(CInt(1)), Box::new(CInt(0)))),
+                Some(TInteger),
+            ),
+        );
+        let test2 = FuncDef(Function {
+            name: "test_with_params".to_string(),
+            kind: None,
+            params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Assert(
+                Box::new(CTrue),
+                Box::new(CString("unreachable".to_string())),
+            ))),
+        });
+
+        let report = run_tests(Sequence(Box::new(test1), Box::new(test2)));
+
+        assert_eq!(report.passed(), 0);
+        assert_eq!(
+            report.outcomes,
+            vec![
+                (
+                    "test_division".to_string(),
+                    Err("[ZeroDivisionError] division by zero in 'Div(CInt(1), CInt(0))' on 'test_division()'.".to_string())
+                ),
+                (
+                    "test_with_params".to_string(),
+                    Err("test functions must not take parameters".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn run_tests_after_setup() {
+        /*
+         * > limit = len("abc")
+         * >
+         * > def test_limit():
+         * >   limit = limit + 1
+         * >   assert limit == 4, "limit should be 4"
+         * >
+         * > def test_limit_unchanged():
+         * >   assert limit == 3, "limit should still be 3"
+         * >
+         * > def test_propagate():
+         * >   Nothing?
+         * >   assert False, "unreachable"
+         */
+
+        let setup = Assignment(
+            "limit".to_string(),
+            Box::new(FuncCall(
+                "len".to_string(),
+                vec![CString("abc".to_string())],
+            )),
+            None,
+        );
+        let test1 = test_func(
+            "test_limit",
+            Sequence(
+                Box::new(Assignment(
+                    "limit".to_string(),
+                    Box::new(Add(Box::new(Var("limit".to_string())), Box::new(CInt(1)))),
+                    None,
+                )),
+                Box::new(Assert(
+                    Box::new(EQ(Box::new(Var("limit".to_string())), Box::new(CInt(4)))),
+                    Box::new(CString("limit should be 4".to_string())),
+                )),
+            ),
+        );
+        let test2 = test_func(
+            "test_limit_unchanged",
+            Assert(
+                Box::new(EQ(Box::new(Var("limit".to_string())), Box::new(CInt(3)))),
+                Box::new(CString("limit should still be 3".to_string())),
+            ),
+        );
+        let test3 = test_func(
+            "test_propagate",
+            Sequence(
+                Box::new(ExprStmt(Box::new(Propagate(Box::new(CNothing))))),
+                Box::new(Assert(
+                    Box::new(CFalse),
+                    Box::new(CString("unreachable".to_string())),
+                )),
+            ),
+        );
+
+        let program = Sequence(
+            Box::new(setup),
+            Box::new(Sequence(
+                Box::new(test1),
+                Box::new(Sequence(Box::new(test2), Box::new(test3))),
+            )),
+        );
+
+        assert_eq!(
+            run_tests(program).outcomes,
+            vec![
+                ("test_limit".to_string(), Ok(())),
+                ("test_limit_unchanged".to_string(), Ok(())),
+                (
+                    "test_propagate".to_string(),
+                    Err("test returned early with 'Nothing'".to_string())
+                ),
+            ]
+        );
+
+        let failing_setup = Sequence(
+            Box::new(Raise(
+                "ValueError".to_string(),
+                Box::new(CString("no fixtures".to_string())),
+            )),
+            Box::new(test_func(
+                "test_anything",
+                Assert(
+                    Box::new(CTrue),
+                    Box::new(CString("unreachable".to_string())),
+                ),
+            )),
+        );
+
+        assert_eq!(
+            run_tests(failing_setup).outcomes,
+            vec![(
+                "test_anything".to_string(),
+                Err("setup failed: [ValueError] no fixtures".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn run_tests_with_fresh_objects() {
+        /*
+         * > class Box:
+         * >   n: TInteger
+         * >
+         * > b: Box = Box()
+         * > b.n = 0
+         * >
+         * > def test_a():
+         * >   b.n = 5
+         * >   assert b.n == 5, "b.n should be 5"
+         * >
+         * > def test_b():
+         * >   assert b.n == 0, "b.n should still be 0"
+         */
+
+        let class = ClassDef(Class {
+            name: "Box".to_string(),
+            parent: None,
+            fields: vec![("n".to_string(), TInteger)],
+            methods: vec![],
+        });
+        let setup = Sequence(
+            Box::new(Assignment(
+                "b".to_string(),
+                Box::new(New("Box".to_string(), vec![])),
+                Some(TClass("Box".to_string())),
+            )),
+            Box::new(FieldAssignment(
+                "b".to_string(),
+                "n".to_string(),
+                Box::new(CInt(0)),
+            )),
+        );
+        let field = || Box::new(FieldAccess(Box::new(Var("b".to_string())), "n".to_string()));
+        let test_a = test_func(
+            "test_a",
+            Sequence(
+                Box::new(FieldAssignment(
+                    "b".to_string(),
+                    "n".to_string(),
+                    Box::new(CInt(5)),
+                )),
+                Box::new(Assert(
+                    Box::new(EQ(field(), Box::new(CInt(5)))),
+                    Box::new(CString("b.n should be 5".to_string())),
+                )),
+            ),
+        );
+        let test_b = test_func(
+            "test_b",
+            Assert(
+                Box::new(EQ(field(), Box::new(CInt(0)))),
+                Box::new(CString("b.n should still be 0".to_string())),
+            ),
+        );
+
+        let program = Sequence(
+            Box::new(class),
+            Box::new(Sequence(
+                Box::new(setup),
+                Box::new(Sequence(Box::new(test_a), Box::new(test_b))),
+            )),
+        );
+
+        assert_eq!(
+            run_tests(program).outcomes,
+            vec![
+                ("test_a".to_string(), Ok(())),
+                ("test_b".to_string(), Ok(()))
+            ]
+        );
+    }
+}
@@ -1 +1,4 @@
 pub mod ast;
+pub mod io;
+pub mod module;
+pub mod sandbox;
@@ -1,82 +1,323 @@
 pub type Name = String;
 
-use std::collections::HashMap;
+/* each parameter with the argument bound to it, then the surplus for '*args' */
+pub type BoundArguments<T> = (Vec<(Name, Type, Option<T>)>, Vec<T>);
+
+/* the positional arguments of a call, then its keyword arguments */
+pub type CallArguments<T = Expression> = (Vec<T>, Vec<(Name, T)>);
+
+use crate::ir::io::Io;
+use crate::ir::module::{is_exported, MemoryLoader, Modules};
+use crate::ir::sandbox::{Denial, Sandbox};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct Frame<A> {
     pub parent_function: Option<Function>,
     pub parent_key: Option<(Name, i32)>,
     pub variables: HashMap<Name, A>,
+    /* names assigned on some paths only, which the type checker refuses to read */
+    pub unassigned: HashSet<Name>,
+    /* the declarations of the functions bound in this frame, for calls by keyword */
+    pub functions: HashMap<Name, Function>,
+    /* the functions the enclosing block defines, which the type checker lets the bodies
+    of its functions call before their definitions run */
+    pub declared: HashMap<Name, Function>,
 }
 
 impl<A> Frame<A> {
     pub fn new(func: Option<Function>, key: Option<(Name, i32)>) -> Frame<A> {
         let variables: HashMap<Name, A> = HashMap::new();
 
-        return Frame {
+        Frame {
             parent_function: func,
             parent_key: key,
             variables,
+            unassigned: HashSet::new(),
+            functions: HashMap::new(),
+            declared: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /* when set, dividing a real by zero is a runtime error instead of yielding 'inf' or 'NaN' */
+    pub strict_real_division: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            strict_real_division: true,
+        }
+    }
+}
+
+/* type inference state; every copy of an environment shares it, so bindings made
+while checking an expression outlive the environment the expression was checked in */
+#[derive(Clone)]
+pub struct Inference {
+    next_var: Rc<Cell<usize>>,
+    bindings: Rc<RefCell<HashMap<Name, Type>>>,
+    /* the type variables that only numbers may bind, as the operands of arithmetic */
+    numeric: Rc<RefCell<HashSet<Name>>>,
+    inferred: Rc<RefCell<Vec<(Name, Name, Type)>>>,
+}
+
+impl Default for Inference {
+    fn default() -> Inference {
+        Inference::new()
+    }
+}
+
+impl Inference {
+    pub fn new() -> Inference {
+        Inference {
+            next_var: Rc::new(Cell::new(0)),
+            bindings: Rc::new(RefCell::new(HashMap::new())),
+            numeric: Rc::new(RefCell::new(HashSet::new())),
+            inferred: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /* inference variables are named '?n', which no type variable written in a program can be */
+    pub fn fresh_var(&self) -> Type {
+        let id = self.next_var.get() + 1;
+        self.next_var.set(id);
+        Type::TVar(format!("?{}", id))
+    }
+
+    pub fn is_inference_var(name: &Name) -> bool {
+        name.starts_with('?')
+    }
+
+    pub fn binding(&self, var: &Name) -> Option<Type> {
+        self.bindings.borrow().get(var).cloned()
+    }
+
+    pub fn bind(&self, var: Name, kind: Type) {
+        self.bindings.borrow_mut().insert(var, kind);
+    }
+
+    pub fn constrain_numeric(&self, var: Name) {
+        self.numeric.borrow_mut().insert(var);
+    }
+
+    pub fn is_numeric(&self, var: &Name) -> bool {
+        self.numeric.borrow().contains(var)
+    }
+
+    /* remembers the type inferred for a name declared without annotation in a scope */
+    pub fn record(&self, scope: Name, name: Name, kind: Type) {
+        self.inferred.borrow_mut().push((scope, name, kind));
+    }
+
+    pub fn inferred(&self) -> Vec<(Name, Name, Type)> {
+        self.inferred.borrow().clone()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exception {
+    pub kind: Name,
+    pub message: String,
+}
+
+impl Exception {
+    pub fn new(kind: &str, message: String) -> Exception {
+        Exception {
+            kind: kind.to_string(),
+            message,
+        }
+    }
+
+    /* 'Exception' is the root of the hierarchy and handles every kind */
+    pub fn is_handled_by(&self, kind: &Name) -> bool {
+        kind == "Exception" || *kind == self.kind
+    }
+}
+
+impl fmt::Display for Exception {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.kind, self.message)
+    }
+}
+
+impl From<std::io::Error> for Exception {
+    fn from(error: std::io::Error) -> Exception {
+        let kind = match error.kind() {
+            std::io::ErrorKind::NotFound => "FileNotFoundError",
+            std::io::ErrorKind::PermissionDenied => "PermissionError",
+            _ => "IOError",
         };
+        Exception::new(kind, error.to_string())
+    }
+}
+
+/* a file operation the sandbox policy forbids */
+impl From<Denial> for Exception {
+    fn from(denial: Denial) -> Exception {
+        Exception::new("SandboxError", denial.to_string())
+    }
+}
+
+/* receives the argument values in parameter order, with the values a variadic parameter
+collects as a list at the end */
+pub type NativeFunction = Rc<dyn Fn(Vec<Expression>) -> Result<Expression, Exception>>;
+
+/* the native implementations of the functions without a body, by name; every copy of
+an environment shares them */
+#[derive(Clone)]
+pub struct Natives {
+    functions: Rc<RefCell<HashMap<Name, NativeFunction>>>,
+}
+
+impl Default for Natives {
+    fn default() -> Natives {
+        Natives::new()
+    }
+}
+
+impl Natives {
+    pub fn new() -> Natives {
+        Natives {
+            functions: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    pub fn insert(&self, name: Name, native: NativeFunction) {
+        self.functions.borrow_mut().insert(name, native);
+    }
+
+    pub fn search(&self, name: &Name) -> Option<NativeFunction> {
+        self.functions.borrow().get(name).cloned()
     }
 }
 
 #[derive(Clone)]
 pub struct Environment<A> {
-    pub scope: Function,
+    /* boxed, since environments are moved through every statement the interpreter runs */
+    pub scope: Box<Function>,
     pub recursion: i32,
     pub stack: HashMap<(Name, i32), Frame<A>>,
+    pub definitions: HashMap<Name, TypeDefinition>,
+    pub settings: Settings,
+    pub inference: Inference,
+    pub modules: Modules<A>,
+    pub natives: Natives,
+    /* where programs read input, print and keep files, the process itself when unset;
+    every copy of an environment shares it */
+    pub io: Option<Rc<dyn Io>>,
+    pub sandbox: Sandbox,
+}
+
+impl<A> Default for Environment<A> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<A> Environment<A> {
     pub fn new() -> Environment<A> {
         let frame: Frame<A> = Frame::new(None, None);
-        let scope = Function::new();
+        let scope = Box::new(Function::new());
 
-        return Environment {
+        Environment {
             scope,
             recursion: 0,
             stack: HashMap::from([(("__main__".to_string(), 0), frame)]),
-        };
+            definitions: HashMap::new(),
+            settings: Settings::new(),
+            inference: Inference::new(),
+            modules: Modules::new(Rc::new(MemoryLoader::new())),
+            natives: Natives::new(),
+            io: None,
+            sandbox: Sandbox::new(),
+        }
+    }
+
+    /* a top-level name of a module that programs importing it can use */
+    pub fn search_export(&self, name: &Name) -> Option<&A> {
+        if !is_exported(name) {
+            return None;
+        }
+        self.search_frame(name.clone())
+    }
+
+    /* binds a built-in function in the frame below the top level, where every program
+    and module finds it unless it binds the name itself */
+    pub fn insert_builtin(&mut self, declaration: Function, value: A) {
+        let key = builtins_key();
+        let top = self.scope_key();
+        let builtins = self
+            .stack
+            .entry(key.clone())
+            .or_insert_with(|| Frame::new(None, None));
+
+        builtins.variables.insert(declaration.name.clone(), value);
+        builtins
+            .functions
+            .insert(declaration.name.clone(), declaration);
+
+        if let Some(frame) = self.stack.get_mut(&top) {
+            frame.parent_key = Some(key);
+        }
+    }
+
+    /* the declarations of the built-in functions, including those the host registered */
+    pub fn builtin_functions(&self) -> Vec<Function> {
+        match self.stack.get(&builtins_key()) {
+            Some(frame) => frame.functions.values().cloned().collect(),
+            None => vec![],
+        }
     }
 
     pub fn scope_key(&self) -> (Name, i32) {
-        return (self.scope_name(), self.recursion);
+        (self.scope_name(), self.recursion)
     }
 
     pub fn scope_name(&self) -> Name {
-        return self.scope.name.clone();
+        self.scope.name.clone()
     }
 
     pub fn scope_return(&self) -> Option<&A> {
-        return self.search_frame(self.scope_name());
+        self.search_frame(self.scope_name())
     }
 
     pub fn get_frame(&self, key: (Name, i32)) -> &Frame<A> {
-        return self.stack.get(&key).unwrap();
+        self.stack.get(&key).unwrap()
     }
 
     pub fn search_frame(&self, name: Name) -> Option<&A> {
-        return self
-            .stack
+        self.stack
             .get(&self.scope_key())
             .unwrap()
             .variables
-            .get(&name);
+            .get(&name)
     }
 
-    pub fn insert_frame(&mut self, func: Function) -> () {
-        let new_frame: Frame<A> = Frame::new(Some(self.scope.clone()), Some(self.scope_key()));
+    pub fn insert_frame(&mut self, func: Function) {
+        let new_frame: Frame<A> = Frame::new(Some(*self.scope.clone()), Some(self.scope_key()));
 
         self.stack
             .insert((func.name.clone(), self.scope_key().1 + 1), new_frame);
-        self.scope = func;
+        *self.scope = func;
         self.recursion += 1;
     }
 
-    pub fn remove_frame(&mut self) -> () {
+    pub fn remove_frame(&mut self) {
         let recursion = self.scope_key().1 - 1;
-        self.scope = self
+        *self.scope = self
             .stack
             .remove(&self.scope_key())
             .unwrap()
@@ -85,11 +326,164 @@ impl<A> Environment<A> {
         self.recursion = recursion;
     }
 
-    pub fn insert_variable(&mut self, name: Name, kind: A) -> () {
+    pub fn insert_variable(&mut self, name: Name, kind: A) {
         if let Some(frame) = self.stack.get_mut(&self.scope_key()) {
+            frame.functions.remove(&name);
             frame.variables.insert(name, kind);
         }
     }
+
+    /* records the declaration of a function, after its name is bound as a variable */
+    pub fn insert_function(&mut self, func: Function) {
+        if let Some(frame) = self.stack.get_mut(&self.scope_key()) {
+            frame.functions.insert(func.name.clone(), func);
+        }
+    }
+
+    /* the declaration of the function a name refers to, unless the name was rebound */
+    pub fn search_function(&self, name: &Name) -> Option<&Function> {
+        let mut frame = self.stack.get(&self.scope_key())?;
+
+        loop {
+            if frame.variables.contains_key(name) {
+                return frame.functions.get(name);
+            }
+            frame = self.stack.get(frame.parent_key.as_ref()?)?;
+        }
+    }
+
+    pub fn insert_unassigned(&mut self, name: Name) {
+        if let Some(frame) = self.stack.get_mut(&self.scope_key()) {
+            frame.unassigned.insert(name);
+        }
+    }
+
+    pub fn search_definition(&self, name: &Name) -> Option<&TypeDefinition> {
+        self.definitions.get(name)
+    }
+
+    pub fn insert_definition(&mut self, name: Name, definition: TypeDefinition) {
+        self.definitions.insert(name, definition);
+    }
+
+    /* finds the algebraic data type declaring a constructor, along with its argument types */
+    pub fn search_constructor(&self, name: &Name) -> Option<(Name, Vec<Type>)> {
+        for (adt_name, definition) in &self.definitions {
+            if let TypeDefinition::Adt(constructors) = definition {
+                if let Some((_, types)) = constructors.iter().find(|(cons, _)| cons == name) {
+                    return Some((adt_name.clone(), types.clone()));
+                }
+            }
+        }
+        None
+    }
+
+    /* expands aliases at the top of a type until it names no alias; a recursive alias is
+    left unexpanded, since the type checker rejects it where it is declared */
+    pub fn expand_alias(&self, kind: &Type) -> Type {
+        let mut kind = kind.clone();
+        let mut seen = vec![];
+
+        while let Type::TAlias(name) = &kind {
+            match self.definitions.get(name) {
+                Some(TypeDefinition::Alias(target)) if !seen.contains(name) => {
+                    seen.push(name.clone());
+                    kind = target.clone();
+                }
+                _ => break,
+            }
+        }
+        kind
+    }
+
+    pub fn search_class(&self, name: &Name) -> Option<&Class> {
+        match self.definitions.get(name) {
+            Some(TypeDefinition::Class(class)) => Some(class),
+            _ => None,
+        }
+    }
+
+    /* fields of a class, inherited ones first */
+    pub fn class_fields(&self, name: &Name) -> Vec<(Name, Type)> {
+        match self.search_class(name) {
+            Some(class) => {
+                let mut fields = match &class.parent {
+                    Some(parent) => self.class_fields(parent),
+                    None => vec![],
+                };
+                fields.extend(class.fields.clone());
+                fields
+            }
+            None => vec![],
+        }
+    }
+
+    /* finds a method on a class or its nearest ancestor that defines it */
+    pub fn search_method(&self, class: &Name, method: &Name) -> Option<Function> {
+        let mut current = self.search_class(class);
+
+        while let Some(class) = current {
+            if let Some(func) = class.methods.iter().find(|func| func.name == *method) {
+                return Some(func.clone());
+            }
+            current = class
+                .parent
+                .as_ref()
+                .and_then(|parent| self.search_class(parent));
+        }
+        None
+    }
+
+    pub fn is_subclass(&self, class: &Name, ancestor: &Name) -> bool {
+        let mut current = Some(class.clone());
+
+        while let Some(name) = current {
+            if name == *ancestor {
+                return true;
+            }
+            current = self
+                .search_class(&name)
+                .and_then(|class| class.parent.clone());
+        }
+        false
+    }
+}
+
+impl<A: Clone> Environment<A> {
+    /* a fresh top-level environment for a module, whose scope takes the name of the
+    module and which shares the built-in functions and the state of the run */
+    pub fn for_module(&self, name: &Name) -> Environment<A> {
+        let mut frame: Frame<A> = Frame::new(None, None);
+        let mut stack = HashMap::new();
+
+        if let Some(builtins) = self.stack.get(&builtins_key()) {
+            frame.parent_key = Some(builtins_key());
+            stack.insert(builtins_key(), builtins.clone());
+        }
+        stack.insert((name.clone(), 0), frame);
+
+        let scope = Box::new(Function {
+            name: name.clone(),
+            ..Function::new()
+        });
+
+        Environment {
+            scope,
+            recursion: 0,
+            stack,
+            definitions: HashMap::new(),
+            settings: self.settings.clone(),
+            inference: self.inference.clone(),
+            modules: self.modules.clone(),
+            natives: self.natives.clone(),
+            io: self.io.clone(),
+            sandbox: self.sandbox.clone(),
+        }
+    }
+}
+
+fn builtins_key() -> (Name, i32) {
+    ("__builtins__".to_string(), -1)
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -97,18 +491,131 @@ pub struct Function {
     pub name: Name,
     pub kind: Option<Type>,
     pub params: Option<Vec<(Name, Type)>>,
+    /* default values of trailing parameters, by parameter name */
+    pub defaults: Vec<(Name, Expression)>,
+    /* a '*args' parameter collecting extra positional arguments of the given type */
+    pub variadic: Option<(Name, Type)>,
+    /* the module defining an imported function, whose top-level names its body reads */
+    pub module: Option<Name>,
     pub body: Option<Box<Statement>>,
 }
 
+impl Default for Function {
+    fn default() -> Function {
+        Function::new()
+    }
+}
+
 impl Function {
     pub fn new() -> Function {
-        return Function {
+        Function {
             name: "__main__".to_string(),
             kind: None,
             params: None,
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: None,
-        };
+        }
+    }
+
+    /* the type of the function, as a value of its name has it */
+    pub fn function_type(&self) -> Type {
+        let params = self.params.clone().unwrap_or_default();
+
+        Type::TFunction(
+            Box::new(self.kind.clone()),
+            params.into_iter().map(|(_, kind)| kind).collect(),
+        )
+    }
+
+    /* binds the arguments of a call to the parameters, positional ones in order and
+    then keyword ones by name. A parameter left unbound is paired with 'None' and takes
+    its default; extra positional arguments are returned for the variadic parameter */
+    pub fn bind_arguments<T>(
+        &self,
+        positional: Vec<T>,
+        keywords: Vec<(Name, T)>,
+    ) -> Result<BoundArguments<T>, String> {
+        let found = positional.len() + keywords.len();
+        let exact = self.defaults.is_empty() && self.variadic.is_none();
+
+        let mut positional = positional.into_iter();
+        let mut bound: Vec<(Name, Type, Option<T>)> = self
+            .params
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, kind)| (name, kind, positional.next()))
+            .collect();
+        let extra: Vec<T> = positional.collect();
+
+        if !extra.is_empty() && self.variadic.is_none() {
+            return Err(format!(
+                "'{}()' expected {}{} arguments, found {}.",
+                self.name,
+                if exact { "" } else { "at most " },
+                bound.len(),
+                found
+            ));
+        }
+
+        for (keyword, arg) in keywords {
+            match bound.iter_mut().find(|(name, _, _)| *name == keyword) {
+                Some((_, _, Some(_))) => {
+                    return Err(format!(
+                        "'{}()' got multiple values for argument '{}'.",
+                        self.name, keyword
+                    ))
+                }
+                Some((_, _, slot)) => *slot = Some(arg),
+                None => {
+                    return Err(format!(
+                        "'{}()' got an unexpected keyword argument '{}'.",
+                        self.name, keyword
+                    ))
+                }
+            }
+        }
+
+        for (name, _, arg) in &bound {
+            if arg.is_none() && !self.defaults.iter().any(|(param, _)| param == name) {
+                return Err(match exact {
+                    true => format!(
+                        "'{}()' expected {} arguments, found {}.",
+                        self.name,
+                        bound.len(),
+                        found
+                    ),
+                    false => format!("'{}()' missing required argument '{}'.", self.name, name),
+                });
+            }
+        }
+
+        Ok((bound, extra))
+    }
+}
+
+/* separates the positional arguments of a call from its keyword arguments, which must
+come after them */
+pub fn split_arguments(name: &Name, args: Vec<Expression>) -> Result<CallArguments, String> {
+    let mut positional = vec![];
+    let mut keywords = vec![];
+
+    for arg in args {
+        match arg {
+            Expression::KeywordArg(keyword, arg) => keywords.push((keyword, *arg)),
+            arg if keywords.is_empty() => positional.push(arg),
+            _ => {
+                return Err(format!(
+                    "positional argument follows keyword argument in call to '{}()'.",
+                    name
+                ))
+            }
+        }
     }
+
+    Ok((positional, keywords))
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -120,6 +627,48 @@ pub enum Type {
     TFunction(Box<Option<Type>>, Vec<Type>),
     TList(Box<Type>),
     TTuple(Vec<Type>),
+    TRecord(Name),
+    TAdt(Name),
+    TClass(Name),
+    /* the type of a name bound by 'import', whose attributes are the exports of the module */
+    TModule(Name),
+    /* a reference to a type alias, kept unexpanded so diagnostics can name it */
+    TAlias(Name),
+    TMaybe(Box<Type>),
+    TResult(Box<Type>, Box<Type>),
+    /* a type variable makes the function whose signature mentions it generic */
+    TVar(Name),
+    /* a part of a type that is not determined by its expression, like the value type of 'Nothing' */
+    TUnknown,
+    /* a dynamically typed value, checked when it flows into a statically typed position */
+    TAny,
+    /* the type of 'None', which procedures return */
+    TUnit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeDefinition {
+    Record(Vec<(Name, Type)>),
+    Adt(Vec<(Name, Vec<Type>)>),
+    Class(Class),
+    Alias(Type),
+}
+
+/* the initializer is the method named '__init__'; every method takes 'self' first */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Class {
+    pub name: Name,
+    pub parent: Option<Name>,
+    pub fields: Vec<(Name, Type)>,
+    pub methods: Vec<Function>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Pattern {
+    Wildcard,
+    Binding(Name),
+    Literal(Expression),
+    Constructor(Name, Vec<Pattern>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -130,12 +679,34 @@ pub enum Expression {
     CInt(i32),
     CReal(f64),
     CString(String),
+    CNone,
+    /* a list of values, like the extra arguments collected by a variadic parameter */
+    CList(Vec<Expression>),
+
+    /* built-in 'Maybe' and 'Result' constructors */
+    CJust(Box<Expression>),
+    CNothing,
+    COk(Box<Expression>),
+    CErr(Box<Expression>),
 
     /* variable reference */
     Var(Name),
 
-    /* function call */
+    /* function call, whose keyword arguments follow the positional ones */
     FuncCall(Name, Vec<Expression>),
+    KeywordArg(Name, Box<Expression>),
+
+    /* record construction and field access */
+    Record(Name, Vec<(Name, Expression)>),
+    FieldAccess(Box<Expression>, Name),
+
+    /* algebraic data type construction and pattern matching */
+    Constructor(Name, Vec<Expression>),
+    MatchExp(Box<Expression>, Vec<(Pattern, Expression)>),
+
+    /* object instantiation and method call */
+    New(Name, Vec<Expression>),
+    MethodCall(Box<Expression>, Name, Vec<Expression>),
 
     /* arithmetic expressions over numbers */
     Add(Box<Expression>, Box<Expression>),
@@ -148,13 +719,27 @@ pub enum Expression {
     Or(Box<Expression>, Box<Expression>),
     Not(Box<Expression>),
 
-    /* relational expressions over numbers */
+    /* equality expressions over comparable values */
     EQ(Box<Expression>, Box<Expression>),
+    NEQ(Box<Expression>, Box<Expression>),
+
+    /* relational expressions over numbers */
     GT(Box<Expression>, Box<Expression>),
     LT(Box<Expression>, Box<Expression>),
     GTE(Box<Expression>, Box<Expression>),
     LTE(Box<Expression>, Box<Expression>),
 
+    /* helpers over 'Maybe' and 'Result' values */
+    IsJust(Box<Expression>),
+    IsNothing(Box<Expression>),
+    IsOk(Box<Expression>),
+    IsErr(Box<Expression>),
+    Unwrap(Box<Expression>),
+    UnwrapOr(Box<Expression>, Box<Expression>),
+
+    /* early-returns 'Nothing' or 'Err(e)' from the enclosing function, otherwise unwraps */
+    Propagate(Box<Expression>),
+
     ReadFile(Box<Expression>),
     ReadString,
     ReadInt,
@@ -167,11 +752,62 @@ pub enum Statement {
     VarDeclaration(Name),
     ValDeclaration(Name),
     Assignment(Name, Box<Expression>, Option<Type>),
+    FieldAssignment(Name, Name, Box<Expression>),
+    ChainedAssignment(Vec<Name>, Box<Expression>, Option<Type>),
+    AddAssignment(Name, Box<Expression>),
+    SubAssignment(Name, Box<Expression>),
+    MulAssignment(Name, Box<Expression>),
+    DivAssignment(Name, Box<Expression>),
     IfThenElse(Box<Expression>, Box<Statement>, Option<Box<Statement>>),
     While(Box<Expression>, Box<Statement>),
     Sequence(Box<Statement>, Box<Statement>),
     FuncDef(Function),
+    RecordDef(Name, Vec<(Name, Type)>),
+    AdtDef(Name, Vec<(Name, Vec<Type>)>),
+    ClassDef(Class),
+    TypeAlias(Name, Type),
+    /* 'import module' binds the module, 'from module import a, b' binds the names it exports */
+    Import(Name),
+    FromImport(Name, Vec<Name>),
+    Match(Box<Expression>, Vec<(Pattern, Statement)>),
     Return(Box<Expression>),
+    BareReturn,
+    /* evaluates an expression, like a procedure call, for its effects only */
+    ExprStmt(Box<Expression>),
     WriteToFile(Box<Expression>, Box<Expression>),
     Print(Box<Expression>),
+    Raise(Name, Box<Expression>),
+    Assert(Box<Expression>, Box<Expression>),
+    TryExcept(
+        Box<Statement>,
+        Vec<(Name, Option<Name>, Statement)>,
+        Option<Box<Statement>>,
+    ),
+}
+
+impl Statement {
+    /* rewrites an augmented assignment 'x op= e' as 'x = x op e' */
+    pub fn expand_augmented_assignment(self) -> Statement {
+        let (name, exp) = match self {
+            Statement::AddAssignment(name, exp) => {
+                let var = Box::new(Expression::Var(name.clone()));
+                (name, Expression::Add(var, exp))
+            }
+            Statement::SubAssignment(name, exp) => {
+                let var = Box::new(Expression::Var(name.clone()));
+                (name, Expression::Sub(var, exp))
+            }
+            Statement::MulAssignment(name, exp) => {
+                let var = Box::new(Expression::Var(name.clone()));
+                (name, Expression::Mul(var, exp))
+            }
+            Statement::DivAssignment(name, exp) => {
+                let var = Box::new(Expression::Var(name.clone()));
+                (name, Expression::Div(var, exp))
+            }
+            stmt => return stmt,
+        };
+
+        Statement::Assignment(name, Box::new(exp), None)
+    }
 }
@@ -0,0 +1,12 @@
+/*
+ * Where programs read input, print output and keep files. The interpreter
+ * supplies the backends.
+ */
+
+pub trait Io {
+    /* the next line of input without its line break, or 'None' at the end of input */
+    fn read_line(&self) -> std::io::Result<Option<String>>;
+    fn write_line(&self, text: &str) -> std::io::Result<()>;
+    fn read_file(&self, path: &str) -> std::io::Result<String>;
+    fn write_file(&self, path: &str, content: &str) -> std::io::Result<()>;
+}
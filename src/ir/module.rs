@@ -0,0 +1,266 @@
+use crate::ir::ast::{Environment, Name, Statement};
+use crate::ir::sandbox::Sandbox;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/*
+ * A module is one file holding a program, whose top-level names other
+ * programs import. There is no parser yet, so a loader yields the
+ * statement tree of a module from its name.
+ */
+
+pub trait ModuleLoader {
+    /* the program of a module, or 'None' when no module has that name. A loader that
+    reads files reads only those the sandbox of the run allows */
+    fn load(&self, name: &Name, sandbox: &Sandbox) -> Result<Option<Statement>, String>;
+}
+
+/* modules given as statement trees, for tests and for hosts that build programs */
+pub struct MemoryLoader {
+    modules: HashMap<Name, Statement>,
+}
+
+impl Default for MemoryLoader {
+    fn default() -> MemoryLoader {
+        MemoryLoader::new()
+    }
+}
+
+impl MemoryLoader {
+    pub fn new() -> MemoryLoader {
+        MemoryLoader {
+            modules: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, name: &str, program: Statement) {
+        self.modules.insert(name.to_string(), program);
+    }
+}
+
+impl ModuleLoader for MemoryLoader {
+    fn load(&self, name: &Name, _sandbox: &Sandbox) -> Result<Option<Statement>, String> {
+        Ok(self.modules.get(name).cloned())
+    }
+}
+
+/* finds module 'a.b' as the file 'a/b.rpy' under the first directory of the search
+path that has it, and turns its source into a program with the parse hook */
+pub struct FileLoader {
+    pub search_path: Vec<PathBuf>,
+    pub parse: fn(&str) -> Result<Statement, String>,
+}
+
+impl FileLoader {
+    pub fn new(search_path: Vec<PathBuf>, parse: fn(&str) -> Result<Statement, String>) -> Self {
+        FileLoader { search_path, parse }
+    }
+
+    pub fn resolve(&self, name: &Name) -> Option<PathBuf> {
+        let relative: PathBuf = name.split('.').collect();
+        let relative = relative.with_extension("rpy");
+
+        self.search_path
+            .iter()
+            .map(|root| root.join(&relative))
+            .find(|path| path.is_file())
+    }
+}
+
+impl ModuleLoader for FileLoader {
+    fn load(&self, name: &Name, sandbox: &Sandbox) -> Result<Option<Statement>, String> {
+        let path = match self.resolve(name) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        sandbox
+            .check_read(&path.to_string_lossy())
+            .map_err(|denial| {
+                format!(
+                    "cannot read module '{}': {}",
+                    name,
+                    denial.to_string().trim_end_matches('.')
+                )
+            })?;
+
+        let source = std::fs::read_to_string(&path)
+            .map_err(|error| format!("cannot read module '{}': {}", name, error))?;
+
+        (self.parse)(&source)
+            .map(Some)
+            .map_err(|error| format!("cannot parse module '{}': {}", name, error))
+    }
+}
+
+/* the modules of a run; every copy of an environment shares them, so a module is
+checked or executed once however many programs import it */
+pub struct Modules<A> {
+    loader: Rc<dyn ModuleLoader>,
+    /* the top-level environment each module ended with */
+    loaded: Rc<RefCell<HashMap<Name, Environment<A>>>>,
+    /* the modules being loaded, each imported by the one before it */
+    loading: Rc<RefCell<Vec<Name>>>,
+}
+
+/* derived 'Clone' would require the values to be cloneable, though only the handles are cloned */
+impl<A> Clone for Modules<A> {
+    fn clone(&self) -> Modules<A> {
+        Modules {
+            loader: self.loader.clone(),
+            loaded: self.loaded.clone(),
+            loading: self.loading.clone(),
+        }
+    }
+}
+
+impl<A> Modules<A> {
+    pub fn new(loader: Rc<dyn ModuleLoader>) -> Modules<A> {
+        Modules {
+            loader,
+            loaded: Rc::new(RefCell::new(HashMap::new())),
+            loading: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    pub fn load(&self, name: &Name, sandbox: &Sandbox) -> Result<Option<Statement>, String> {
+        self.loader.load(name, sandbox)
+    }
+
+    /* the same modules for another pass over the programs, which loads each one again */
+    pub fn with_same_loader<B>(&self) -> Modules<B> {
+        Modules::new(self.loader.clone())
+    }
+
+    /* marks a module as being loaded, or returns the import cycle that reaches it again */
+    pub fn begin(&self, name: &Name) -> Result<(), Vec<Name>> {
+        let mut loading = self.loading.borrow_mut();
+
+        if let Some(start) = loading.iter().position(|module| module == name) {
+            let mut cycle = loading[start..].to_vec();
+            cycle.push(name.clone());
+            return Err(cycle);
+        }
+        loading.push(name.clone());
+        Ok(())
+    }
+
+    /* records the environment a module ended with, or nothing when loading it failed */
+    pub fn finish(&self, name: &Name, env: Option<Environment<A>>) {
+        self.loading.borrow_mut().retain(|module| module != name);
+
+        if let Some(env) = env {
+            self.loaded.borrow_mut().insert(name.clone(), env);
+        }
+    }
+}
+
+impl<A: Clone> Modules<A> {
+    pub fn search(&self, name: &Name) -> Option<Environment<A>> {
+        self.loaded.borrow().get(name).cloned()
+    }
+}
+
+/* names starting with an underscore are private to their module */
+pub fn is_exported(name: &Name) -> bool {
+    !name.starts_with('_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::ast::Expression::*;
+    use crate::ir::ast::Statement::*;
+    use crate::ir::ast::Type;
+    use crate::ir::sandbox::Policy;
+
+    fn parse_source(source: &str) -> Result<Statement, String> {
+        Ok(Assignment(
+            "source".to_string(),
+            Box::new(CString(source.to_string())),
+            None,
+        ))
+    }
+
+    #[test]
+    fn file_loader_searches_path_in_order() {
+        let root = std::env::temp_dir().join(format!("rpy_modules_{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+
+        std::fs::create_dir_all(second.join("pkg")).unwrap();
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::write(second.join("pkg").join("util.rpy"), "util").unwrap();
+
+        let loader = FileLoader::new(vec![first, second.clone()], parse_source);
+
+        assert_eq!(
+            loader.resolve(&"pkg.util".to_string()),
+            Some(second.join("pkg").join("util.rpy"))
+        );
+        assert_eq!(
+            loader.load(&"pkg.util".to_string(), &Sandbox::new()),
+            Ok(Some(parse_source("util").unwrap()))
+        );
+        assert_eq!(
+            loader.load(&"pkg.missing".to_string(), &Sandbox::new()),
+            Ok(None)
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn file_loader_reads_only_inside_the_sandbox() {
+        let root = std::env::temp_dir().join(format!("rpy_sandboxed_{}", std::process::id()));
+        let (allowed, denied) = (root.join("allowed"), root.join("denied"));
+
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&denied).unwrap();
+        std::fs::write(allowed.join("inside.rpy"), "inside").unwrap();
+        std::fs::write(denied.join("outside.rpy"), "outside").unwrap();
+
+        let loader = FileLoader::new(vec![allowed.clone(), denied.clone()], parse_source);
+        let sandbox = Sandbox::with_policy(Policy {
+            roots: vec![allowed],
+            ..Policy::new()
+        });
+        let outside = denied.join("outside.rpy");
+
+        assert_eq!(
+            loader.load(&"inside".to_string(), &sandbox),
+            Ok(Some(parse_source("inside").unwrap()))
+        );
+        assert_eq!(
+            loader.load(&"outside".to_string(), &sandbox),
+            Err(format!(
+                "cannot read module 'outside': '{}' is outside the allowed directories",
+                outside.display()
+            ))
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn modules_detect_import_cycles() {
+        let modules: Modules<Type> = Modules::new(Rc::new(MemoryLoader::new()));
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+
+        assert_eq!(modules.begin(&a), Ok(()));
+        assert_eq!(modules.begin(&b), Ok(()));
+        assert_eq!(modules.begin(&c), Ok(()));
+        assert_eq!(
+            modules.begin(&b),
+            Err(vec![b.clone(), c.clone(), b.clone()])
+        );
+
+        modules.finish(&c, None);
+        modules.finish(&b, Some(Environment::new()));
+
+        assert!(modules.search(&b).is_some());
+        assert!(modules.search(&c).is_none());
+        assert_eq!(modules.begin(&c), Ok(()));
+    }
+}
@@ -0,0 +1,238 @@
+use std::cell::Cell;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+/*
+ * An execution policy for untrusted programs: the directories whose files
+ * they may read and write, whether they may write at all, and how many bytes
+ * they may write in total. The interpreter denies file operations the policy
+ * forbids, and the type checker can reject those it forbids on every run.
+ */
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    /* the directories holding every file a program may access */
+    pub roots: Vec<PathBuf>,
+    pub read_only: bool,
+    pub max_bytes_written: Option<usize>,
+    /* when set, the type checker rejects file operations whose constant arguments the
+    policy denies */
+    pub check_statically: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy::new()
+    }
+}
+
+impl Policy {
+    /* no file access at all until roots are allowed */
+    pub fn new() -> Policy {
+        Policy {
+            roots: vec![],
+            read_only: false,
+            max_bytes_written: None,
+            check_statically: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Denial {
+    OutsideRoots(String),
+    ReadOnly(String),
+    WriteLimit {
+        path: String,
+        requested: usize,
+        written: usize,
+        limit: usize,
+    },
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Denial::OutsideRoots(path) => {
+                write!(f, "'{}' is outside the allowed directories.", path)
+            }
+            Denial::ReadOnly(path) => write!(f, "cannot write '{}' in read-only mode.", path),
+            Denial::WriteLimit {
+                path,
+                requested,
+                written,
+                limit,
+            } => write!(
+                f,
+                "writing {} bytes to '{}' exceeds the limit of {} bytes ({} already written).",
+                requested, path, limit, written
+            ),
+        }
+    }
+}
+
+/* the policy of a run, if any, and the bytes written so far; every copy of an
+environment shares the count */
+#[derive(Clone, Debug)]
+pub struct Sandbox {
+    pub policy: Option<Policy>,
+    written: Rc<Cell<usize>>,
+}
+
+impl Default for Sandbox {
+    fn default() -> Sandbox {
+        Sandbox::new()
+    }
+}
+
+impl Sandbox {
+    /* programs may access any file */
+    pub fn new() -> Sandbox {
+        Sandbox {
+            policy: None,
+            written: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub fn with_policy(policy: Policy) -> Sandbox {
+        Sandbox {
+            policy: Some(policy),
+            ..Sandbox::new()
+        }
+    }
+
+    /* the policy the type checker enforces, if it enforces one */
+    pub fn static_policy(&self) -> Option<&Policy> {
+        self.policy
+            .as_ref()
+            .filter(|policy| policy.check_statically)
+    }
+
+    pub fn written(&self) -> usize {
+        self.written.get()
+    }
+
+    pub fn check_read(&self, path: &str) -> Result<(), Denial> {
+        match &self.policy {
+            Some(policy) => check_roots(policy, path),
+            None => Ok(()),
+        }
+    }
+
+    /* checks writing 'bytes' more bytes, which the caller records once they are written */
+    pub fn check_write(&self, path: &str, bytes: usize) -> Result<(), Denial> {
+        match &self.policy {
+            Some(policy) => check_write(policy, path, bytes, self.written()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_write(&self, bytes: usize) {
+        self.written.set(self.written.get() + bytes);
+    }
+}
+
+pub fn check_roots(policy: &Policy, path: &str) -> Result<(), Denial> {
+    let resolved = resolve(Path::new(path));
+
+    if policy
+        .roots
+        .iter()
+        .any(|root| resolved.starts_with(resolve(root)))
+    {
+        return Ok(());
+    }
+    Err(Denial::OutsideRoots(path.to_string()))
+}
+
+pub fn check_write(
+    policy: &Policy,
+    path: &str,
+    bytes: usize,
+    written: usize,
+) -> Result<(), Denial> {
+    if policy.read_only {
+        return Err(Denial::ReadOnly(path.to_string()));
+    }
+    check_roots(policy, path)?;
+
+    match policy.max_bytes_written {
+        Some(limit) if written + bytes > limit => Err(Denial::WriteLimit {
+            path: path.to_string(),
+            requested: bytes,
+            written,
+            limit,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/* the absolute path without '.' and '..' components, following the symbolic links of
+the part that exists, so no spelling of a path escapes the roots */
+fn resolve(path: &Path) -> PathBuf {
+    let absolute = match std::env::current_dir() {
+        Ok(dir) => dir.join(path),
+        Err(_) => path.to_path_buf(),
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    let mut existing = normalized.as_path();
+    let mut missing = vec![];
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return normalized,
+        }
+    }
+
+    let mut resolved = existing
+        .canonicalize()
+        .unwrap_or_else(|_| existing.to_path_buf());
+    resolved.extend(missing.into_iter().rev());
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_resolve_before_checking_roots() {
+        let root = std::env::temp_dir().join(format!("rpy_sandbox_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("data")).unwrap();
+
+        let policy = Policy {
+            roots: vec![root.join("data")],
+            ..Policy::new()
+        };
+        let inside = root.join("data").join("new").join("out.txt");
+        let escape = root.join("data").join("..").join("secret.txt");
+        let escape = escape.to_str().unwrap();
+
+        assert_eq!(check_roots(&policy, inside.to_str().unwrap()), Ok(()));
+        assert_eq!(
+            check_roots(&policy, escape),
+            Err(Denial::OutsideRoots(escape.to_string()))
+        );
+        assert_eq!(
+            check_roots(&Policy::new(), inside.to_str().unwrap()),
+            Err(Denial::OutsideRoots(inside.to_str().unwrap().to_string()))
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -7,6 +7,7 @@ use ir::ast::{Environment, Expression, Statement};
 
 pub mod interpreter;
 pub mod ir;
+pub mod stdlib;
 pub mod tc;
 
 fn main() -> Result<(), String> {
@@ -27,6 +28,7 @@ fn main() -> Result<(), String> {
     match execute(print_stmt, &exec_env) {
         Ok(ControlFlow::Continue(_)) => println!("Print statement executed successfully"),
         Ok(ControlFlow::Return(_)) => println!("Unexpected return from print statement"),
+        Ok(ControlFlow::Raise(e)) => return Err(format!("Uncaught exception: {}", e)),
         Err(e) => return Err(format!("Execution failed: {}", e)),
         
     }
@@ -0,0 +1,509 @@
+use crate::interpreter::interpreter::{type_of_value, value_to_string, EnvValue};
+use crate::ir::ast::{Environment, Exception, Expression, Function, Name, Type};
+use std::rc::Rc;
+
+/*
+ * The built-in functions, implemented in Rust. Each one has a signature the
+ * type checker sees like that of a declared function, and a native
+ * implementation the interpreter calls in place of a body.
+ */
+
+/* receives the arguments in parameter order, with the values a variadic parameter
+collects as a list at the end */
+pub type Native = fn(Vec<Expression>) -> Result<Expression, Exception>;
+
+pub struct Builtin {
+    pub signature: Function,
+    pub native: Native,
+}
+
+impl Builtin {
+    fn new(
+        name: &str,
+        params: Vec<(&str, Type)>,
+        variadic: Option<(&str, Type)>,
+        kind: Type,
+        native: Native,
+    ) -> Builtin {
+        let signature = Function {
+            name: name.to_string(),
+            kind: Some(kind),
+            params: Some(
+                params
+                    .into_iter()
+                    .map(|(param, kind)| (param.to_string(), kind))
+                    .collect(),
+            ),
+            defaults: vec![],
+            variadic: variadic.map(|(param, kind)| (param.to_string(), kind)),
+            module: None,
+            body: None,
+        };
+
+        Builtin { signature, native }
+    }
+
+    pub fn name(&self) -> Name {
+        self.signature.name.clone()
+    }
+
+    pub fn kind(&self) -> Type {
+        self.signature.function_type()
+    }
+}
+
+/* the type variable of the built-in functions generic over both kinds of number. The '#'
+keeps the numeric constraint away from the type variables a program writes, which no
+identifier can name */
+pub const NUMBER: &str = "#Number";
+
+pub fn builtins() -> Vec<Builtin> {
+    let t = || Type::TVar(NUMBER.to_string());
+    let strings = || Type::TList(Box::new(Type::TString));
+
+    vec![
+        /* math */
+        Builtin::new("abs", vec![("x", t())], None, t(), abs),
+        Builtin::new("sqrt", vec![("x", Type::TReal)], None, Type::TReal, sqrt),
+        Builtin::new(
+            "floor",
+            vec![("x", Type::TReal)],
+            None,
+            Type::TInteger,
+            floor,
+        ),
+        Builtin::new(
+            "min",
+            vec![("a", t()), ("b", t())],
+            Some(("rest", t())),
+            t(),
+            min,
+        ),
+        Builtin::new(
+            "max",
+            vec![("a", t()), ("b", t())],
+            Some(("rest", t())),
+            t(),
+            max,
+        ),
+        /* conversion */
+        Builtin::new("int", vec![("x", Type::TAny)], None, Type::TInteger, int),
+        Builtin::new("real", vec![("x", Type::TAny)], None, Type::TReal, real),
+        Builtin::new("str", vec![("x", Type::TAny)], None, Type::TString, str),
+        /* strings */
+        Builtin::new("len", vec![("x", Type::TAny)], None, Type::TInteger, len),
+        Builtin::new(
+            "upper",
+            vec![("s", Type::TString)],
+            None,
+            Type::TString,
+            upper,
+        ),
+        Builtin::new(
+            "lower",
+            vec![("s", Type::TString)],
+            None,
+            Type::TString,
+            lower,
+        ),
+        Builtin::new(
+            "strip",
+            vec![("s", Type::TString)],
+            None,
+            Type::TString,
+            strip,
+        ),
+        Builtin::new(
+            "replace",
+            vec![
+                ("s", Type::TString),
+                ("old", Type::TString),
+                ("new", Type::TString),
+            ],
+            None,
+            Type::TString,
+            replace,
+        ),
+        Builtin::new(
+            "split",
+            vec![("s", Type::TString), ("sep", Type::TString)],
+            None,
+            strings(),
+            split,
+        ),
+        Builtin::new(
+            "join",
+            vec![("sep", Type::TString), ("parts", strings())],
+            None,
+            Type::TString,
+            join,
+        ),
+    ]
+}
+
+pub fn search_builtin(name: &Name) -> Option<Builtin> {
+    builtins()
+        .into_iter()
+        .find(|builtin| builtin.signature.name == *name)
+}
+
+/* the environment the type checker starts in, binding the signatures of the built-in functions */
+pub fn checker_env() -> Environment<Type> {
+    let mut env: Environment<Type> = Environment::new();
+
+    env.inference.constrain_numeric(NUMBER.to_string());
+    for builtin in builtins() {
+        let kind = builtin.kind();
+        env.insert_builtin(builtin.signature, kind);
+    }
+    env
+}
+
+/* the environment the interpreter starts in, binding the built-in functions */
+pub fn interpreter_env() -> Environment<EnvValue> {
+    let mut env = Environment::new();
+
+    for builtin in builtins() {
+        env.insert_native(builtin.signature, Rc::new(builtin.native));
+    }
+    env
+}
+
+fn type_error(name: &str, expected: &str, value: &Expression) -> Exception {
+    Exception::new(
+        "TypeError",
+        format!(
+            "'{}()' expects {}, found a value of type '{}'.",
+            name,
+            expected,
+            type_of_value(&EnvValue::Exp(value.clone()))
+        ),
+    )
+}
+
+fn number(name: &str, value: &Expression) -> Result<f64, Exception> {
+    match value {
+        Expression::CInt(i) => Ok(*i as f64),
+        Expression::CReal(r) => Ok(*r),
+        _ => Err(type_error(name, "a number", value)),
+    }
+}
+
+/* the integer a real number truncates or rounds to, if it has one */
+fn to_int(name: &str, r: f64) -> Result<Expression, Exception> {
+    if r.is_nan() {
+        return Err(Exception::new(
+            "ValueError",
+            format!("'{}()' cannot convert NaN to an integer.", name),
+        ));
+    }
+    if r < i32::MIN as f64 || r > i32::MAX as f64 {
+        return Err(overflow_error(name));
+    }
+    Ok(Expression::CInt(r as i32))
+}
+
+fn overflow_error(name: &str) -> Exception {
+    Exception::new(
+        "OverflowError",
+        format!("'{}()' result is out of the integer range.", name),
+    )
+}
+
+fn string(name: &str, value: &Expression) -> Result<String, Exception> {
+    match value {
+        Expression::CString(s) => Ok(s.clone()),
+        _ => Err(type_error(name, "a string", value)),
+    }
+}
+
+/* Math */
+
+fn abs(args: Vec<Expression>) -> Result<Expression, Exception> {
+    match &args[0] {
+        Expression::CInt(i) => match i.checked_abs() {
+            Some(i) => Ok(Expression::CInt(i)),
+            None => Err(overflow_error("abs")),
+        },
+        value => Ok(Expression::CReal(number("abs", value)?.abs())),
+    }
+}
+
+fn sqrt(args: Vec<Expression>) -> Result<Expression, Exception> {
+    let x = number("sqrt", &args[0])?;
+
+    if x < 0.0 {
+        return Err(Exception::new(
+            "ValueError",
+            String::from("'sqrt()' is not defined for negative numbers."),
+        ));
+    }
+    Ok(Expression::CReal(x.sqrt()))
+}
+
+fn floor(args: Vec<Expression>) -> Result<Expression, Exception> {
+    to_int("floor", number("floor", &args[0])?.floor())
+}
+
+/* the least of the arguments by 'ordering' */
+fn extreme(
+    name: &str,
+    args: Vec<Expression>,
+    ordering: std::cmp::Ordering,
+) -> Result<Expression, Exception> {
+    let mut args = args.into_iter();
+    let mut values = vec![args.next().unwrap(), args.next().unwrap()];
+
+    if let Some(Expression::CList(rest)) = args.next() {
+        values.extend(rest);
+    }
+
+    let mut best = values[0].clone();
+    let any_real = values
+        .iter()
+        .any(|value| matches!(value, Expression::CReal(_)));
+
+    for value in values.into_iter().skip(1) {
+        let (left, right) = (number(name, &value)?, number(name, &best)?);
+        let order = left
+            .partial_cmp(&right)
+            .unwrap_or(std::cmp::Ordering::Equal);
+        if order == ordering {
+            best = value;
+        }
+    }
+
+    /* like arithmetic, a mix of integers and reals yields a real */
+    match best {
+        Expression::CInt(i) if any_real => Ok(Expression::CReal(i as f64)),
+        best => Ok(best),
+    }
+}
+
+fn min(args: Vec<Expression>) -> Result<Expression, Exception> {
+    extreme("min", args, std::cmp::Ordering::Less)
+}
+
+fn max(args: Vec<Expression>) -> Result<Expression, Exception> {
+    extreme("max", args, std::cmp::Ordering::Greater)
+}
+
+/* Conversion */
+
+fn int(args: Vec<Expression>) -> Result<Expression, Exception> {
+    match &args[0] {
+        Expression::CInt(i) => Ok(Expression::CInt(*i)),
+        Expression::CReal(r) => to_int("int", r.trunc()),
+        Expression::CTrue => Ok(Expression::CInt(1)),
+        Expression::CFalse => Ok(Expression::CInt(0)),
+        Expression::CString(s) => match s.trim().parse::<i32>() {
+            Ok(i) => Ok(Expression::CInt(i)),
+            Err(_) => Err(Exception::new(
+                "ValueError",
+                format!("invalid literal for 'int()': '{}'.", s),
+            )),
+        },
+        value => Err(type_error("int", "a number, a bool or a string", value)),
+    }
+}
+
+fn real(args: Vec<Expression>) -> Result<Expression, Exception> {
+    match &args[0] {
+        Expression::CTrue => Ok(Expression::CReal(1.0)),
+        Expression::CFalse => Ok(Expression::CReal(0.0)),
+        Expression::CString(s) => match s.trim().parse::<f64>() {
+            Ok(r) => Ok(Expression::CReal(r)),
+            Err(_) => Err(Exception::new(
+                "ValueError",
+                format!("invalid literal for 'real()': '{}'.", s),
+            )),
+        },
+        value => match number("real", value) {
+            Ok(r) => Ok(Expression::CReal(r)),
+            Err(_) => Err(type_error("real", "a number, a bool or a string", value)),
+        },
+    }
+}
+
+fn str(args: Vec<Expression>) -> Result<Expression, Exception> {
+    match value_to_string(&args[0]) {
+        Some(text) => Ok(Expression::CString(text)),
+        None => Err(type_error("str", "a printable value", &args[0])),
+    }
+}
+
+/* Strings */
+
+fn len(args: Vec<Expression>) -> Result<Expression, Exception> {
+    match &args[0] {
+        Expression::CString(s) => Ok(Expression::CInt(s.chars().count() as i32)),
+        Expression::CList(values) => Ok(Expression::CInt(values.len() as i32)),
+        value => Err(type_error("len", "a string or a list", value)),
+    }
+}
+
+fn upper(args: Vec<Expression>) -> Result<Expression, Exception> {
+    Ok(Expression::CString(
+        string("upper", &args[0])?.to_uppercase(),
+    ))
+}
+
+fn lower(args: Vec<Expression>) -> Result<Expression, Exception> {
+    Ok(Expression::CString(
+        string("lower", &args[0])?.to_lowercase(),
+    ))
+}
+
+fn strip(args: Vec<Expression>) -> Result<Expression, Exception> {
+    Ok(Expression::CString(
+        string("strip", &args[0])?.trim().to_string(),
+    ))
+}
+
+fn replace(args: Vec<Expression>) -> Result<Expression, Exception> {
+    let (s, old, new) = (
+        string("replace", &args[0])?,
+        string("replace", &args[1])?,
+        string("replace", &args[2])?,
+    );
+    Ok(Expression::CString(s.replace(&old, &new)))
+}
+
+fn split(args: Vec<Expression>) -> Result<Expression, Exception> {
+    let (s, sep) = (string("split", &args[0])?, string("split", &args[1])?);
+
+    if sep.is_empty() {
+        return Err(Exception::new(
+            "ValueError",
+            String::from("'split()' separator is empty."),
+        ));
+    }
+    Ok(Expression::CList(
+        s.split(sep.as_str())
+            .map(|part| Expression::CString(part.to_string()))
+            .collect(),
+    ))
+}
+
+fn join(args: Vec<Expression>) -> Result<Expression, Exception> {
+    let sep = string("join", &args[0])?;
+    let parts = match &args[1] {
+        Expression::CList(parts) => parts
+            .iter()
+            .map(|part| string("join", part))
+            .collect::<Result<Vec<String>, Exception>>()?,
+        value => return Err(type_error("join", "a list of strings", value)),
+    };
+    Ok(Expression::CString(parts.join(&sep)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::ast::Expression::*;
+
+    fn native(name: &str) -> Native {
+        search_builtin(&name.to_string()).unwrap().native
+    }
+
+    #[test]
+    fn min_and_max_collect_rest_arguments() {
+        let (min, max) = (native("min"), native("max"));
+
+        assert_eq!(
+            min(vec![CInt(4), CInt(2), CList(vec![CReal(2.5), CInt(-1)])]),
+            Ok(CReal(-1.0))
+        );
+        assert_eq!(
+            max(vec![CInt(3), CReal(2.5), CList(vec![])]),
+            Ok(CReal(3.0))
+        );
+        assert_eq!(
+            max(vec![CInt(3), CInt(2), CList(vec![CInt(5)])]),
+            Ok(CInt(5))
+        );
+        assert_eq!(
+            max(vec![CInt(4), CInt(2), CList(vec![CReal(4.5)])]),
+            Ok(CReal(4.5))
+        );
+        assert_eq!(
+            max(vec![CInt(4), CString("a".to_string()), CList(vec![])]),
+            Err(Exception::new(
+                "TypeError",
+                String::from("'max()' expects a number, found a value of type 'str'.")
+            ))
+        );
+    }
+
+    #[test]
+    fn integer_results_stay_in_range() {
+        let overflow = |name: &str| {
+            Err(Exception::new(
+                "OverflowError",
+                format!("'{}()' result is out of the integer range.", name),
+            ))
+        };
+
+        assert_eq!(native("abs")(vec![CInt(-3)]), Ok(CInt(3)));
+        assert_eq!(native("abs")(vec![CInt(i32::MIN)]), overflow("abs"));
+        assert_eq!(native("floor")(vec![CReal(-2.5)]), Ok(CInt(-3)));
+        assert_eq!(native("floor")(vec![CReal(1e20)]), overflow("floor"));
+        assert_eq!(native("int")(vec![CReal(-2.7)]), Ok(CInt(-2)));
+        assert_eq!(native("int")(vec![CReal(f64::INFINITY)]), overflow("int"));
+        assert_eq!(
+            native("int")(vec![CReal(f64::NAN)]),
+            Err(Exception::new(
+                "ValueError",
+                String::from("'int()' cannot convert NaN to an integer.")
+            ))
+        );
+    }
+
+    #[test]
+    fn math_signatures_take_numbers() {
+        let number = || Type::TVar(NUMBER.to_string());
+        let signature = |name: &str| search_builtin(&name.to_string()).unwrap().signature;
+        let env = checker_env();
+
+        assert!(env.inference.is_numeric(&NUMBER.to_string()));
+        assert_eq!(
+            signature("abs").function_type(),
+            Type::TFunction(Box::new(Some(number())), vec![number()])
+        );
+        assert_eq!(
+            signature("max").function_type(),
+            Type::TFunction(Box::new(Some(number())), vec![number(), number()])
+        );
+        assert_eq!(
+            signature("max").variadic,
+            Some(("rest".to_string(), number()))
+        );
+        assert_eq!(
+            signature("floor").function_type(),
+            Type::TFunction(Box::new(Some(Type::TInteger)), vec![Type::TReal])
+        );
+        assert_eq!(
+            native("min")(vec![
+                CString("a".to_string()),
+                CString("b".to_string()),
+                CList(vec![])
+            ]),
+            Err(Exception::new(
+                "TypeError",
+                String::from("'min()' expects a number, found a value of type 'str'.")
+            ))
+        );
+    }
+
+    #[test]
+    fn split_rejects_empty_separator() {
+        assert!(search_builtin(&"print".to_string()).is_none());
+        assert_eq!(
+            native("split")(vec![CString("a,b".to_string()), CString(String::new())]),
+            Err(Exception::new(
+                "ValueError",
+                String::from("'split()' separator is empty.")
+            ))
+        );
+    }
+}
@@ -1 +1,2 @@
+pub mod exhaustiveness;
 pub mod type_checker;
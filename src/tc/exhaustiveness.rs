@@ -0,0 +1,196 @@
+use crate::ir::ast::{Environment, Expression, Name, Pattern, Type, TypeDefinition};
+
+/*
+ * Pattern usefulness, following Maranget's "Warnings for pattern
+ * matching". A pattern vector is useful with respect to a matrix of
+ * previous rows when some value matches it but none of the rows. A
+ * match is exhaustive when a wildcard is not useful after all of its
+ * arms, and an arm is unreachable when it is not useful after the arms
+ * above it.
+ */
+
+pub fn is_exhaustive(patterns: &[Pattern], kind: &Type, env: &Environment<Type>) -> bool {
+    let matrix: Vec<Vec<Pattern>> = patterns.iter().map(|p| vec![p.clone()]).collect();
+
+    !is_useful(
+        &matrix,
+        &[Pattern::Wildcard],
+        std::slice::from_ref(kind),
+        env,
+    )
+}
+
+pub fn is_reachable(
+    previous: &[Pattern],
+    pattern: &Pattern,
+    kind: &Type,
+    env: &Environment<Type>,
+) -> bool {
+    let matrix: Vec<Vec<Pattern>> = previous.iter().map(|p| vec![p.clone()]).collect();
+
+    is_useful(
+        &matrix,
+        std::slice::from_ref(pattern),
+        std::slice::from_ref(kind),
+        env,
+    )
+}
+
+fn is_useful(
+    matrix: &[Vec<Pattern>],
+    vector: &[Pattern],
+    types: &[Type],
+    env: &Environment<Type>,
+) -> bool {
+    if vector.is_empty() {
+        return matrix.is_empty();
+    }
+
+    match &vector[0] {
+        Pattern::Constructor(name, args) => {
+            let mut new_vector = args.clone();
+            new_vector.extend_from_slice(&vector[1..]);
+
+            let mut new_types = constructor_types(name, &types[0], env);
+            new_types.extend_from_slice(&types[1..]);
+
+            let specialized = specialize_constructor(matrix, name, args.len());
+            is_useful(&specialized, &new_vector, &new_types, env)
+        }
+        Pattern::Literal(literal) => {
+            let specialized = specialize_literal(matrix, literal);
+            is_useful(&specialized, &vector[1..], &types[1..], env)
+        }
+        Pattern::Wildcard | Pattern::Binding(_) => match complete_signature(matrix, &types[0], env)
+        {
+            Some(heads) => heads.into_iter().any(|head| {
+                let mut new_vector = vec![head];
+                new_vector.extend_from_slice(&vector[1..]);
+                is_useful(matrix, &new_vector, types, env)
+            }),
+            None => {
+                let default: Vec<Vec<Pattern>> = matrix
+                    .iter()
+                    .filter(|row| is_irrefutable(&row[0]))
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+                is_useful(&default, &vector[1..], &types[1..], env)
+            }
+        },
+    }
+}
+
+fn is_irrefutable(pattern: &Pattern) -> bool {
+    matches!(pattern, Pattern::Wildcard | Pattern::Binding(_))
+}
+
+fn specialize_constructor(matrix: &[Vec<Pattern>], name: &Name, arity: usize) -> Vec<Vec<Pattern>> {
+    let mut specialized = vec![];
+
+    for row in matrix {
+        match &row[0] {
+            Pattern::Constructor(cons, args) if cons == name => {
+                let mut new_row = args.clone();
+                new_row.extend_from_slice(&row[1..]);
+                specialized.push(new_row);
+            }
+            Pattern::Wildcard | Pattern::Binding(_) => {
+                let mut new_row = vec![Pattern::Wildcard; arity];
+                new_row.extend_from_slice(&row[1..]);
+                specialized.push(new_row);
+            }
+            _ => (),
+        }
+    }
+
+    specialized
+}
+
+fn specialize_literal(matrix: &[Vec<Pattern>], literal: &Expression) -> Vec<Vec<Pattern>> {
+    let mut specialized = vec![];
+
+    for row in matrix {
+        match &row[0] {
+            Pattern::Literal(other) if other == literal => specialized.push(row[1..].to_vec()),
+            Pattern::Wildcard | Pattern::Binding(_) => specialized.push(row[1..].to_vec()),
+            _ => (),
+        }
+    }
+
+    specialized
+}
+
+/*
+ * When the first column mentions every constructor of its type, returns
+ * one head pattern per constructor. Types with infinitely many values
+ * (numbers and strings) never have a complete signature.
+ */
+fn complete_signature(
+    matrix: &[Vec<Pattern>],
+    kind: &Type,
+    env: &Environment<Type>,
+) -> Option<Vec<Pattern>> {
+    let heads: Vec<Pattern> = match &env.expand_alias(kind) {
+        Type::TBool => vec![
+            Pattern::Literal(Expression::CTrue),
+            Pattern::Literal(Expression::CFalse),
+        ],
+        Type::TMaybe(_) | Type::TResult(_, _) => wrapped_constructors(kind, env)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(cons, types)| Pattern::Constructor(cons, vec![Pattern::Wildcard; types.len()]))
+            .collect(),
+        Type::TAdt(name) => match env.search_definition(name) {
+            Some(TypeDefinition::Adt(constructors)) => constructors
+                .iter()
+                .map(|(cons, types)| {
+                    Pattern::Constructor(cons.clone(), vec![Pattern::Wildcard; types.len()])
+                })
+                .collect(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let covered = heads.iter().all(|head| {
+        matrix.iter().any(|row| match (&row[0], head) {
+            (Pattern::Constructor(cons, _), Pattern::Constructor(name, _)) => cons == name,
+            (Pattern::Literal(literal), Pattern::Literal(other)) => literal == other,
+            _ => false,
+        })
+    });
+
+    if covered {
+        Some(heads)
+    } else {
+        None
+    }
+}
+
+/* the constructors of Maybe and Result values, along with their argument types */
+pub fn wrapped_constructors(
+    kind: &Type,
+    env: &Environment<Type>,
+) -> Option<Vec<(Name, Vec<Type>)>> {
+    match env.expand_alias(kind) {
+        Type::TMaybe(inner) => Some(vec![
+            ("Just".to_string(), vec![*inner]),
+            ("Nothing".to_string(), vec![]),
+        ]),
+        Type::TResult(ok, err) => Some(vec![
+            ("Ok".to_string(), vec![*ok]),
+            ("Err".to_string(), vec![*err]),
+        ]),
+        _ => None,
+    }
+}
+
+fn constructor_types(name: &Name, kind: &Type, env: &Environment<Type>) -> Vec<Type> {
+    let wrapped = wrapped_constructors(kind, env)
+        .and_then(|constructors| constructors.into_iter().find(|(cons, _)| cons == name));
+
+    match wrapped.or_else(|| env.search_constructor(name)) {
+        Some((_, types)) => types,
+        None => vec![],
+    }
+}
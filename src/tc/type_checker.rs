@@ -1,10 +1,20 @@
-use crate::ir::ast::{Environment, Expression, Name, Statement, Type};
+use crate::ir::ast::{
+    split_arguments, Class, Environment, Expression, Function, Inference, Name, Pattern, Statement,
+    Type, TypeDefinition,
+};
+use crate::ir::sandbox::{check_roots, check_write};
+use crate::tc::exhaustiveness::{is_exhaustive, is_reachable, wrapped_constructors};
+use std::collections::HashMap;
 
 type ErrorMessage = String;
 
+/* nearly every statement continues, so boxing the environment would only add an allocation */
+#[allow(clippy::large_enum_variant)]
 pub enum ControlFlow {
     Continue(Environment<Type>),
     Return(Type),
+    /* every path through the statement raises, so nothing after it runs */
+    Raise,
 }
 
 pub fn check_exp(exp: Expression, env: &Environment<Type>) -> Result<Type, ErrorMessage> {
@@ -14,6 +24,36 @@ pub fn check_exp(exp: Expression, env: &Environment<Type>) -> Result<Type, Error
         Expression::CInt(_) => Ok(Type::TInteger),
         Expression::CReal(_) => Ok(Type::TReal),
         Expression::CString(_) => Ok(Type::TString),
+        Expression::CNone => Ok(Type::TUnit),
+        Expression::CList(values) => {
+            let mut list_type = Type::TUnknown;
+
+            for value in values {
+                let value_type = check_exp(value, env)?;
+
+                list_type = match join_types(&list_type, &value_type, env) {
+                    Some(joined) => joined,
+                    None => return Err(format!(
+                        "[Type Error on '{}()'] list elements have mismatched types: expected '{:?}', found '{:?}'.",
+                        env.scope_name(),
+                        list_type,
+                        value_type
+                    )),
+                };
+            }
+
+            Ok(Type::TList(Box::new(list_type)))
+        }
+        Expression::CJust(e) => Ok(Type::TMaybe(Box::new(check_exp(*e, env)?))),
+        Expression::CNothing => Ok(Type::TMaybe(Box::new(Type::TUnknown))),
+        Expression::COk(e) => Ok(Type::TResult(
+            Box::new(check_exp(*e, env)?),
+            Box::new(Type::TUnknown),
+        )),
+        Expression::CErr(e) => Ok(Type::TResult(
+            Box::new(Type::TUnknown),
+            Box::new(check_exp(*e, env)?),
+        )),
         Expression::Add(l, r) => check_bin_arithmetic_expression(*l, *r, env),
         Expression::Sub(l, r) => check_bin_arithmetic_expression(*l, *r, env),
         Expression::Mul(l, r) => check_bin_arithmetic_expression(*l, *r, env),
@@ -21,14 +61,79 @@ pub fn check_exp(exp: Expression, env: &Environment<Type>) -> Result<Type, Error
         Expression::And(l, r) => check_bin_boolean_expression(*l, *r, env),
         Expression::Or(l, r) => check_bin_boolean_expression(*l, *r, env),
         Expression::Not(e) => check_not_expression(*e, env),
-        Expression::EQ(l, r) => check_bin_relational_expression(*l, *r, env),
+        Expression::EQ(l, r) => check_bin_equality_expression(*l, *r, env),
+        Expression::NEQ(l, r) => check_bin_equality_expression(*l, *r, env),
         Expression::GT(l, r) => check_bin_relational_expression(*l, *r, env),
         Expression::LT(l, r) => check_bin_relational_expression(*l, *r, env),
         Expression::GTE(l, r) => check_bin_relational_expression(*l, *r, env),
         Expression::LTE(l, r) => check_bin_relational_expression(*l, *r, env),
-        Expression::Var(name) => check_var_name(name, env, false),
+        Expression::Var(name) => Ok(resolve(&check_var_name(name, env, false)?, env)),
         Expression::FuncCall(name, args) => check_func_call(name, args, env),
+        Expression::KeywordArg(name, _) => Err(format!(
+            "[Syntax Error] keyword argument '{}' used outside a call on '{}()'.",
+            name,
+            env.scope_name()
+        )),
+        Expression::Record(name, fields) => check_record(name, fields, env),
+        Expression::FieldAccess(exp, field) => check_field_access(*exp, field, env),
+        Expression::Constructor(name, args) => check_constructor(name, args, env),
+        Expression::New(name, args) => check_new(name, args, env),
+        Expression::MethodCall(exp, method, args) => check_method_call(*exp, method, args, env),
+        Expression::MatchExp(exp, arms) => {
+            let exp_type = check_exp(*exp, env)?;
+            let patterns: Vec<Pattern> = arms.iter().map(|(pattern, _)| pattern.clone()).collect();
+
+            check_match_patterns(&patterns, &exp_type, env)?;
+
+            let mut arm_types = vec![];
+
+            for (pattern, arm) in arms {
+                let arm_env = check_pattern_bindings(&pattern, &exp_type, env)?;
+                arm_types.push(check_exp(arm, &arm_env)?);
+            }
+
+            let mut match_type = arm_types[0].clone();
+
+            for kind in &arm_types[1..] {
+                match_type = match join_types(&match_type, kind, env) {
+                    Some(merged) => merged,
+                    None => return Err(format!(
+                        "[Type Error on '{}()'] match arms have mismatched types: expected '{:?}', found '{:?}'.",
+                        env.scope_name(),
+                        match_type,
+                        kind
+                    )),
+                };
+            }
+
+            Ok(match_type)
+        }
+        Expression::IsJust(e) | Expression::IsNothing(e) => {
+            check_maybe_or_result(*e, "is_just/is_nothing", true, false, env)?;
+            Ok(Type::TBool)
+        }
+        Expression::IsOk(e) | Expression::IsErr(e) => {
+            check_maybe_or_result(*e, "is_ok/is_err", false, true, env)?;
+            Ok(Type::TBool)
+        }
+        Expression::Unwrap(e) => check_maybe_or_result(*e, "unwrap", true, true, env),
+        Expression::UnwrapOr(e, default) => {
+            let value_type = check_maybe_or_result(*e, "unwrap_or", true, true, env)?;
+            let default_type = check_exp(*default, env)?;
+
+            match join_types(&value_type, &default_type, env) {
+                Some(kind) => Ok(kind),
+                None => Err(format!(
+                    "[Type Error on '{}()'] unwrap_or has mismatched types: expected '{:?}', found '{:?}'.",
+                    env.scope_name(),
+                    value_type,
+                    default_type
+                )),
+            }
+        }
+        Expression::Propagate(e) => check_propagate(*e, env),
         Expression::ReadFile(file_path_exp) => {
+            check_sandbox_read(&file_path_exp, env)?;
             let file_path_type = check_exp(*file_path_exp, env)?;
 
             if file_path_type != Type::TString {
@@ -43,31 +148,85 @@ pub fn check_exp(exp: Expression, env: &Environment<Type>) -> Result<Type, Error
     }
 }
 
+/* rejects reading a constant path the sandbox policy denies, when it is enforced statically */
+fn check_sandbox_read(path: &Expression, env: &Environment<Type>) -> Result<(), ErrorMessage> {
+    match (env.sandbox.static_policy(), path) {
+        (Some(policy), Expression::CString(path)) => check_roots(policy, path)
+            .map_err(|denial| format!("[Sandbox Error on '{}()'] {}", env.scope_name(), denial)),
+        _ => Ok(()),
+    }
+}
+
+/* rejects writes the sandbox policy denies on every run, judging paths and contents
+only when they are constant */
+fn check_sandbox_write(
+    path: &Expression,
+    content: &Expression,
+    env: &Environment<Type>,
+) -> Result<(), ErrorMessage> {
+    let policy = match env.sandbox.static_policy() {
+        Some(policy) => policy,
+        None => return Ok(()),
+    };
+    let bytes = match content {
+        Expression::CString(content) => content.len(),
+        _ => 0,
+    };
+
+    match path {
+        Expression::CString(path) => check_write(policy, path, bytes, 0)
+            .map_err(|denial| format!("[Sandbox Error on '{}()'] {}", env.scope_name(), denial)),
+        _ if policy.read_only => Err(format!(
+            "[Sandbox Error on '{}()'] cannot write files in read-only mode.",
+            env.scope_name()
+        )),
+        _ => Ok(()),
+    }
+}
+
 pub fn check_stmt(stmt: Statement, env: &Environment<Type>) -> Result<ControlFlow, ErrorMessage> {
     let mut new_env = env.clone();
 
     match stmt {
         Statement::Assignment(name, exp, kind) => {
-            let exp_type = check_exp(*exp, &new_env)?;
+            let exp_type = check_exp_expecting(*exp, &kind, &new_env)?;
 
-            if let Some(state_type) = kind {
-                if exp_type != state_type {
-                    return Err(format!("[Type Error on '{}()'] '{}' has mismatched types: expected '{:?}', found '{:?}'.", new_env.scope_name(), name, state_type, exp_type));
-                }
-            } else {
-                let stated_type = check_var_name(name.clone(), &new_env, true)?;
+            let var_type = check_assignment_target(name.clone(), &exp_type, kind, &new_env)?;
+            new_env.insert_variable(name, var_type);
 
-                if exp_type != stated_type {
-                    return Err(format!("[Type Error on '{}()'] '{}' has mismatched types: expected '{:?}', found '{:?}'.", new_env.scope_name(), name, stated_type, exp_type));
-                }
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::FieldAssignment(name, field, exp) => {
+            let record_type = check_var_name(name.clone(), &new_env, true)?;
+            let field_type = check_field_type(record_type, field.clone(), &new_env)?;
+            let exp_type = check_exp(*exp, &new_env)?;
+
+            if !matches_type(&field_type, &exp_type, &new_env) {
+                return Err(format!("[Type Error on '{}()'] '{}.{}' has mismatched types: expected '{:?}', found '{:?}'.", new_env.scope_name(), name, field, field_type, exp_type));
             }
 
-            new_env.insert_variable(name, exp_type);
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::ChainedAssignment(names, exp, kind) => {
+            let exp_type = check_exp_expecting(*exp, &kind, &new_env)?;
+
+            for name in names {
+                let var_type =
+                    check_assignment_target(name.clone(), &exp_type, kind.clone(), &new_env)?;
+                new_env.insert_variable(name, var_type);
+            }
 
             Ok(ControlFlow::Continue(new_env))
         }
+        Statement::AddAssignment(ref name, _)
+        | Statement::SubAssignment(ref name, _)
+        | Statement::MulAssignment(ref name, _)
+        | Statement::DivAssignment(ref name, _) => {
+            check_var_name(name.clone(), &new_env, true)?;
+            check_stmt(stmt.expand_augmented_assignment(), &new_env)
+        }
         Statement::IfThenElse(exp, stmt_then, option) => {
-            let exp_type = check_exp(*exp, &new_env)?;
+            let exp_type = require(check_exp(*exp, &new_env)?, Type::TBool, &new_env);
 
             if exp_type != Type::TBool {
                 return Err(format!(
@@ -79,24 +238,14 @@ pub fn check_stmt(stmt: Statement, env: &Environment<Type>) -> Result<ControlFlo
             let stmt_then_result = check_stmt(*stmt_then, &new_env)?;
             let stmt_else_result = match option {
                 Some(stmt_else) => check_stmt(*stmt_else, &new_env)?,
-                None => return Ok(ControlFlow::Continue(new_env)),
+                /* without an else branch, the condition may skip the assignments of the other */
+                None => ControlFlow::Continue(new_env.clone()),
             };
 
-            match (stmt_then_result, stmt_else_result) {
-                (ControlFlow::Return(kind), ControlFlow::Continue(_)) => {
-                    Ok(ControlFlow::Return(kind))
-                }
-                (ControlFlow::Continue(_), ControlFlow::Return(kind)) => {
-                    Ok(ControlFlow::Return(kind))
-                }
-                (ControlFlow::Return(kind1), ControlFlow::Return(_)) => {
-                    Ok(ControlFlow::Return(kind1))
-                }
-                _ => Ok(ControlFlow::Continue(new_env)),
-            }
+            join_flows(&new_env, vec![stmt_then_result, stmt_else_result])
         }
         Statement::While(exp, stmt_while) => {
-            let exp_type = check_exp(*exp, &new_env)?;
+            let exp_type = require(check_exp(*exp, &new_env)?, Type::TBool, &new_env);
 
             if exp_type != Type::TBool {
                 return Err(format!(
@@ -105,69 +254,283 @@ pub fn check_stmt(stmt: Statement, env: &Environment<Type>) -> Result<ControlFlo
                 ));
             }
 
+            /* the body may run no times, so neither what it assigns nor a return in it
+            holds after the loop */
             match check_stmt(*stmt_while, &new_env)? {
-                ControlFlow::Continue(_) => Ok(ControlFlow::Continue(new_env)),
-                ControlFlow::Return(kind) => Ok(ControlFlow::Return(kind)),
+                ControlFlow::Continue(body_env) => Ok(ControlFlow::Continue(join_paths(
+                    &new_env,
+                    &[body_env, new_env.clone()],
+                )?)),
+                ControlFlow::Return(_) | ControlFlow::Raise => Ok(ControlFlow::Continue(new_env)),
             }
         }
-        Statement::Sequence(stmt1, stmt2) => {
-            if let ControlFlow::Continue(control_env) = check_stmt(*stmt1, &new_env)? {
-                new_env = control_env;
-            }
-            check_stmt(*stmt2, &new_env)
+        Statement::Sequence(_, _) => {
+            declare_functions(&stmt, &mut new_env);
+            check_block(stmt, &new_env)
         }
         Statement::FuncDef(func) => {
+            let declared = new_env.get_frame(new_env.scope_key()).declared.clone();
             new_env.insert_frame(func.clone());
 
+            /* the body may call the functions its block defines after it */
+            for (name, other) in declared {
+                if name != func.name && check_var_name(name.clone(), &new_env, false).is_err() {
+                    if let Some(kind) = declared_type(&other) {
+                        new_env.insert_variable(name, kind);
+                        new_env.insert_function(other);
+                    }
+                }
+            }
+
             let mut type_vec = vec![];
+            let mut inferred = false;
 
             if let Some(params) = func.params.clone() {
                 // Adicionamos a verificação de parâmetros duplicados
-                check_duplicate_params(&params)?;
+                check_duplicate_params(&params.iter().chain(&func.variadic).cloned().collect())?;
 
                 for (param_name, param_kind) in params {
+                    /* parameters written without a type are inferred from the body */
+                    let param_kind = match param_kind {
+                        Type::TUnknown => {
+                            inferred = true;
+                            new_env.inference.fresh_var()
+                        }
+                        param_kind => param_kind,
+                    };
                     new_env.insert_variable(param_name, param_kind.clone());
                     type_vec.push(param_kind);
                 }
             }
 
-            let func_type = Type::TFunction(Box::new(func.kind), type_vec);
+            check_defaults(&func, &type_vec, env)?;
 
-            if let None = new_env.search_frame(func.name.clone()) {
+            /* extra positional arguments are collected in a list */
+            if let Some((args_name, args_kind)) = func.variadic.clone() {
+                new_env.insert_variable(args_name, Type::TList(Box::new(args_kind)));
+            }
+
+            let kind = match func.kind.clone() {
+                Some(Type::TUnknown) => {
+                    inferred = true;
+                    Some(new_env.inference.fresh_var())
+                }
+                kind => kind,
+            };
+
+            let func_type = Type::TFunction(Box::new(kind), type_vec);
+
+            if new_env.search_frame(func.name.clone()).is_none() {
                 new_env.insert_variable(func.name.clone(), func_type.clone());
+                new_env.insert_function(func.clone());
             }
 
+            let declaration = func.clone();
+
+            /* a procedure, declared without a return type or as returning 'None', may end
+            without returning */
             match check_stmt(*func.body.unwrap(), &new_env)? {
-                ControlFlow::Continue(_) => Err(format!(
-                    "[Syntax Error] '{}()' does not have a return statement.",
+                ControlFlow::Continue(_) if !is_procedure(&func.kind) => Err(format!(
+                    "[Type Error] '{}()' does not return a value on every path.",
                     func.name
                 )),
-                ControlFlow::Return(_) => {
+                _ => {
                     new_env.remove_frame();
+
+                    let func_type = match inferred {
+                        true => {
+                            let func_type = generalize(&resolve(&func_type, &new_env), &new_env);
+                            new_env.inference.record(
+                                new_env.scope_name(),
+                                func.name.clone(),
+                                func_type.clone(),
+                            );
+                            func_type
+                        }
+                        false => func_type,
+                    };
+
                     new_env.insert_variable(func.name, func_type);
+                    new_env.insert_function(declaration);
                     Ok(ControlFlow::Continue(new_env))
                 }
             }
         }
-        Statement::Return(exp) => {
-            let exp_type = check_exp(*exp, &new_env)?;
+        Statement::RecordDef(name, fields) => {
+            let mut seen_fields = std::collections::HashSet::new();
 
-            if let Some(Type::TFunction(func_type, _)) = new_env.scope_return() {
-                if exp_type != func_type.clone().unwrap() {
+            for (field, field_type) in &fields {
+                if !seen_fields.insert(field.clone()) {
                     return Err(format!(
-                        "[Type Error] '{}()' has mismatched types: expected '{:?}', found '{:?}'.",
+                        "[Type Error on '{}()'] record '{}' has duplicate field '{}'.",
                         new_env.scope_name(),
-                        func_type.clone().unwrap(),
-                        exp_type
+                        name,
+                        field
                     ));
                 }
 
-                Ok(ControlFlow::Return(exp_type))
-            } else {
-                Err(format!("[Syntax Error] return statement outside function."))
+                match field_type {
+                    Type::TFunction(_, _) => {
+                        return Err(format!(
+                        "[Type Error on '{}()'] record field '{}.{}' cannot have a function type.",
+                        new_env.scope_name(),
+                        name,
+                        field
+                    ))
+                    }
+                    Type::TRecord(field_record)
+                        if *field_record != name
+                            && new_env.search_definition(field_record).is_none() =>
+                    {
+                        return Err(format!(
+                            "[Name Error on '{}()'] record '{}' is not defined.",
+                            new_env.scope_name(),
+                            field_record
+                        ))
+                    }
+                    _ => (),
+                }
+            }
+
+            new_env.insert_definition(name, TypeDefinition::Record(fields));
+
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::AdtDef(name, constructors) => {
+            for (cons, types) in &constructors {
+                let already_defined = constructors
+                    .iter()
+                    .filter(|(other, _)| other == cons)
+                    .count()
+                    > 1
+                    || new_env.search_constructor(cons).is_some();
+
+                if already_defined {
+                    return Err(format!(
+                        "[Type Error on '{}()'] constructor '{}' is already defined.",
+                        new_env.scope_name(),
+                        cons
+                    ));
+                }
+
+                for kind in types {
+                    match kind {
+                        Type::TFunction(_, _) => {
+                            return Err(format!(
+                            "[Type Error on '{}()'] constructor '{}' cannot hold a function type.",
+                            new_env.scope_name(),
+                            cons
+                        ))
+                        }
+                        Type::TAdt(other) | Type::TRecord(other)
+                            if *other != name && new_env.search_definition(other).is_none() =>
+                        {
+                            return Err(format!(
+                                "[Name Error on '{}()'] type '{}' is not defined.",
+                                new_env.scope_name(),
+                                other
+                            ))
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            new_env.insert_definition(name, TypeDefinition::Adt(constructors));
+
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::ClassDef(class) => {
+            check_class_definition(&class, &new_env)?;
+            new_env.insert_definition(class.name.clone(), TypeDefinition::Class(class.clone()));
+
+            for method in class.methods {
+                check_method(&class.name, method, &new_env)?;
+            }
+
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::TypeAlias(name, kind) => {
+            if new_env.search_definition(&name).is_some() {
+                return Err(format!(
+                    "[Type Error on '{}()'] type '{}' is already defined.",
+                    new_env.scope_name(),
+                    name
+                ));
+            }
+
+            check_alias_target(&name, &kind, &new_env)?;
+            new_env.insert_definition(name, TypeDefinition::Alias(kind));
+
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::Import(module) => {
+            let module_env = check_module(&module, &new_env)?;
+
+            import_definitions(&module, &module_env, &mut new_env)?;
+            new_env.insert_variable(module.clone(), Type::TModule(module));
+
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::FromImport(module, names) => {
+            let module_env = check_module(&module, &new_env)?;
+
+            import_definitions(&module, &module_env, &mut new_env)?;
+
+            for name in names {
+                let kind = match module_env.search_export(&name) {
+                    Some(kind) => kind.clone(),
+                    None => {
+                        return Err(format!(
+                            "[Import Error on '{}()'] cannot import name '{}' from module '{}'.",
+                            new_env.scope_name(),
+                            name,
+                            module
+                        ))
+                    }
+                };
+
+                new_env.insert_variable(name.clone(), kind);
+
+                if let Some(func) = module_env.search_function(&name) {
+                    new_env.insert_function(func.clone());
+                }
+            }
+
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::Match(exp, arms) => {
+            let exp_type = check_exp(*exp, &new_env)?;
+            let patterns: Vec<Pattern> = arms.iter().map(|(pattern, _)| pattern.clone()).collect();
+
+            check_match_patterns(&patterns, &exp_type, &new_env)?;
+
+            /* the match is exhaustive, so some arm runs and its paths are joined like
+            the branches of an if */
+            let mut results = vec![];
+
+            for (pattern, arm) in arms {
+                let arm_env = check_pattern_bindings(&pattern, &exp_type, &new_env)?;
+                results.push(check_stmt(arm, &arm_env)?);
             }
+
+            join_flows(&new_env, results)
+        }
+        Statement::Return(exp) => {
+            let expected = match new_env.scope_return() {
+                Some(Type::TFunction(kind, _)) => *kind.clone(),
+                _ => None,
+            };
+            let exp_type = check_exp_expecting(*exp, &expected, &new_env)?;
+            check_return(Some(exp_type), &new_env)
+        }
+        Statement::BareReturn => check_return(None, &new_env),
+        Statement::ExprStmt(exp) => {
+            check_exp(*exp, &new_env)?;
+            Ok(ControlFlow::Continue(new_env))
         }
         Statement::WriteToFile(file_path_exp, content_exp) => {
+            check_sandbox_write(&file_path_exp, &content_exp, &new_env)?;
             let file_path_type = check_exp(*file_path_exp, &new_env)?;
             let content_type = check_exp(*content_exp, &new_env)?;
 
@@ -181,313 +544,4511 @@ pub fn check_stmt(stmt: Statement, env: &Environment<Type>) -> Result<ControlFlo
         Statement::Print(exp) => {
             let exp_type = check_exp(*exp, &new_env)?;
 
-            match exp_type {
-                Type::TInteger | Type::TReal | Type::TString | Type::TBool => Ok(ControlFlow::Continue(new_env)),
+            match expand(&exp_type, &new_env) {
+                Type::TInteger
+                | Type::TReal
+                | Type::TString
+                | Type::TBool
+                | Type::TRecord(_)
+                | Type::TAdt(_)
+                | Type::TMaybe(_)
+                | Type::TResult(_, _)
+                | Type::TUnit
+                | Type::TList(_)
+                | Type::TAny => Ok(ControlFlow::Continue(new_env)),
                 _ => Err(String::from("Cannot print this type of value")),
             }
         }
 
 
-        _ => Err(String::from("not implemented yet.")),
-    }
-}
+        Statement::Raise(_, exp) => {
+            let exp_type = require(check_exp(*exp, &new_env)?, Type::TString, &new_env);
 
-fn check_func_call(
-    name: String,
-    args: Vec<Expression>,
-    env: &Environment<Type>,
-) -> Result<Type, ErrorMessage> {
-    match check_var_name(name.clone(), env, false) {
-        Ok(Type::TFunction(kind, type_vec)) => {
-            if args.len() != type_vec.len() {
+            if exp_type != Type::TString {
                 return Err(format!(
-                    "[Type Error on '{}()'] '{}()' expected {} arguments, found {}.",
-                    env.scope_name(),
-                    name,
-                    type_vec.len(),
-                    args.len()
+                    "[Type Error on '{}()'] raise expects a string as the exception message.",
+                    new_env.scope_name()
+                ));
+            }
+
+            Ok(ControlFlow::Raise)
+        }
+        Statement::Assert(cond, exp) => {
+            let cond_type = require(check_exp(*cond, &new_env)?, Type::TBool, &new_env);
+            let exp_type = require(check_exp(*exp, &new_env)?, Type::TString, &new_env);
+
+            if cond_type != Type::TBool {
+                return Err(format!(
+                    "[Type Error on '{}()'] assert condition must be boolean.",
+                    new_env.scope_name()
+                ));
+            }
+
+            if exp_type != Type::TString {
+                return Err(format!(
+                    "[Type Error on '{}()'] assert expects a string as the failure message.",
+                    new_env.scope_name()
                 ));
             }
 
-            for (arg, param_type) in args.iter().zip(type_vec) {
-                let arg_type = check_exp(arg.clone(), env)?;
-                if arg_type != param_type {
-                    return Err(format!("[Type Error on '{}()'] '{}()' has mismatched arguments: expected '{:?}', found '{:?}'.", env.scope_name(), name, param_type, arg_type));
+            Ok(ControlFlow::Continue(new_env))
+        }
+        Statement::TryExcept(stmt_try, handlers, stmt_finally) => {
+            let mut results = vec![check_stmt(*stmt_try, &new_env)?];
+
+            for (_, binding, stmt_handler) in handlers {
+                let mut handler_env = new_env.clone();
+
+                if let Some(name) = binding {
+                    handler_env.insert_variable(name, Type::TString);
                 }
+
+                results.push(check_stmt(stmt_handler, &handler_env)?);
             }
 
-            Ok(kind.unwrap())
+            /* the body and each handler are separate paths, like the branches of an if */
+            let flow = join_flows(&new_env, results)?;
+
+            /* a finally block runs on every path, so its assignments hold after the
+            statement, and a return or raise in it replaces the outcome of the others */
+            match stmt_finally {
+                Some(stmt_finally) => match (check_stmt(*stmt_finally, &new_env)?, flow) {
+                    (ControlFlow::Continue(finally_env), ControlFlow::Continue(path_env)) => Ok(
+                        ControlFlow::Continue(assigned_after(&new_env, &path_env, &finally_env)),
+                    ),
+                    (ControlFlow::Continue(_), flow) => Ok(flow),
+                    (finally_flow, _) => Ok(finally_flow),
+                },
+                None => Ok(flow),
+            }
         }
-        _ => Err(format!(
-            "[Name Error on '{}()'] '{}()' is not defined.",
-            env.scope_name(),
-            name
-        )),
+
+        _ => Err(String::from("not implemented yet.")),
     }
 }
 
-fn check_duplicate_params(params: &Vec<(Name, Type)>) -> Result<(), ErrorMessage> {
-    let mut seen_params = std::collections::HashSet::new();
+fn is_procedure(kind: &Option<Type>) -> bool {
+    matches!(kind, None | Some(Type::TUnit))
+}
 
-    for (name, _) in params {
-        if !seen_params.insert(name.clone()) {
+/* defaults are checked in the scope of the definition, and like in Python only the
+trailing parameters may have them */
+fn check_defaults(
+    func: &Function,
+    param_types: &[Type],
+    env: &Environment<Type>,
+) -> Result<(), ErrorMessage> {
+    let params = func.params.clone().unwrap_or_default();
+
+    for (name, exp) in &func.defaults {
+        let index = match params.iter().position(|(param, _)| param == name) {
+            Some(index) => index,
+            None => {
+                return Err(format!(
+                    "[Syntax Error] '{}()' has a default for unknown parameter '{}'.",
+                    func.name, name
+                ))
+            }
+        };
+        let exp_type = check_exp(exp.clone(), env)?;
+
+        if !matches_type(&param_types[index], &exp_type, env) {
             return Err(format!(
-                "[Parameter Error] Duplicate parameter name '{}'",
-                name
+                "[Type Error] '{}()' has a mismatched default for '{}': expected '{:?}', found '{:?}'.",
+                func.name,
+                name,
+                resolve(&param_types[index], env),
+                exp_type
+            ));
+        }
+    }
+
+    let mut after_default = false;
+
+    for (name, _) in &params {
+        let has_default = func.defaults.iter().any(|(param, _)| param == name);
+
+        if after_default && !has_default {
+            return Err(format!(
+                "[Syntax Error] '{}()' has parameter '{}' without a default after one with a default.",
+                func.name, name
             ));
         }
+        after_default |= has_default;
     }
 
     Ok(())
 }
 
-fn check_var_name(name: Name, env: &Environment<Type>, scoped: bool) -> Result<Type, ErrorMessage> {
-    let mut curr_scope = env.scope_key();
-
-    loop {
-        let frame = env.get_frame(curr_scope.clone());
+/* checks an expression against the type its context declares, so that the elements of
+a list literal are checked against the declared element type rather than joined with
+each other first */
+fn check_exp_expecting(
+    exp: Expression,
+    expected: &Option<Type>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    match (exp, expected.as_ref().map(|kind| expand(kind, env))) {
+        (Expression::CList(values), Some(Type::TList(elem_type))) => {
+            for value in values {
+                let value_type = check_exp_expecting(value, &Some(*elem_type.clone()), env)?;
 
-        match frame.variables.get(&name) {
-            Some(kind) => {
-                if scoped && curr_scope != env.scope_key() {
+                if !matches_type(&elem_type, &value_type, env) {
                     return Err(format!(
-                        "[Local Name Error on '{}'] cannot access local variable '{}'.",
+                        "[Type Error on '{}()'] list elements have mismatched types: expected '{:?}', found '{:?}'.",
                         env.scope_name(),
-                        name
+                        elem_type,
+                        value_type
                     ));
-                } else {
-                    return Ok(kind.clone());
                 }
             }
-            None => match &frame.parent_key {
-                Some(parent) => curr_scope = parent.clone(),
-                None => {
-                    return Err(format!(
-                        "[Name Error on '{}'] '{}' is not defined.",
-                        env.scope_name(),
-                        name
-                    ))
-                }
-            },
+
+            Ok(Type::TList(elem_type))
         }
+        (exp, _) => check_exp(exp, env),
     }
 }
 
-fn check_bin_arithmetic_expression(
-    left: Expression,
-    right: Expression,
+/* checks a returned value, or its absence for a bare 'return', against the one return
+type the enclosing function declares or infers, which every path then returns */
+fn check_return(
+    exp_type: Option<Type>,
     env: &Environment<Type>,
-) -> Result<Type, ErrorMessage> {
-    let left_type = check_exp(left, env)?;
-    let right_type = check_exp(right, env)?;
+) -> Result<ControlFlow, ErrorMessage> {
+    let func_type = match env.scope_return() {
+        Some(Type::TFunction(func_type, _)) => *func_type.clone(),
+        _ => return Err("[Syntax Error] return statement outside function.".to_string()),
+    };
+
+    match (func_type, exp_type) {
+        (None, None | Some(Type::TUnit)) => Ok(ControlFlow::Return(Type::TUnit)),
+        (None, Some(_)) => Err(format!(
+            "[Type Error] '{}()' does not return a value.",
+            env.scope_name()
+        )),
+        (Some(func_type), exp_type) => {
+            let exp_type = exp_type.unwrap_or(Type::TUnit);
 
-    match (left_type, right_type) {
-        (Type::TInteger, Type::TInteger) => Ok(Type::TInteger),
-        (Type::TInteger, Type::TReal) => Ok(Type::TReal),
-        (Type::TReal, Type::TInteger) => Ok(Type::TReal),
-        (Type::TReal, Type::TReal) => Ok(Type::TReal),
-        _ => Err(String::from("[Type Error] expecting numeric type values.")),
+            if !matches_type(&func_type, &exp_type, env) {
+                return Err(format!(
+                    "[Type Error] '{}()' has mismatched types: expected '{:?}', found '{:?}'.",
+                    env.scope_name(),
+                    resolve(&func_type, env),
+                    exp_type
+                ));
+            }
+
+            Ok(ControlFlow::Return(resolve(&func_type, env)))
+        }
     }
 }
 
-fn check_bin_boolean_expression(
-    left: Expression,
-    right: Expression,
+/* joins the environments that the paths through a statement end in: a variable
+assigned on every path is declared with the join of its types, while one assigned
+on only some of them cannot be read until it is assigned again */
+fn join_paths(
     env: &Environment<Type>,
-) -> Result<Type, ErrorMessage> {
-    let left_type = check_exp(left, env)?;
-    let right_type = check_exp(right, env)?;
-    match (left_type, right_type) {
-        (Type::TBool, Type::TBool) => Ok(Type::TBool),
-        _ => Err(String::from("[Type Error] expecting boolean type values.")),
-    }
-}
+    paths: &[Environment<Type>],
+) -> Result<Environment<Type>, ErrorMessage> {
+    let mut new_env = env.clone();
+    let frames: Vec<_> = paths
+        .iter()
+        .map(|path| path.get_frame(env.scope_key()))
+        .collect();
+
+    let mut names: Vec<&Name> = frames
+        .iter()
+        .flat_map(|frame| frame.variables.keys())
+        .filter(|name| env.search_frame((*name).clone()).is_none())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    for name in names {
+        let kinds: Option<Vec<&Type>> = frames
+            .iter()
+            .map(|frame| frame.variables.get(name))
+            .collect();
+
+        let kinds = match kinds {
+            Some(kinds) => kinds,
+            None => {
+                new_env.insert_unassigned(name.clone());
+                continue;
+            }
+        };
 
-fn check_not_expression(exp: Expression, env: &Environment<Type>) -> Result<Type, ErrorMessage> {
-    let exp_type = check_exp(exp, env)?;
+        let mut kind = kinds[0].clone();
 
-    match exp_type {
-        Type::TBool => Ok(Type::TBool),
-        _ => Err(String::from("[Type Error] expecting a boolean type value.")),
+        for other in &kinds[1..] {
+            kind = match join_types(&kind, other, env) {
+                Some(joined) => joined,
+                None => return Err(format!(
+                    "[Type Error on '{}()'] '{}' has mismatched types across branches: expected '{:?}', found '{:?}'.",
+                    env.scope_name(),
+                    name,
+                    kind,
+                    other
+                )),
+            };
+        }
+        new_env.insert_variable(name.clone(), kind);
+    }
+
+    for frame in frames {
+        for name in &frame.unassigned {
+            if new_env.search_frame(name.clone()).is_none() {
+                new_env.insert_unassigned(name.clone());
+            }
+        }
     }
+
+    Ok(new_env)
 }
 
-fn check_bin_relational_expression(
-    left: Expression,
-    right: Expression,
+/* the outcome of a statement whose paths end as 'flows': it continues in the join of the
+paths that continue, and otherwise returns when some path returns and raises when all
+of them raise */
+fn join_flows(
     env: &Environment<Type>,
-) -> Result<Type, ErrorMessage> {
-    let left_type = check_exp(left, env)?;
-    let right_type = check_exp(right, env)?;
+    flows: Vec<ControlFlow>,
+) -> Result<ControlFlow, ErrorMessage> {
+    let mut paths = vec![];
+    let mut return_type = None;
+
+    for flow in flows {
+        match flow {
+            ControlFlow::Continue(path_env) => paths.push(path_env),
+            ControlFlow::Return(kind) => return_type = return_type.or(Some(kind)),
+            ControlFlow::Raise => {}
+        }
+    }
 
-    match (left_type, right_type) {
-        (Type::TInteger, Type::TInteger) => Ok(Type::TBool),
-        (Type::TInteger, Type::TReal) => Ok(Type::TBool),
-        (Type::TReal, Type::TInteger) => Ok(Type::TBool),
-        (Type::TReal, Type::TReal) => Ok(Type::TBool),
-        _ => Err(String::from("[Type Error] expecting numeric type values.")),
+    if !paths.is_empty() {
+        return Ok(ControlFlow::Continue(join_paths(env, &paths)?));
+    }
+    match return_type {
+        Some(kind) => Ok(ControlFlow::Return(kind)),
+        None => Ok(ControlFlow::Raise),
     }
 }
 
-#[cfg(test)]
+/* 'path_env' with the assignments a later block made to 'env' in 'block_env' */
+fn assigned_after(
+    env: &Environment<Type>,
+    path_env: &Environment<Type>,
+    block_env: &Environment<Type>,
+) -> Environment<Type> {
+    let mut new_env = path_env.clone();
+    let (key, before, after) = match (
+        env.stack.get(&env.scope_key()),
+        block_env.stack.get(&block_env.scope_key()),
+    ) {
+        (Some(before), Some(after)) => (env.scope_key(), before, after),
+        _ => return new_env,
+    };
+
+    if let Some(frame) = new_env.stack.get_mut(&key) {
+        for (name, kind) in &after.variables {
+            if before.variables.get(name) != Some(kind) {
+                frame.variables.insert(name.clone(), kind.clone());
+                frame.unassigned.remove(name);
+            }
+        }
+    }
+    new_env
+}
+
+/* checks the statements of a block in order. The functions the block defines are
+declared once, before it is checked, rather than at every nested sequence */
+fn check_block(stmt: Statement, env: &Environment<Type>) -> Result<ControlFlow, ErrorMessage> {
+    match stmt {
+        Statement::Sequence(stmt1, stmt2) => match check_block(*stmt1, env)? {
+            ControlFlow::Continue(control_env) => check_block(*stmt2, &control_env),
+            /* statements after a return or a raise are still checked, but never reached */
+            flow => {
+                check_block(*stmt2, env)?;
+                Ok(flow)
+            }
+        },
+        stmt => check_stmt(stmt, env),
+    }
+}
+
+/* records the functions a block defines, so that the bodies of its functions may call
+the ones defined after them. The statements of the block itself still only see a
+function once its definition has run */
+fn declare_functions(stmt: &Statement, env: &mut Environment<Type>) {
+    match stmt {
+        Statement::Sequence(stmt1, stmt2) => {
+            declare_functions(stmt1, env);
+            declare_functions(stmt2, env);
+        }
+        Statement::FuncDef(func) => {
+            let key = env.scope_key();
+            if let Some(frame) = env.stack.get_mut(&key) {
+                frame.declared.insert(func.name.clone(), func.clone());
+            }
+        }
+        _ => (),
+    }
+}
+
+/* the type of a declared function, which is only known ahead of its definition when
+its signature is fully annotated, since the others are inferred from their bodies */
+fn declared_type(func: &Function) -> Option<Type> {
+    let params: Vec<Type> = func
+        .params
+        .iter()
+        .flatten()
+        .map(|(_, kind)| kind.clone())
+        .collect();
+
+    if params.contains(&Type::TUnknown) || func.kind == Some(Type::TUnknown) {
+        return None;
+    }
+    Some(Type::TFunction(Box::new(func.kind.clone()), params))
+}
+
+fn check_func_call(
+    name: String,
+    args: Vec<Expression>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    match check_var_name(name.clone(), env, false).map(|kind| expand(&kind, env)) {
+        Ok(Type::TFunction(kind, type_vec)) => {
+            let declaration = env.search_function(&name).cloned();
+            check_call(&name, *kind, type_vec, declaration, args, env)
+        }
+        _ => Err(format!(
+            "[Name Error on '{}()'] '{}()' is not defined.",
+            env.scope_name(),
+            name
+        )),
+    }
+}
+
+fn check_call(
+    name: &Name,
+    kind: Option<Type>,
+    type_vec: Vec<Type>,
+    declaration: Option<Function>,
+    args: Vec<Expression>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    /* only a call to a declared function, not to a function value, knows the names
+    and defaults of its parameters */
+    let declaration = declaration.unwrap_or_default();
+    let names = declaration
+        .params
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(param, _)| param)
+        .chain(std::iter::repeat(String::new()));
+    let func = Function {
+        name: name.clone(),
+        params: Some(names.zip(type_vec).collect()),
+        ..declaration
+    };
+
+    let (positional, keywords) =
+        split_arguments(name, args).map_err(|msg| format!("[Syntax Error] {}", msg))?;
+    let (bound, extra) = func
+        .bind_arguments(positional, keywords)
+        .map_err(|msg| format!("[Type Error on '{}()'] {}", env.scope_name(), msg))?;
+
+    let mut typed_args: Vec<(Type, Expression)> = bound
+        .into_iter()
+        .filter_map(|(_, param_type, arg)| Some((param_type, arg?)))
+        .collect();
+
+    if let Some((_, args_kind)) = &func.variadic {
+        typed_args.extend(extra.into_iter().map(|arg| (args_kind.clone(), arg)));
+    }
+
+    /* each call instantiates the type variables of a generic function afresh */
+    let mut subst = HashMap::new();
+
+    for (param_type, arg) in typed_args {
+        let arg_type = check_exp(arg, env)?;
+
+        match unify(&param_type, &arg_type, &mut subst, env) {
+            Ok(()) => (),
+            Err(UnifyError::Mismatch) => {
+                return Err(format!("[Type Error on '{}()'] '{}()' has mismatched arguments: expected '{:?}', found '{:?}'.", env.scope_name(), name, param_type, arg_type));
+            }
+            Err(UnifyError::Conflict(var, bound, found)) => {
+                return Err(format!("[Type Error on '{}()'] '{}()' cannot unify type variable '{}': bound to '{:?}', found '{:?}'.", env.scope_name(), name, var, bound, found));
+            }
+        }
+    }
+
+    match kind {
+        Some(kind) => Ok(substitute(&kind, &subst)),
+        None => Ok(Type::TUnit),
+    }
+}
+
+enum UnifyError {
+    Mismatch,
+    /* a type variable that two arguments bind to different types */
+    Conflict(Name, Type, Type),
+}
+
+/* binds the type variables of an expected type so that the found type matches it */
+fn unify(
+    expected: &Type,
+    found: &Type,
+    subst: &mut HashMap<Name, Type>,
+    env: &Environment<Type>,
+) -> Result<(), UnifyError> {
+    match (expected, found) {
+        (_, Type::TUnknown) => Ok(()),
+        (Type::TVar(var), found) if !Inference::is_inference_var(var) => {
+            if env.inference.is_numeric(var) && !is_number(&numeric_operand(found.clone(), env)) {
+                return Err(UnifyError::Mismatch);
+            }
+
+            let bound = match subst.get(var) {
+                Some(bound) => match join_types(bound, found, env) {
+                    Some(merged) => merged,
+                    None => {
+                        return Err(UnifyError::Conflict(
+                            var.clone(),
+                            bound.clone(),
+                            found.clone(),
+                        ))
+                    }
+                },
+                None => found.clone(),
+            };
+            subst.insert(var.clone(), bound);
+            Ok(())
+        }
+        (Type::TAlias(_), _) | (_, Type::TAlias(_)) => {
+            let (expanded, expanded_found) = (env.expand_alias(expected), env.expand_alias(found));
+
+            if expanded == *expected && expanded_found == *found {
+                return Err(UnifyError::Mismatch);
+            }
+            unify(&expanded, &expanded_found, subst, env)
+        }
+        (Type::TMaybe(expected), Type::TMaybe(found))
+        | (Type::TList(expected), Type::TList(found)) => unify(expected, found, subst, env),
+        (Type::TResult(expected_ok, expected_err), Type::TResult(found_ok, found_err)) => {
+            unify(expected_ok, found_ok, subst, env)?;
+            unify(expected_err, found_err, subst, env)
+        }
+        (Type::TTuple(expected), Type::TTuple(found)) if expected.len() == found.len() => {
+            for (expected, found) in expected.iter().zip(found) {
+                unify(expected, found, subst, env)?;
+            }
+            Ok(())
+        }
+        (
+            Type::TFunction(expected_kind, expected_params),
+            Type::TFunction(found_kind, found_params),
+        ) if expected_params.len() == found_params.len() => {
+            match (expected_kind.as_ref(), found_kind.as_ref()) {
+                (Some(expected), Some(found)) => unify(expected, found, subst, env)?,
+                (None, None) => (),
+                _ => return Err(UnifyError::Mismatch),
+            }
+            for (expected, found) in expected_params.iter().zip(found_params) {
+                unify(expected, found, subst, env)?;
+            }
+            Ok(())
+        }
+        (expected, found) if matches_type(expected, found, env) => Ok(()),
+        _ => Err(UnifyError::Mismatch),
+    }
+}
+
+/* replaces bound type variables, leaving the unbound ones undetermined */
+fn substitute(kind: &Type, subst: &HashMap<Name, Type>) -> Type {
+    match kind {
+        Type::TVar(var) if Inference::is_inference_var(var) => kind.clone(),
+        Type::TVar(var) => subst.get(var).cloned().unwrap_or(Type::TUnknown),
+        Type::TMaybe(kind) => Type::TMaybe(Box::new(substitute(kind, subst))),
+        Type::TList(kind) => Type::TList(Box::new(substitute(kind, subst))),
+        Type::TResult(kind, err) => Type::TResult(
+            Box::new(substitute(kind, subst)),
+            Box::new(substitute(err, subst)),
+        ),
+        Type::TTuple(kinds) => {
+            Type::TTuple(kinds.iter().map(|kind| substitute(kind, subst)).collect())
+        }
+        Type::TFunction(kind, params) => Type::TFunction(
+            Box::new(kind.as_ref().as_ref().map(|kind| substitute(kind, subst))),
+            params
+                .iter()
+                .map(|param| substitute(param, subst))
+                .collect(),
+        ),
+        kind => kind.clone(),
+    }
+}
+
+/* applies the bindings of inference variables made so far */
+fn resolve(kind: &Type, env: &Environment<Type>) -> Type {
+    match kind {
+        Type::TVar(var) => match env.inference.binding(var) {
+            Some(bound) => resolve(&bound, env),
+            None => kind.clone(),
+        },
+        Type::TMaybe(kind) => Type::TMaybe(Box::new(resolve(kind, env))),
+        Type::TList(kind) => Type::TList(Box::new(resolve(kind, env))),
+        Type::TResult(kind, err) => {
+            Type::TResult(Box::new(resolve(kind, env)), Box::new(resolve(err, env)))
+        }
+        Type::TTuple(kinds) => Type::TTuple(kinds.iter().map(|kind| resolve(kind, env)).collect()),
+        Type::TFunction(kind, params) => Type::TFunction(
+            Box::new(kind.as_ref().as_ref().map(|kind| resolve(kind, env))),
+            params.iter().map(|param| resolve(param, env)).collect(),
+        ),
+        kind => kind.clone(),
+    }
+}
+
+/* the structure a type stands for, looking through inference variables and aliases */
+fn expand(kind: &Type, env: &Environment<Type>) -> Type {
+    resolve(&env.expand_alias(&resolve(kind, env)), env)
+}
+
+fn occurs(var: &Name, kind: &Type) -> bool {
+    match kind {
+        Type::TVar(other) => other == var,
+        Type::TMaybe(kind) | Type::TList(kind) => occurs(var, kind),
+        Type::TResult(kind, err) => occurs(var, kind) || occurs(var, err),
+        Type::TTuple(kinds) => kinds.iter().any(|kind| occurs(var, kind)),
+        Type::TFunction(kind, params) => {
+            kind.as_ref().as_ref().is_some_and(|kind| occurs(var, kind))
+                || params.iter().any(|param| occurs(var, param))
+        }
+        _ => false,
+    }
+}
+
+/* inference variables left unbound by a function body make the function generic; those
+used in arithmetic stay restricted to numbers */
+fn generalize(kind: &Type, env: &Environment<Type>) -> Type {
+    match kind {
+        Type::TVar(var) if Inference::is_inference_var(var) => {
+            let name = format!("T{}", var.trim_start_matches('?'));
+
+            if env.inference.is_numeric(var) {
+                env.inference.constrain_numeric(name.clone());
+            }
+            Type::TVar(name)
+        }
+        Type::TMaybe(kind) => Type::TMaybe(Box::new(generalize(kind, env))),
+        Type::TList(kind) => Type::TList(Box::new(generalize(kind, env))),
+        Type::TResult(kind, err) => Type::TResult(
+            Box::new(generalize(kind, env)),
+            Box::new(generalize(err, env)),
+        ),
+        Type::TTuple(kinds) => {
+            Type::TTuple(kinds.iter().map(|kind| generalize(kind, env)).collect())
+        }
+        Type::TFunction(kind, params) => Type::TFunction(
+            Box::new(kind.as_ref().as_ref().map(|kind| generalize(kind, env))),
+            params.iter().map(|param| generalize(param, env)).collect(),
+        ),
+        kind => kind.clone(),
+    }
+}
+
+/* binds an inference variable, unless it only stands for numbers and the type is not one */
+fn bind_var(var: &Name, kind: &Type, env: &Environment<Type>) -> bool {
+    if env.inference.is_numeric(var) && !is_number(&numeric_operand(kind.clone(), env)) {
+        return false;
+    }
+    env.inference.bind(var.clone(), kind.clone());
+    true
+}
+
+/* binds an undetermined operand to the type an operation requires of it; a dynamic
+operand is assumed to have that type */
+fn require(kind: Type, required: Type, env: &Environment<Type>) -> Type {
+    match expand(&kind, env) {
+        Type::TVar(var) if Inference::is_inference_var(&var) => {
+            match bind_var(&var, &required, env) {
+                true => required,
+                false => Type::TVar(var),
+            }
+        }
+        Type::TAny => required,
+        kind => kind,
+    }
+}
+
+/* an undetermined operand of a numeric operation is only required to be a number, so
+that a function left unannotated stays generic over both kinds of number */
+fn require_numeric(left: Type, right: Type, env: &Environment<Type>) -> (Type, Type) {
+    (numeric_operand(left, env), numeric_operand(right, env))
+}
+
+fn numeric_operand(kind: Type, env: &Environment<Type>) -> Type {
+    match expand(&kind, env) {
+        Type::TVar(var) if Inference::is_inference_var(&var) => {
+            env.inference.constrain_numeric(var.clone());
+            Type::TVar(var)
+        }
+        kind => kind,
+    }
+}
+
+/* a number, a dynamic value, or an undetermined operand of arithmetic */
+fn is_number(kind: &Type) -> bool {
+    match kind {
+        Type::TInteger | Type::TReal | Type::TAny => true,
+        Type::TVar(var) => Inference::is_inference_var(var),
+        _ => false,
+    }
+}
+
+/* the types inferred for unannotated variables and functions, as (scope, name, type) */
+pub fn inferred_types(env: &Environment<Type>) -> Vec<(Name, Name, Type)> {
+    env.inference
+        .inferred()
+        .into_iter()
+        .map(|(scope, name, kind)| (scope, name, resolve(&kind, env)))
+        .collect()
+}
+
+fn check_assignment_target(
+    name: Name,
+    exp_type: &Type,
+    kind: Option<Type>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let stated_type = match kind {
+        Some(state_type) => state_type,
+        None => match check_var_name(name.clone(), env, true) {
+            Ok(var_type) => var_type,
+            /* the first assignment to an unannotated variable declares it with the type of its value */
+            Err(_) if check_var_name(name.clone(), env, false).is_err() => {
+                env.inference
+                    .record(env.scope_name(), name, exp_type.clone());
+                return Ok(exp_type.clone());
+            }
+            Err(err) => return Err(err),
+        },
+    };
+
+    match merge_types(&stated_type, exp_type, env) {
+        Some(var_type) => Ok(var_type),
+        None => Err(format!(
+            "[Type Error on '{}()'] '{}' has mismatched types: expected '{:?}', found '{:?}'.",
+            env.scope_name(),
+            name,
+            stated_type,
+            exp_type
+        )),
+    }
+}
+
+/* a found type matches an expected one when they are equal up to their 'TUnknown'
+parts, or when it is a subtype: an 'int' for a 'real', or a subclass for its ancestor */
+fn matches_type(expected: &Type, found: &Type, env: &Environment<Type>) -> bool {
+    merge_types(expected, found, env).is_some()
+}
+
+/* the least type both types match, as needed where branches produce values */
+pub fn join_types(left: &Type, right: &Type, env: &Environment<Type>) -> Option<Type> {
+    merge_types(left, right, env).or_else(|| merge_types(right, left, env))
+}
+
+/* combines two matching types, filling the 'TUnknown' parts of one with the other
+and binding the inference variables of either side */
+fn merge_types(left: &Type, right: &Type, env: &Environment<Type>) -> Option<Type> {
+    let (left, right) = (&resolve(left, env), &resolve(right, env));
+
+    match (left, right) {
+        (Type::TUnknown, kind) | (kind, Type::TUnknown) => Some(kind.clone()),
+        /* 'TAny' is consistent with every type; the interpreter checks the values at runtime */
+        (Type::TAny, _) => Some(Type::TAny),
+        (kind, Type::TAny) => Some(kind.clone()),
+        (Type::TVar(var), kind) | (kind, Type::TVar(var)) if Inference::is_inference_var(var) => {
+            if *kind == Type::TVar(var.clone()) {
+                return Some(kind.clone());
+            }
+            if occurs(var, kind) || !bind_var(var, kind, env) {
+                return None;
+            }
+            Some(kind.clone())
+        }
+        /* an alias merges as the type it names, and is kept when nothing was filled in */
+        (Type::TAlias(_), _) => {
+            let expanded = env.expand_alias(left);
+
+            if expanded == *left {
+                return None;
+            }
+            let merged = merge_types(&expanded, right, env)?;
+            Some(if merged == expanded {
+                left.clone()
+            } else {
+                merged
+            })
+        }
+        (_, Type::TAlias(_)) => {
+            let expanded = env.expand_alias(right);
+
+            if expanded == *right {
+                return None;
+            }
+            merge_types(left, &expanded, env)
+        }
+        /* an 'int' widens to a 'real' */
+        (Type::TReal, Type::TInteger) => Some(Type::TReal),
+        (Type::TClass(left), Type::TClass(right)) if env.is_subclass(right, left) => {
+            Some(Type::TClass(left.clone()))
+        }
+        (Type::TMaybe(left), Type::TMaybe(right)) => {
+            Some(Type::TMaybe(Box::new(merge_types(left, right, env)?)))
+        }
+        (Type::TResult(left_ok, left_err), Type::TResult(right_ok, right_err)) => {
+            Some(Type::TResult(
+                Box::new(merge_types(left_ok, right_ok, env)?),
+                Box::new(merge_types(left_err, right_err, env)?),
+            ))
+        }
+        (Type::TList(left), Type::TList(right)) => {
+            Some(Type::TList(Box::new(merge_types(left, right, env)?)))
+        }
+        (Type::TTuple(left), Type::TTuple(right)) if left.len() == right.len() => {
+            let mut kinds = vec![];
+            for (left, right) in left.iter().zip(right) {
+                kinds.push(merge_types(left, right, env)?);
+            }
+            Some(Type::TTuple(kinds))
+        }
+        (left, right) if left == right => Some(left.clone()),
+        _ => None,
+    }
+}
+
+/* checks a 'Maybe' and/or 'Result' operand, returning the type of its success value */
+fn check_maybe_or_result(
+    exp: Expression,
+    helper: &str,
+    maybe: bool,
+    result: bool,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    match expand(&check_exp(exp, env)?, env) {
+        Type::TAny => Ok(Type::TAny),
+        Type::TMaybe(kind) if maybe => Ok(*kind),
+        Type::TResult(kind, _) if result => Ok(*kind),
+        kind => Err(format!(
+            "[Type Error on '{}()'] {} expects a {} value, found '{:?}'.",
+            env.scope_name(),
+            helper,
+            match (maybe, result) {
+                (true, true) => "'Maybe' or 'Result'",
+                (true, false) => "'Maybe'",
+                _ => "'Result'",
+            },
+            kind
+        )),
+    }
+}
+
+/* '?' may only propagate into a function that returns the same kind of value */
+fn check_propagate(exp: Expression, env: &Environment<Type>) -> Result<Type, ErrorMessage> {
+    let exp_type = check_exp(exp, env)?;
+
+    let func_type = match env.scope_return() {
+        Some(Type::TFunction(func_type, _)) => *func_type.clone(),
+        _ => {
+            return Err(format!(
+                "[Syntax Error] '?' used outside a function on '{}()'.",
+                env.scope_name()
+            ))
+        }
+    };
+
+    let expanded_func_type = func_type.as_ref().map(|kind| expand(kind, env));
+
+    match (&expand(&exp_type, env), &expanded_func_type) {
+        (Type::TAny, Some(Type::TMaybe(_) | Type::TResult(_, _))) => Ok(Type::TAny),
+        (Type::TMaybe(kind), Some(Type::TMaybe(_))) => Ok(*kind.clone()),
+        (Type::TResult(kind, err), Some(Type::TResult(_, func_err)))
+            if matches_type(func_err, err, env) =>
+        {
+            Ok(*kind.clone())
+        }
+        (Type::TMaybe(_), _) | (Type::TResult(_, _), _) => Err(format!(
+            "[Type Error on '{}()'] '?' cannot propagate '{:?}' from a function returning '{:?}'.",
+            env.scope_name(),
+            exp_type,
+            func_type
+        )),
+        _ => Err(format!(
+            "[Type Error on '{}()'] '?' expects a 'Maybe' or 'Result' value, found '{:?}'.",
+            env.scope_name(),
+            exp_type
+        )),
+    }
+}
+
+/* checks a module the first time it is imported, in an environment of its own;
+later imports reuse the interface it exports */
+fn check_module(name: &Name, env: &Environment<Type>) -> Result<Environment<Type>, ErrorMessage> {
+    if let Some(module_env) = env.modules.search(name) {
+        return Ok(module_env);
+    }
+
+    let program = match env.modules.load(name, &env.sandbox) {
+        Ok(Some(program)) => program,
+        Ok(None) => {
+            return Err(format!(
+                "[Import Error on '{}()'] no module named '{}'.",
+                env.scope_name(),
+                name
+            ))
+        }
+        Err(msg) => {
+            return Err(format!(
+                "[Import Error on '{}()'] {}.",
+                env.scope_name(),
+                msg
+            ))
+        }
+    };
+
+    env.modules.begin(name).map_err(|cycle| {
+        format!(
+            "[Import Error on '{}()'] circular import: {}.",
+            env.scope_name(),
+            cycle.join(" -> ")
+        )
+    })?;
+
+    let result = match check_stmt(program, &env.for_module(name)) {
+        Ok(ControlFlow::Continue(module_env)) => Ok(module_env),
+        Ok(ControlFlow::Return(_)) => Err(format!(
+            "[Syntax Error] module '{}' returns outside a function.",
+            name
+        )),
+        Ok(ControlFlow::Raise) => Err(format!(
+            "[Import Error on '{}()'] module '{}' raises on every path.",
+            env.scope_name(),
+            name
+        )),
+        Err(msg) => Err(msg),
+    };
+
+    env.modules.finish(name, result.clone().ok());
+    result
+}
+
+/* the types a module defines come along with the values it exports */
+fn import_definitions(
+    module: &Name,
+    module_env: &Environment<Type>,
+    env: &mut Environment<Type>,
+) -> Result<(), ErrorMessage> {
+    for (name, definition) in &module_env.definitions {
+        match env.search_definition(name) {
+            Some(existing) if existing != definition => {
+                return Err(format!(
+                    "[Import Error on '{}()'] type '{}' from module '{}' conflicts with an existing definition.",
+                    env.scope_name(),
+                    name,
+                    module
+                ))
+            }
+            Some(_) => (),
+            None => env.insert_definition(name.clone(), definition.clone()),
+        }
+    }
+    Ok(())
+}
+
+/* the type of a name a module exports, once the module is checked */
+fn check_module_attribute(
+    module: &Name,
+    name: &Name,
+    env: &Environment<Type>,
+) -> Result<(Type, Option<Function>), ErrorMessage> {
+    let module_env = check_module(module, env)?;
+
+    match module_env.search_export(name) {
+        Some(kind) => Ok((kind.clone(), module_env.search_function(name).cloned())),
+        None => Err(format!(
+            "[Type Error on '{}()'] module '{}' has no attribute '{}'.",
+            env.scope_name(),
+            module,
+            name
+        )),
+    }
+}
+
+/* an alias must name types that are already defined; since it is not defined yet
+itself, naming it again anywhere in its target would make it recursive */
+fn check_alias_target(
+    name: &Name,
+    kind: &Type,
+    env: &Environment<Type>,
+) -> Result<(), ErrorMessage> {
+    match kind {
+        Type::TAlias(other) if other == name => Err(format!(
+            "[Type Error on '{}()'] type alias '{}' is recursive.",
+            env.scope_name(),
+            name
+        )),
+        Type::TAlias(other) | Type::TRecord(other) | Type::TAdt(other) | Type::TClass(other)
+            if env.search_definition(other).is_none() =>
+        {
+            Err(format!(
+                "[Name Error on '{}()'] type '{}' is not defined.",
+                env.scope_name(),
+                other
+            ))
+        }
+        Type::TMaybe(kind) | Type::TList(kind) => check_alias_target(name, kind, env),
+        Type::TResult(kind, err) => {
+            check_alias_target(name, kind, env)?;
+            check_alias_target(name, err, env)
+        }
+        Type::TTuple(kinds) => kinds
+            .iter()
+            .try_for_each(|kind| check_alias_target(name, kind, env)),
+        Type::TFunction(kind, params) => {
+            if let Some(kind) = kind.as_ref() {
+                check_alias_target(name, kind, env)?;
+            }
+            params
+                .iter()
+                .try_for_each(|param| check_alias_target(name, param, env))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_record(
+    name: Name,
+    fields: Vec<(Name, Expression)>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let declared_fields = check_record_definition(name.clone(), env)?;
+
+    for (field, exp) in &fields {
+        let field_type = match declared_fields
+            .iter()
+            .find(|(declared, _)| declared == field)
+        {
+            Some((_, field_type)) => field_type.clone(),
+            None => {
+                return Err(format!(
+                    "[Type Error on '{}()'] record '{}' has no field '{}'.",
+                    env.scope_name(),
+                    name,
+                    field
+                ))
+            }
+        };
+
+        let exp_type = check_exp(exp.clone(), env)?;
+
+        if !matches_type(&field_type, &exp_type, env) {
+            return Err(format!("[Type Error on '{}()'] '{}.{}' has mismatched types: expected '{:?}', found '{:?}'.", env.scope_name(), name, field, field_type, exp_type));
+        }
+    }
+
+    for (declared, _) in &declared_fields {
+        if !fields.iter().any(|(field, _)| field == declared) {
+            return Err(format!(
+                "[Type Error on '{}()'] record '{}' is missing field '{}'.",
+                env.scope_name(),
+                name,
+                declared
+            ));
+        }
+    }
+
+    if fields.len() != declared_fields.len() {
+        return Err(format!(
+            "[Type Error on '{}()'] record '{}' has repeated fields.",
+            env.scope_name(),
+            name
+        ));
+    }
+
+    Ok(Type::TRecord(name))
+}
+
+fn check_field_access(
+    exp: Expression,
+    field: Name,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let exp_type = check_exp(exp, env)?;
+
+    check_field_type(exp_type, field, env)
+}
+
+fn check_field_type(
+    record_type: Type,
+    field: Name,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let name = match expand(&record_type, env) {
+        Type::TRecord(name) => name,
+        Type::TAny => return Ok(Type::TAny),
+        Type::TModule(module) => return Ok(check_module_attribute(&module, &field, env)?.0),
+        Type::TClass(class) => {
+            return match env
+                .class_fields(&class)
+                .into_iter()
+                .find(|(declared, _)| *declared == field)
+            {
+                Some((_, field_type)) => Ok(field_type),
+                None => Err(format!(
+                    "[Type Error on '{}()'] class '{}' has no field '{}'.",
+                    env.scope_name(),
+                    class,
+                    field
+                )),
+            }
+        }
+        _ => {
+            return Err(format!(
+                "[Type Error on '{}()'] field access '.{}' expects a record, found '{:?}'.",
+                env.scope_name(),
+                field,
+                record_type
+            ))
+        }
+    };
+
+    let declared_fields = check_record_definition(name.clone(), env)?;
+
+    match declared_fields
+        .into_iter()
+        .find(|(declared, _)| *declared == field)
+    {
+        Some((_, field_type)) => Ok(field_type),
+        None => Err(format!(
+            "[Type Error on '{}()'] record '{}' has no field '{}'.",
+            env.scope_name(),
+            name,
+            field
+        )),
+    }
+}
+
+fn check_record_definition(
+    name: Name,
+    env: &Environment<Type>,
+) -> Result<Vec<(Name, Type)>, ErrorMessage> {
+    match env.search_definition(&name) {
+        Some(TypeDefinition::Record(fields)) => Ok(fields.clone()),
+        _ => Err(format!(
+            "[Name Error on '{}()'] record '{}' is not defined.",
+            env.scope_name(),
+            name
+        )),
+    }
+}
+
+fn check_class_definition(class: &Class, env: &Environment<Type>) -> Result<(), ErrorMessage> {
+    if env.search_definition(&class.name).is_some() {
+        return Err(format!(
+            "[Type Error on '{}()'] type '{}' is already defined.",
+            env.scope_name(),
+            class.name
+        ));
+    }
+
+    if let Some(parent) = &class.parent {
+        if env.search_class(parent).is_none() {
+            return Err(format!(
+                "[Name Error on '{}()'] class '{}' is not defined.",
+                env.scope_name(),
+                parent
+            ));
+        }
+    }
+
+    let mut fields = match &class.parent {
+        Some(parent) => env.class_fields(parent),
+        None => vec![],
+    };
+
+    for (field, field_type) in &class.fields {
+        if fields.iter().any(|(other, _)| other == field) {
+            return Err(format!(
+                "[Type Error on '{}()'] class '{}' has duplicate field '{}'.",
+                env.scope_name(),
+                class.name,
+                field
+            ));
+        }
+
+        match field_type {
+            Type::TFunction(_, _) => {
+                return Err(format!(
+                    "[Type Error on '{}()'] class field '{}.{}' cannot have a function type.",
+                    env.scope_name(),
+                    class.name,
+                    field
+                ))
+            }
+            Type::TRecord(other) | Type::TAdt(other) | Type::TClass(other)
+                if *other != class.name && env.search_definition(other).is_none() =>
+            {
+                return Err(format!(
+                    "[Name Error on '{}()'] type '{}' is not defined.",
+                    env.scope_name(),
+                    other
+                ))
+            }
+            _ => (),
+        }
+
+        fields.push((field.clone(), field_type.clone()));
+    }
+
+    for (index, method) in class.methods.iter().enumerate() {
+        let qualified_name = format!("{}.{}", class.name, method.name);
+
+        if class.methods[..index]
+            .iter()
+            .any(|other| other.name == method.name)
+        {
+            return Err(format!(
+                "[Type Error on '{}()'] class '{}' has duplicate method '{}()'.",
+                env.scope_name(),
+                class.name,
+                method.name
+            ));
+        }
+
+        let params = method.params.clone().unwrap_or_default();
+
+        if params.first() != Some(&(String::from("self"), Type::TClass(class.name.clone()))) {
+            return Err(format!(
+                "[Type Error on '{}()'] method '{}()' must take 'self: {}' as its first parameter.",
+                env.scope_name(),
+                qualified_name,
+                class.name
+            ));
+        }
+
+        if method.name == "__init__" && method.kind.is_some() {
+            return Err(format!(
+                "[Type Error on '{}()'] initializer '{}()' cannot return a value.",
+                env.scope_name(),
+                qualified_name
+            ));
+        }
+
+        /* an override takes the same parameters and returns a subtype of what the overridden method returns */
+        let overridden = class
+            .parent
+            .as_ref()
+            .and_then(|parent| env.search_method(parent, &method.name));
+
+        if let Some(overridden) = overridden {
+            let overridden_params = overridden.params.clone().unwrap_or_default();
+
+            let same_params = params.len() == overridden_params.len()
+                && params[1..]
+                    .iter()
+                    .zip(&overridden_params[1..])
+                    .all(|((_, kind), (_, other))| kind == other);
+
+            let same_return = match (&method.kind, &overridden.kind) {
+                (None, None) => true,
+                (Some(kind), Some(other)) => matches_type(other, kind, env),
+                _ => false,
+            };
+
+            if method.name != "__init__" && !(same_params && same_return) {
+                return Err(format!(
+                    "[Type Error on '{}()'] method '{}()' does not match the signature of the method it overrides.",
+                    env.scope_name(),
+                    qualified_name
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/* methods are checked in a frame named after their class, like 'Point.area()' */
+fn check_method(
+    class: &Name,
+    method: Function,
+    env: &Environment<Type>,
+) -> Result<(), ErrorMessage> {
+    let mut new_env = env.clone();
+    let qualified = Function {
+        name: format!("{}.{}", class, method.name),
+        ..method
+    };
+
+    new_env.insert_frame(qualified.clone());
+
+    let params = qualified.params.clone().unwrap_or_default();
+    check_duplicate_params(&params)?;
+
+    let mut type_vec = vec![];
+
+    for (param_name, param_kind) in params {
+        new_env.insert_variable(param_name, param_kind.clone());
+        type_vec.push(param_kind);
+    }
+
+    check_defaults(&qualified, &type_vec, env)?;
+
+    if let Some((args_name, args_kind)) = qualified.variadic.clone() {
+        new_env.insert_variable(args_name, Type::TList(Box::new(args_kind)));
+    }
+
+    new_env.insert_variable(
+        qualified.name.clone(),
+        Type::TFunction(Box::new(qualified.kind.clone()), type_vec),
+    );
+
+    match check_stmt(*qualified.body.unwrap(), &new_env)? {
+        ControlFlow::Continue(_) if !is_procedure(&qualified.kind) => Err(format!(
+            "[Type Error] '{}()' does not return a value on every path.",
+            qualified.name
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_new(
+    name: Name,
+    args: Vec<Expression>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    if env.search_class(&name).is_none() {
+        return Err(format!(
+            "[Name Error on '{}()'] class '{}' is not defined.",
+            env.scope_name(),
+            name
+        ));
+    }
+
+    let init = env
+        .search_method(&name, &String::from("__init__"))
+        .unwrap_or_default();
+
+    check_method_arguments(&name, init, args, env)?;
+
+    Ok(Type::TClass(name))
+}
+
+fn check_method_call(
+    exp: Expression,
+    method: Name,
+    args: Vec<Expression>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let class = match expand(&check_exp(exp, env)?, env) {
+        Type::TClass(class) => class,
+        Type::TAny => {
+            for arg in args {
+                check_exp(arg, env)?;
+            }
+            return Ok(Type::TAny);
+        }
+        /* 'module.f(...)' calls a function the module exports */
+        Type::TModule(module) => {
+            let qualified_name = format!("{}.{}", module, method);
+
+            return match check_module_attribute(&module, &method, env)? {
+                (Type::TFunction(kind, type_vec), declaration) => {
+                    check_call(&qualified_name, *kind, type_vec, declaration, args, env)
+                }
+                (kind, _) => Err(format!(
+                    "[Type Error on '{}()'] '{}' is not a function, found '{:?}'.",
+                    env.scope_name(),
+                    qualified_name,
+                    kind
+                )),
+            };
+        }
+        kind => {
+            return Err(format!(
+                "[Type Error on '{}()'] method call '.{}()' expects an object, found '{:?}'.",
+                env.scope_name(),
+                method,
+                kind
+            ))
+        }
+    };
+
+    let func = match env.search_method(&class, &method) {
+        Some(func) => func,
+        None => {
+            return Err(format!(
+                "[Type Error on '{}()'] class '{}' has no method '{}()'.",
+                env.scope_name(),
+                class,
+                method
+            ))
+        }
+    };
+
+    let qualified_name = format!("{}.{}", class, method);
+
+    check_method_arguments(&qualified_name, func, args, env)
+}
+
+/* the arguments of a method are bound like those of a function, after the receiver is
+bound to its first parameter */
+fn check_method_arguments(
+    name: &Name,
+    method: Function,
+    args: Vec<Expression>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let params: Vec<(Name, Type)> = method
+        .params
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .skip(1)
+        .collect();
+    let type_vec = params.iter().map(|(_, kind)| kind.clone()).collect();
+    let declaration = Function {
+        params: Some(params),
+        ..method
+    };
+
+    check_call(
+        name,
+        declaration.kind.clone(),
+        type_vec,
+        Some(declaration),
+        args,
+        env,
+    )
+}
+
+fn check_constructor(
+    name: Name,
+    args: Vec<Expression>,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let (adt_name, types) = match env.search_constructor(&name) {
+        Some(constructor) => constructor,
+        None => {
+            return Err(format!(
+                "[Name Error on '{}()'] constructor '{}' is not defined.",
+                env.scope_name(),
+                name
+            ))
+        }
+    };
+
+    if args.len() != types.len() {
+        return Err(format!(
+            "[Type Error on '{}()'] constructor '{}' expected {} arguments, found {}.",
+            env.scope_name(),
+            name,
+            types.len(),
+            args.len()
+        ));
+    }
+
+    for (arg, kind) in args.into_iter().zip(types) {
+        let arg_type = check_exp(arg, env)?;
+
+        if !matches_type(&kind, &arg_type, env) {
+            return Err(format!("[Type Error on '{}()'] constructor '{}' has mismatched arguments: expected '{:?}', found '{:?}'.", env.scope_name(), name, kind, arg_type));
+        }
+    }
+
+    Ok(Type::TAdt(adt_name))
+}
+
+fn check_match_patterns(
+    patterns: &[Pattern],
+    kind: &Type,
+    env: &Environment<Type>,
+) -> Result<(), ErrorMessage> {
+    for (index, pattern) in patterns.iter().enumerate() {
+        if !is_reachable(&patterns[..index], pattern, kind, env) {
+            return Err(format!(
+                "[Match Error on '{}()'] match arm {} is unreachable.",
+                env.scope_name(),
+                index + 1
+            ));
+        }
+    }
+
+    if !is_exhaustive(patterns, kind, env) {
+        return Err(format!(
+            "[Match Error on '{}()'] match over '{:?}' is not exhaustive.",
+            env.scope_name(),
+            kind
+        ));
+    }
+
+    Ok(())
+}
+
+/* checks a pattern against the matched type and returns the environment with its bindings */
+fn check_pattern_bindings(
+    pattern: &Pattern,
+    kind: &Type,
+    env: &Environment<Type>,
+) -> Result<Environment<Type>, ErrorMessage> {
+    let mut bindings = vec![];
+    check_pattern(pattern, kind, env, &mut bindings)?;
+
+    for (index, (name, _)) in bindings.iter().enumerate() {
+        if bindings[..index].iter().any(|(other, _)| other == name) {
+            return Err(format!(
+                "[Match Error on '{}()'] '{}' is bound more than once in the same pattern.",
+                env.scope_name(),
+                name
+            ));
+        }
+    }
+
+    let mut new_env = env.clone();
+
+    for (name, binding_type) in bindings {
+        new_env.insert_variable(name, binding_type);
+    }
+
+    Ok(new_env)
+}
+
+fn check_pattern(
+    pattern: &Pattern,
+    kind: &Type,
+    env: &Environment<Type>,
+    bindings: &mut Vec<(Name, Type)>,
+) -> Result<(), ErrorMessage> {
+    let expanded = expand(kind, env);
+
+    match pattern {
+        Pattern::Wildcard => Ok(()),
+        Pattern::Binding(name) => {
+            bindings.push((name.clone(), kind.clone()));
+            Ok(())
+        }
+        Pattern::Literal(literal) => {
+            let literal_type = check_exp(literal.clone(), env)?;
+
+            if expanded != Type::TAny && literal_type != expanded {
+                return Err(format!(
+                    "[Type Error on '{}()'] pattern has mismatched types: expected '{:?}', found '{:?}'.",
+                    env.scope_name(),
+                    kind,
+                    literal_type
+                ));
+            }
+
+            Ok(())
+        }
+        Pattern::Constructor(name, patterns) => {
+            /* Just and Nothing match Maybe values, and Ok and Err match Result values */
+            let wrapped = wrapped_constructors(&expanded, env)
+                .and_then(|constructors| constructors.into_iter().find(|(cons, _)| cons == name));
+
+            let types = match wrapped {
+                Some((_, types)) => types,
+                None => {
+                    let (adt_name, types) = match env.search_constructor(name) {
+                        Some(constructor) => constructor,
+                        None => {
+                            return Err(format!(
+                                "[Name Error on '{}()'] constructor '{}' is not defined.",
+                                env.scope_name(),
+                                name
+                            ))
+                        }
+                    };
+
+                    if expanded != Type::TAny && Type::TAdt(adt_name.clone()) != expanded {
+                        return Err(format!(
+                            "[Type Error on '{}()'] pattern has mismatched types: expected '{:?}', found '{:?}'.",
+                            env.scope_name(),
+                            kind,
+                            Type::TAdt(adt_name)
+                        ));
+                    }
+                    types
+                }
+            };
+
+            if patterns.len() != types.len() {
+                return Err(format!(
+                    "[Type Error on '{}()'] constructor '{}' expected {} arguments, found {}.",
+                    env.scope_name(),
+                    name,
+                    types.len(),
+                    patterns.len()
+                ));
+            }
+
+            for (pattern, kind) in patterns.iter().zip(types) {
+                check_pattern(pattern, &kind, env, bindings)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn check_duplicate_params(params: &Vec<(Name, Type)>) -> Result<(), ErrorMessage> {
+    let mut seen_params = std::collections::HashSet::new();
+
+    for (name, _) in params {
+        if !seen_params.insert(name.clone()) {
+            return Err(format!(
+                "[Parameter Error] Duplicate parameter name '{}'",
+                name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_var_name(name: Name, env: &Environment<Type>, scoped: bool) -> Result<Type, ErrorMessage> {
+    let mut curr_scope = env.scope_key();
+
+    loop {
+        let frame = env.get_frame(curr_scope.clone());
+
+        match frame.variables.get(&name) {
+            Some(kind) => {
+                if scoped && curr_scope != env.scope_key() {
+                    return Err(format!(
+                        "[Local Name Error on '{}'] cannot access local variable '{}'.",
+                        env.scope_name(),
+                        name
+                    ));
+                } else {
+                    return Ok(kind.clone());
+                }
+            }
+            None if frame.unassigned.contains(&name) => {
+                return Err(format!(
+                    "[Name Error on '{}'] '{}' might not be assigned on every path.",
+                    env.scope_name(),
+                    name
+                ))
+            }
+            None => match &frame.parent_key {
+                Some(parent) => curr_scope = parent.clone(),
+                None => {
+                    return Err(format!(
+                        "[Name Error on '{}'] '{}' is not defined.",
+                        env.scope_name(),
+                        name
+                    ))
+                }
+            },
+        }
+    }
+}
+
+fn check_bin_arithmetic_expression(
+    left: Expression,
+    right: Expression,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let left_type = check_exp(left, env)?;
+    let right_type = check_exp(right, env)?;
+
+    match require_numeric(left_type, right_type, env) {
+        (left, right) if !is_number(&left) || !is_number(&right) => {
+            Err(String::from("[Type Error] expecting numeric type values."))
+        }
+        (Type::TAny, _) | (_, Type::TAny) => Ok(Type::TAny),
+        (Type::TReal, _) | (_, Type::TReal) => Ok(Type::TReal),
+        /* undetermined operands are the same kind of number as each other */
+        (Type::TVar(left), Type::TVar(right)) => {
+            if left != right {
+                env.inference.bind(right, Type::TVar(left.clone()));
+            }
+            Ok(Type::TVar(left))
+        }
+        /* with an 'int', the result is whatever kind of number the other operand is */
+        (Type::TVar(var), _) | (_, Type::TVar(var)) => Ok(Type::TVar(var)),
+        _ => Ok(Type::TInteger),
+    }
+}
+
+fn check_bin_boolean_expression(
+    left: Expression,
+    right: Expression,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let left_type = require(check_exp(left, env)?, Type::TBool, env);
+    let right_type = require(check_exp(right, env)?, Type::TBool, env);
+    match (left_type, right_type) {
+        (Type::TBool, Type::TBool) => Ok(Type::TBool),
+        _ => Err(String::from("[Type Error] expecting boolean type values.")),
+    }
+}
+
+fn check_not_expression(exp: Expression, env: &Environment<Type>) -> Result<Type, ErrorMessage> {
+    let exp_type = require(check_exp(exp, env)?, Type::TBool, env);
+
+    match exp_type {
+        Type::TBool => Ok(Type::TBool),
+        _ => Err(String::from("[Type Error] expecting a boolean type value.")),
+    }
+}
+
+fn check_bin_equality_expression(
+    left: Expression,
+    right: Expression,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let left_type = check_exp(left, env)?;
+    let right_type = check_exp(right, env)?;
+
+    if !is_comparable(&left_type, env) || !is_comparable(&right_type, env) {
+        return Err(String::from(
+            "[Type Error] functions cannot be compared for equality.",
+        ));
+    }
+
+    match (expand(&left_type, env), expand(&right_type, env)) {
+        (Type::TInteger, Type::TReal) => Ok(Type::TBool),
+        (Type::TReal, Type::TInteger) => Ok(Type::TBool),
+        _ if matches_type(&left_type, &right_type, env)
+            || matches_type(&right_type, &left_type, env) =>
+        {
+            Ok(Type::TBool)
+        }
+        _ => Err(format!(
+            "[Type Error] cannot compare '{:?}' with '{:?}'.",
+            left_type, right_type
+        )),
+    }
+}
+
+fn is_comparable(kind: &Type, env: &Environment<Type>) -> bool {
+    match expand(kind, env) {
+        Type::TFunction(_, _) => false,
+        Type::TList(elem_type) => is_comparable(&elem_type, env),
+        Type::TTuple(elem_types) => elem_types.iter().all(|kind| is_comparable(kind, env)),
+        Type::TMaybe(kind) => is_comparable(&kind, env),
+        Type::TResult(kind, err) => is_comparable(&kind, env) && is_comparable(&err, env),
+        _ => true,
+    }
+}
+
+fn check_bin_relational_expression(
+    left: Expression,
+    right: Expression,
+    env: &Environment<Type>,
+) -> Result<Type, ErrorMessage> {
+    let left_type = check_exp(left, env)?;
+    let right_type = check_exp(right, env)?;
+
+    match require_numeric(left_type, right_type, env) {
+        (left, right) if is_number(&left) && is_number(&right) => Ok(Type::TBool),
+        _ => Err(String::from("[Type Error] expecting numeric type values.")),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
 
-    use crate::ir::ast::Environment;
-    use crate::ir::ast::Expression::*;
-    use crate::ir::ast::Function;
-    use crate::ir::ast::Statement::*;
-    use crate::ir::ast::Type::*;
+    use crate::ir::ast::Class;
+    use crate::ir::ast::Environment;
+    use crate::ir::ast::Expression::*;
+    use crate::ir::ast::Function;
+    use crate::ir::ast::Pattern;
+    use crate::ir::ast::Statement::*;
+    use crate::ir::ast::Type::*;
+    use crate::ir::module::{MemoryLoader, Modules};
+    use crate::ir::sandbox::{Policy, Sandbox};
+    use crate::stdlib::builtins::checker_env;
+    use std::rc::Rc;
+
+    #[test]
+    fn check_tlist_comparison() {
+        let t_list1 = TList(Box::new(TInteger));
+        let t_list2 = TList(Box::new(TInteger));
+
+        assert_eq!(t_list1, t_list2);
+    }
+
+    #[test]
+    fn check_tlist_comparison_different_types() {
+        let t_list1 = TList(Box::new(TInteger));
+        let t_list2 = TList(Box::new(TBool));
+
+        assert_ne!(t_list1, t_list2);
+    }
+
+    #[test]
+    fn check_ttuple_comparison() {
+        let t_tuple1 = TTuple(vec![TInteger, TBool]);
+        let t_tuple2 = TTuple(vec![TInteger, TBool]);
+
+        assert_eq!(t_tuple1, t_tuple2);
+    }
+
+    #[test]
+    fn check_ttuple_comparison_different_types() {
+        let t_tuple1 = TTuple(vec![TInteger, TBool]);
+        let t_tuple2 = TTuple(vec![TBool, TInteger]);
+
+        assert_ne!(t_tuple1, t_tuple2);
+    }
+
+    #[test]
+    fn check_constant() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+
+        assert_eq!(check_exp(c10, &env), Ok(TInteger));
+    }
+
+    #[test]
+    fn check_add_integers() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CInt(20);
+        let add = Add(Box::new(c10), Box::new(c20));
+
+        assert_eq!(check_exp(add, &env), Ok(TInteger));
+    }
+
+    #[test]
+    fn check_add_reals() {
+        let env = Environment::new();
+
+        let c10 = CReal(10.5);
+        let c20 = CReal(20.3);
+        let add = Add(Box::new(c10), Box::new(c20));
+
+        assert_eq!(check_exp(add, &env), Ok(TReal));
+    }
+
+    #[test]
+    fn check_add_real_and_integer() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+        let c20 = CReal(20.3);
+        let add = Add(Box::new(c10), Box::new(c20));
+
+        assert_eq!(check_exp(add, &env), Ok(TReal));
+    }
+
+    #[test]
+    fn check_add_integer_and_real() {
+        let env = Environment::new();
+
+        let c10 = CReal(10.5);
+        let c20 = CInt(20);
+        let add = Add(Box::new(c10), Box::new(c20));
+
+        assert_eq!(check_exp(add, &env), Ok(TReal));
+    }
+
+    #[test]
+    fn check_type_error_arithmetic_expression() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+        let bool = CFalse;
+        let add = Add(Box::new(c10), Box::new(bool));
+
+        assert_eq!(
+            check_exp(add, &env),
+            Err(String::from("[Type Error] expecting numeric type values."))
+        );
+    }
+
+    #[test]
+    fn check_type_error_not_expression() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+        let not = Not(Box::new(c10));
+
+        assert_eq!(
+            check_exp(not, &env),
+            Err(String::from("[Type Error] expecting a boolean type value."))
+        );
+    }
+
+    #[test]
+    fn check_type_error_and_expression() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+        let bool = CTrue;
+        let and = And(Box::new(c10), Box::new(bool));
+
+        assert_eq!(
+            check_exp(and, &env),
+            Err(String::from("[Type Error] expecting boolean type values."))
+        );
+    }
+
+    #[test]
+    fn check_type_error_or_expression() {
+        let env = Environment::new();
+
+        let c10 = CInt(10);
+        let bool = CTrue;
+        let or = Or(Box::new(c10), Box::new(bool));
+
+        assert_eq!(
+            check_exp(or, &env),
+            Err(String::from("[Type Error] expecting boolean type values."))
+        );
+    }
+
+    #[test]
+    fn check_equality_of_strings_and_booleans() {
+        let env = Environment::new();
+
+        let eq = EQ(
+            Box::new(CString("a".to_string())),
+            Box::new(CString("b".to_string())),
+        );
+        let neq = NEQ(Box::new(CTrue), Box::new(CFalse));
+        let mixed = NEQ(Box::new(CInt(1)), Box::new(CReal(1.0)));
+
+        assert_eq!(check_exp(eq, &env), Ok(TBool));
+        assert_eq!(check_exp(neq, &env), Ok(TBool));
+        assert_eq!(check_exp(mixed, &env), Ok(TBool));
+    }
+
+    #[test]
+    fn check_type_error_equality_expression() {
+        let env = Environment::new();
+
+        let eq = EQ(Box::new(CString("1".to_string())), Box::new(CInt(1)));
+
+        assert_eq!(
+            check_exp(eq, &env),
+            Err(String::from(
+                "[Type Error] cannot compare 'TString' with 'TInteger'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_type_error_equality_of_functions() {
+        let mut env: Environment<Type> = Environment::new();
+        env.insert_variable("f".to_string(), TFunction(Box::new(Some(TInteger)), vec![]));
+
+        let neq = NEQ(
+            Box::new(Var("f".to_string())),
+            Box::new(Var("f".to_string())),
+        );
+
+        assert_eq!(
+            check_exp(neq, &env),
+            Err(String::from(
+                "[Type Error] functions cannot be compared for equality."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_assignment() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment = Assignment("a".to_string(), Box::new(CTrue), Some(TBool));
+
+        match check_stmt(assignment, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("a".to_string()), Some(TBool).as_ref());
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_assignment_error1() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment = Assignment("a".to_string(), Box::new(CTrue), Some(TInteger));
+
+        match check_stmt(assignment, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] 'a' has mismatched types: expected 'TInteger', found 'TBool'."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_assignment_error2() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment1 = Assignment("a".to_string(), Box::new(CTrue), Some(TBool));
+        let assignment2 = Assignment("a".to_string(), Box::new(CInt(1)), None);
+        let program = Sequence(Box::new(assignment1), Box::new(assignment2));
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] 'a' has mismatched types: expected 'TBool', found 'TInteger'."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_augmented_assignment() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment1 = Assignment("a".to_string(), Box::new(CReal(1.0)), Some(TReal));
+        let assignment2 = AddAssignment("a".to_string(), Box::new(CInt(1)));
+        let program = Sequence(Box::new(assignment1), Box::new(assignment2));
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("a".to_string()), Some(TReal).as_ref());
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_augmented_assignment_undeclared() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment = MulAssignment("a".to_string(), Box::new(CInt(2)));
+
+        match check_stmt(assignment, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(s, "[Name Error on '__main__'] 'a' is not defined."),
+        }
+    }
+
+    #[test]
+    fn check_augmented_assignment_error() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment1 = Assignment("a".to_string(), Box::new(CInt(1)), Some(TInteger));
+        let assignment2 = DivAssignment("a".to_string(), Box::new(CReal(2.0)));
+        let program = Sequence(Box::new(assignment1), Box::new(assignment2));
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] 'a' has mismatched types: expected 'TInteger', found 'TReal'."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_chained_assignment() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment1 = Assignment("a".to_string(), Box::new(CTrue), Some(TBool));
+        let assignment2 = ChainedAssignment(
+            vec!["a".to_string(), "b".to_string()],
+            Box::new(CInt(0)),
+            None,
+        );
+        let program = Sequence(Box::new(assignment1), Box::new(assignment2));
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] 'a' has mismatched types: expected 'TBool', found 'TInteger'."
+            ),
+        }
+
+        let assignment = ChainedAssignment(
+            vec!["a".to_string(), "b".to_string()],
+            Box::new(CInt(0)),
+            Some(TInteger),
+        );
+
+        match check_stmt(assignment, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("a".to_string()),
+                    Some(TInteger).as_ref()
+                );
+                assert_eq!(
+                    new_env.search_frame("b".to_string()),
+                    Some(TInteger).as_ref()
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_try_except() {
+        let env: Environment<Type> = Environment::new();
+
+        let program = TryExcept(
+            Box::new(Raise(
+                "ValueError".to_string(),
+                Box::new(CString("bad value".to_string())),
+            )),
+            vec![(
+                "ValueError".to_string(),
+                Some("e".to_string()),
+                Print(Box::new(Var("e".to_string()))),
+            )],
+            Some(Box::new(Print(Box::new(CString("done".to_string()))))),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(_)) => (),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_raise_error() {
+        let env: Environment<Type> = Environment::new();
+
+        let program = Raise("ValueError".to_string(), Box::new(CInt(1)));
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] raise expects a string as the exception message."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_assert() {
+        let env: Environment<Type> = Environment::new();
+
+        let assert1 = Assert(
+            Box::new(GT(Box::new(CInt(2)), Box::new(CInt(1)))),
+            Box::new(CString("2 > 1".to_string())),
+        );
+        let assert2 = Assert(Box::new(CInt(1)), Box::new(CString("1".to_string())));
+        let assert3 = Assert(Box::new(CTrue), Box::new(CInt(1)));
+
+        assert!(check_stmt(assert1, &env).is_ok());
+
+        match check_stmt(assert2, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] assert condition must be boolean."
+            ),
+        }
+        match check_stmt(assert3, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] assert expects a string as the failure message."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_try_except_returns() {
+        let env: Environment<Type> = Environment::new();
+
+        let func = FuncDef(Function {
+            name: "safe_div".to_string(),
+            kind: Some(TInteger),
+            params: Some(vec![
+                ("a".to_string(), TInteger),
+                ("b".to_string(), TInteger),
+            ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(TryExcept(
+                Box::new(Return(Box::new(Div(
+                    Box::new(Var("a".to_string())),
+                    Box::new(Var("b".to_string())),
+                )))),
+                vec![(
+                    "ZeroDivisionError".to_string(),
+                    None,
+                    Return(Box::new(CInt(0))),
+                )],
+                None,
+            ))),
+        });
+
+        match check_stmt(func, &env) {
+            Ok(ControlFlow::Continue(_)) => (),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_try_except_assignments() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > try:
+         * >     x = 1
+         * > except Exception:
+         * >     x = 2
+         * > finally:
+         * >     y = 3
+         * > print(x + y)
+         */
+        let program = Sequence(
+            Box::new(TryExcept(
+                Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+                vec![(
+                    "Exception".to_string(),
+                    None,
+                    Assignment("x".to_string(), Box::new(CInt(2)), None),
+                )],
+                Some(Box::new(Assignment(
+                    "y".to_string(),
+                    Box::new(CInt(3)),
+                    None,
+                ))),
+            )),
+            Box::new(Print(Box::new(Add(
+                Box::new(Var("x".to_string())),
+                Box::new(Var("y".to_string())),
+            )))),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("x".to_string()), Some(&TInteger));
+                assert_eq!(new_env.search_frame("y".to_string()), Some(&TInteger));
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+
+        /*
+         * > try:
+         * >     x = 1
+         * > except Exception:
+         * >     pass
+         * > print(x)
+         */
+        let program = Sequence(
+            Box::new(TryExcept(
+                Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+                vec![(
+                    "Exception".to_string(),
+                    None,
+                    Print(Box::new(CString("failed".to_string()))),
+                )],
+                None,
+            )),
+            Box::new(Print(Box::new(Var("x".to_string())))),
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Name Error on '__main__'] 'x' might not be assigned on every path."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_raise_ends_path() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > if True:
+         * >     x = 1
+         * > else:
+         * >     raise ValueError("no value")
+         * > print(x)
+         */
+        let program = Sequence(
+            Box::new(IfThenElse(
+                Box::new(CTrue),
+                Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+                Some(Box::new(Raise(
+                    "ValueError".to_string(),
+                    Box::new(CString("no value".to_string())),
+                ))),
+            )),
+            Box::new(Print(Box::new(Var("x".to_string())))),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("x".to_string()), Some(&TInteger))
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+
+        /*
+         * > try:
+         * >     raise ValueError("bad")
+         * > except ValueError:
+         * >     raise KeyError("worse")
+         */
+        let program = TryExcept(
+            Box::new(Raise(
+                "ValueError".to_string(),
+                Box::new(CString("bad".to_string())),
+            )),
+            vec![(
+                "ValueError".to_string(),
+                None,
+                Raise(
+                    "KeyError".to_string(),
+                    Box::new(CString("worse".to_string())),
+                ),
+            )],
+            None,
+        );
+
+        assert!(matches!(check_stmt(program, &env), Ok(ControlFlow::Raise)));
+    }
+
+    fn point_def() -> Statement {
+        RecordDef(
+            "Point".to_string(),
+            vec![("x".to_string(), TReal), ("y".to_string(), TReal)],
+        )
+    }
+
+    #[test]
+    fn check_record() {
+        let env: Environment<Type> = Environment::new();
+
+        let assignment1 = Assignment(
+            "p".to_string(),
+            Box::new(Record(
+                "Point".to_string(),
+                vec![("x".to_string(), CReal(1.0)), ("y".to_string(), CReal(2.0))],
+            )),
+            Some(TRecord("Point".to_string())),
+        );
+        let assignment2 = FieldAssignment(
+            "p".to_string(),
+            "y".to_string(),
+            Box::new(FieldAccess(Box::new(Var("p".to_string())), "x".to_string())),
+        );
+        let program = Sequence(
+            Box::new(point_def()),
+            Box::new(Sequence(Box::new(assignment1), Box::new(assignment2))),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("p".to_string()),
+                    Some(TRecord("Point".to_string())).as_ref()
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_record_field_errors() {
+        let env = match check_stmt(point_def(), &Environment::new()) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            _ => panic!("record definition failed"),
+        };
+
+        let missing = Record("Point".to_string(), vec![("x".to_string(), CReal(1.0))]);
+        let unknown = Record(
+            "Point".to_string(),
+            vec![
+                ("x".to_string(), CReal(1.0)),
+                ("y".to_string(), CReal(1.0)),
+                ("z".to_string(), CReal(1.0)),
+            ],
+        );
+        let mismatched = Record(
+            "Point".to_string(),
+            vec![("x".to_string(), CReal(1.0)), ("y".to_string(), CTrue)],
+        );
+        let undefined = Record("Circle".to_string(), vec![]);
+        let access = FieldAccess(Box::new(CInt(1)), "x".to_string());
+
+        assert_eq!(
+            check_exp(missing, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] record 'Point' is missing field 'y'."
+            ))
+        );
+        assert_eq!(
+            check_exp(unknown, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] record 'Point' has no field 'z'."
+            ))
+        );
+        assert_eq!(
+            check_exp(mismatched, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'Point.y' has mismatched types: expected 'TReal', found 'TBool'."
+            ))
+        );
+        assert_eq!(
+            check_exp(undefined, &env),
+            Err(String::from(
+                "[Name Error on '__main__()'] record 'Circle' is not defined."
+            ))
+        );
+        assert_eq!(
+            check_exp(access, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] field access '.x' expects a record, found 'TInteger'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_record_nominal_typing() {
+        let env: Environment<Type> = Environment::new();
+
+        let vector_def = RecordDef(
+            "Vector".to_string(),
+            vec![("x".to_string(), TReal), ("y".to_string(), TReal)],
+        );
+        let assignment = Assignment(
+            "v".to_string(),
+            Box::new(Record(
+                "Point".to_string(),
+                vec![("x".to_string(), CReal(1.0)), ("y".to_string(), CReal(2.0))],
+            )),
+            Some(TRecord("Vector".to_string())),
+        );
+        let program = Sequence(
+            Box::new(point_def()),
+            Box::new(Sequence(Box::new(vector_def), Box::new(assignment))),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] 'v' has mismatched types: expected 'TRecord(\"Vector\")', found 'TRecord(\"Point\")'."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_record_def_errors() {
+        let env: Environment<Type> = Environment::new();
+
+        let duplicate = RecordDef(
+            "Pair".to_string(),
+            vec![("a".to_string(), TInteger), ("a".to_string(), TInteger)],
+        );
+        let function_field = RecordDef(
+            "Handler".to_string(),
+            vec![("callback".to_string(), TFunction(Box::new(None), vec![]))],
+        );
+        let recursive = RecordDef(
+            "Node".to_string(),
+            vec![
+                ("value".to_string(), TInteger),
+                ("next".to_string(), TRecord("Node".to_string())),
+            ],
+        );
+
+        match check_stmt(duplicate, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] record 'Pair' has duplicate field 'a'."
+            ),
+        }
+        match check_stmt(function_field, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] record field 'Handler.callback' cannot have a function type."
+            ),
+        }
+        assert!(check_stmt(recursive, &env).is_ok());
+    }
+
+    fn shape_env() -> Environment<Type> {
+        let shape = AdtDef(
+            "Shape".to_string(),
+            vec![
+                ("Circle".to_string(), vec![TReal]),
+                ("Rect".to_string(), vec![TReal, TReal]),
+                ("Empty".to_string(), vec![]),
+            ],
+        );
+
+        match check_stmt(shape, &Environment::new()) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            _ => panic!("type definition failed"),
+        }
+    }
+
+    fn circle(pattern: Pattern) -> Pattern {
+        Pattern::Constructor("Circle".to_string(), vec![pattern])
+    }
+
+    fn rect(left: Pattern, right: Pattern) -> Pattern {
+        Pattern::Constructor("Rect".to_string(), vec![left, right])
+    }
+
+    fn empty() -> Pattern {
+        Pattern::Constructor("Empty".to_string(), vec![])
+    }
+
+    #[test]
+    fn check_match_exhaustive() {
+        let env = shape_env();
+
+        let exp = MatchExp(
+            Box::new(Constructor("Circle".to_string(), vec![CReal(1.0)])),
+            vec![
+                (
+                    circle(Pattern::Binding("r".to_string())),
+                    Var("r".to_string()),
+                ),
+                (
+                    rect(Pattern::Literal(CReal(0.0)), Pattern::Wildcard),
+                    CReal(0.0),
+                ),
+                (
+                    rect(Pattern::Wildcard, Pattern::Binding("h".to_string())),
+                    Var("h".to_string()),
+                ),
+                (empty(), CReal(0.0)),
+            ],
+        );
+
+        assert_eq!(check_exp(exp, &env), Ok(TReal));
+    }
+
+    #[test]
+    fn check_match_not_exhaustive() {
+        let env = shape_env();
+
+        let exp = MatchExp(
+            Box::new(Constructor("Empty".to_string(), vec![])),
+            vec![
+                (circle(Pattern::Wildcard), CInt(1)),
+                (
+                    rect(Pattern::Literal(CReal(0.0)), Pattern::Wildcard),
+                    CInt(2),
+                ),
+                (empty(), CInt(3)),
+            ],
+        );
+
+        assert_eq!(
+            check_exp(exp, &env),
+            Err(String::from(
+                "[Match Error on '__main__()'] match over 'TAdt(\"Shape\")' is not exhaustive."
+            ))
+        );
+
+        let literals = MatchExp(
+            Box::new(CInt(1)),
+            vec![
+                (Pattern::Literal(CInt(1)), CTrue),
+                (Pattern::Literal(CInt(2)), CFalse),
+            ],
+        );
+
+        assert_eq!(
+            check_exp(literals, &env),
+            Err(String::from(
+                "[Match Error on '__main__()'] match over 'TInteger' is not exhaustive."
+            ))
+        );
+
+        let booleans = MatchExp(
+            Box::new(CTrue),
+            vec![
+                (Pattern::Literal(CTrue), CInt(1)),
+                (Pattern::Literal(CFalse), CInt(0)),
+            ],
+        );
+
+        assert_eq!(check_exp(booleans, &env), Ok(TInteger));
+    }
+
+    #[test]
+    fn check_match_unreachable_arm() {
+        let env = shape_env();
+
+        let program = Match(
+            Box::new(Constructor("Circle".to_string(), vec![CReal(1.0)])),
+            vec![
+                (
+                    Pattern::Binding("s".to_string()),
+                    Print(Box::new(Var("s".to_string()))),
+                ),
+                (empty(), Print(Box::new(CString("empty".to_string())))),
+            ],
+        );
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Match Error on '__main__()'] match arm 2 is unreachable."
+            ),
+        }
+
+        let program = Match(
+            Box::new(Constructor("Circle".to_string(), vec![CReal(1.0)])),
+            vec![
+                (circle(Pattern::Wildcard), Print(Box::new(CInt(1)))),
+                (
+                    rect(Pattern::Wildcard, Pattern::Wildcard),
+                    Print(Box::new(CInt(2))),
+                ),
+                (
+                    circle(Pattern::Literal(CReal(1.0))),
+                    Print(Box::new(CInt(3))),
+                ),
+                (empty(), Print(Box::new(CInt(4)))),
+            ],
+        );
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Match Error on '__main__()'] match arm 3 is unreachable."
+            ),
+        }
+    }
+
+    #[test]
+    fn check_match_wrapped_values() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > match Just(2):
+         * >   case Just(n):
+         * >     total = n
+         * >   case Nothing:
+         * >     total = 0
+         * > print(total)
+         */
+        let program = Sequence(
+            Box::new(Match(
+                Box::new(CJust(Box::new(CInt(2)))),
+                vec![
+                    (
+                        Pattern::Constructor(
+                            "Just".to_string(),
+                            vec![Pattern::Binding("n".to_string())],
+                        ),
+                        Assignment("total".to_string(), Box::new(Var("n".to_string())), None),
+                    ),
+                    (
+                        Pattern::Constructor("Nothing".to_string(), vec![]),
+                        Assignment("total".to_string(), Box::new(CInt(0)), None),
+                    ),
+                ],
+            )),
+            Box::new(Print(Box::new(Var("total".to_string())))),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("total".to_string()), Some(&TInteger))
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+
+        /*
+         * > match Ok(1):
+         * >   case Ok(n):
+         * >     print(n)
+         */
+        let program = Match(
+            Box::new(COk(Box::new(CInt(1)))),
+            vec![(
+                Pattern::Constructor("Ok".to_string(), vec![Pattern::Binding("n".to_string())]),
+                Print(Box::new(Var("n".to_string()))),
+            )],
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Match Error on '__main__()'] match over 'TResult(TInteger, TUnknown)' is not exhaustive."
+            ))
+        );
+
+        /*
+         * > match Err("bad"):
+         * >   case Ok(n):
+         * >     message = "ok"
+         * >   case Err(e):
+         * >     message = e
+         * > print(message)
+         */
+        let program = Sequence(
+            Box::new(Match(
+                Box::new(CErr(Box::new(CString("bad".to_string())))),
+                vec![
+                    (
+                        Pattern::Constructor("Ok".to_string(), vec![Pattern::Wildcard]),
+                        Assignment(
+                            "message".to_string(),
+                            Box::new(CString("ok".to_string())),
+                            None,
+                        ),
+                    ),
+                    (
+                        Pattern::Constructor(
+                            "Err".to_string(),
+                            vec![Pattern::Binding("e".to_string())],
+                        ),
+                        Assignment("message".to_string(), Box::new(Var("e".to_string())), None),
+                    ),
+                ],
+            )),
+            Box::new(Print(Box::new(Var("message".to_string())))),
+        );
+
+        assert!(check_stmt(program, &env).is_ok());
+    }
+
+    #[test]
+    fn check_match_type_errors() {
+        let env = shape_env();
+
+        let wrong_constructor = MatchExp(
+            Box::new(CInt(1)),
+            vec![(empty(), CInt(1)), (Pattern::Wildcard, CInt(2))],
+        );
+        let wrong_arity = MatchExp(
+            Box::new(Constructor("Empty".to_string(), vec![])),
+            vec![
+                (Pattern::Constructor("Circle".to_string(), vec![]), CInt(1)),
+                (Pattern::Wildcard, CInt(2)),
+            ],
+        );
+        let mismatched_arms = MatchExp(
+            Box::new(CInt(1)),
+            vec![
+                (Pattern::Literal(CInt(1)), CInt(1)),
+                (Pattern::Wildcard, CTrue),
+            ],
+        );
+        let wrong_arguments = Constructor(
+            "Rect".to_string(),
+            vec![CReal(1.0), CString("2".to_string())],
+        );
+
+        assert_eq!(
+            check_exp(wrong_constructor, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] pattern has mismatched types: expected 'TInteger', found 'TAdt(\"Shape\")'."
+            ))
+        );
+        assert_eq!(
+            check_exp(wrong_arity, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] constructor 'Circle' expected 1 arguments, found 0."
+            ))
+        );
+        assert_eq!(
+            check_exp(mismatched_arms, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] match arms have mismatched types: expected 'TInteger', found 'TBool'."
+            ))
+        );
+        assert_eq!(
+            check_exp(wrong_arguments, &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] constructor 'Rect' has mismatched arguments: expected 'TReal', found 'TString'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_match_statement_returns() {
+        let env = shape_env();
+
+        let func = FuncDef(Function {
+            name: "sides".to_string(),
+            kind: Some(TInteger),
+            params: Some(vec![("s".to_string(), TAdt("Shape".to_string()))]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Match(
+                Box::new(Var("s".to_string())),
+                vec![
+                    (
+                        rect(Pattern::Wildcard, Pattern::Wildcard),
+                        Return(Box::new(CInt(4))),
+                    ),
+                    (Pattern::Wildcard, Return(Box::new(CInt(0)))),
+                ],
+            ))),
+        });
+
+        match check_stmt(func, &env) {
+            Ok(ControlFlow::Continue(_)) => (),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_numeric_widening() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def half(x: real) -> real:
+         * >     return 1
+         * >
+         * > r: real = 1
+         * > h: real = half(3)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "half".to_string(),
+                kind: Some(TReal),
+                params: Some(vec![("x".to_string(), TReal)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(CInt(1))))),
+            })),
+            Box::new(Sequence(
+                Box::new(Assignment("r".to_string(), Box::new(CInt(1)), Some(TReal))),
+                Box::new(Assignment(
+                    "h".to_string(),
+                    Box::new(FuncCall("half".to_string(), vec![CInt(3)])),
+                    Some(TReal),
+                )),
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("r".to_string()), Some(&TReal));
+                assert_eq!(
+                    check_stmt(
+                        Assignment("n".to_string(), Box::new(CReal(1.5)), Some(TInteger)),
+                        &new_env
+                    )
+                    .err(),
+                    Some(String::from(
+                        "[Type Error on '__main__()'] 'n' has mismatched types: expected 'TInteger', found 'TReal'."
+                    ))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_any_is_consistent() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def load(s: str) -> Any:
+         * >     return s
+         * >
+         * > x = load("1")
+         * > n: int = x
+         * > y = x + 1
+         * > ok: bool = x and true
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "load".to_string(),
+                kind: Some(TAny),
+                params: Some(vec![("s".to_string(), TString)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("s".to_string()))))),
+            })),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "x".to_string(),
+                    Box::new(FuncCall("load".to_string(), vec![CString("1".to_string())])),
+                    None,
+                )),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "n".to_string(),
+                        Box::new(Var("x".to_string())),
+                        Some(TInteger),
+                    )),
+                    Box::new(Sequence(
+                        Box::new(Assignment(
+                            "y".to_string(),
+                            Box::new(Add(Box::new(Var("x".to_string())), Box::new(CInt(1)))),
+                            None,
+                        )),
+                        Box::new(Assignment(
+                            "ok".to_string(),
+                            Box::new(And(Box::new(Var("x".to_string())), Box::new(CTrue))),
+                            Some(TBool),
+                        )),
+                    )),
+                )),
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("x".to_string()), Some(&TAny));
+                assert_eq!(new_env.search_frame("n".to_string()), Some(&TInteger));
+                assert_eq!(new_env.search_frame("y".to_string()), Some(&TAny));
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_list_literal_against_annotation() {
+        let env: Environment<Type> = Environment::new();
+
+        /* > xs: list[Any] = [1, "x", [2.5]] */
+        let mixed = Assignment(
+            "xs".to_string(),
+            Box::new(CList(vec![
+                CInt(1),
+                CString("x".to_string()),
+                CList(vec![CReal(2.5)]),
+            ])),
+            Some(TList(Box::new(TAny))),
+        );
+
+        match check_stmt(mixed, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("xs".to_string()),
+                Some(&TList(Box::new(TAny)))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+
+        /*
+         * > def rows() -> list[list[Any]]:
+         * >     return [[1, "one"], [True]]
+         */
+        let rows = FuncDef(Function {
+            name: "rows".to_string(),
+            kind: Some(TList(Box::new(TList(Box::new(TAny))))),
+            params: Some(vec![]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(CList(vec![
+                CList(vec![CInt(1), CString("one".to_string())]),
+                CList(vec![CTrue]),
+            ]))))),
+        });
+
+        assert!(check_stmt(rows, &env).is_ok());
+
+        /* > ns: list[int] = [1, "x"] */
+        let wrong = Assignment(
+            "ns".to_string(),
+            Box::new(CList(vec![CInt(1), CString("x".to_string())])),
+            Some(TList(Box::new(TInteger))),
+        );
+
+        assert_eq!(
+            check_stmt(wrong, &env).err(),
+            Some(String::from(
+                "[Type Error on '__main__()'] list elements have mismatched types: expected 'TInteger', found 'TString'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_definite_assignment() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > if True:
+         * >     x = 1
+         * > else:
+         * >     x = 2.5
+         * > y = x
+         */
+        let program = Sequence(
+            Box::new(IfThenElse(
+                Box::new(CTrue),
+                Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+                Some(Box::new(Assignment(
+                    "x".to_string(),
+                    Box::new(CReal(2.5)),
+                    None,
+                ))),
+            )),
+            Box::new(Assignment(
+                "y".to_string(),
+                Box::new(Var("x".to_string())),
+                None,
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("x".to_string()), Some(&TReal));
+                assert_eq!(new_env.search_frame("y".to_string()), Some(&TReal));
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_unassigned_variable_errors() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > if True:
+         * >     x = 1
+         * > y = x
+         */
+        let program = Sequence(
+            Box::new(IfThenElse(
+                Box::new(CTrue),
+                Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+                None,
+            )),
+            Box::new(Assignment(
+                "y".to_string(),
+                Box::new(Var("x".to_string())),
+                None,
+            )),
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Name Error on '__main__'] 'x' might not be assigned on every path."
+            ))
+        );
+
+        /*
+         * > while False:
+         * >     z = 1
+         * > z += 1
+         */
+        let program = Sequence(
+            Box::new(While(
+                Box::new(CFalse),
+                Box::new(Assignment("z".to_string(), Box::new(CInt(1)), None)),
+            )),
+            Box::new(AddAssignment("z".to_string(), Box::new(CInt(1)))),
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Name Error on '__main__'] 'z' might not be assigned on every path."
+            ))
+        );
+
+        /*
+         * > if True:
+         * >     x = 1
+         * > else:
+         * >     x = "one"
+         */
+        let program = IfThenElse(
+            Box::new(CTrue),
+            Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+            Some(Box::new(Assignment(
+                "x".to_string(),
+                Box::new(CString("one".to_string())),
+                None,
+            ))),
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Type Error on '__main__()'] 'x' has mismatched types across branches: expected 'TInteger', found 'TString'."
+            ))
+        );
+    }
+
+    fn greet_env() -> Environment<Type> {
+        /*
+         * > def greet(name: str, greeting: str = "Hello", *rest: str) -> str:
+         * >     return greeting
+         * >
+         * > def scale(x: real, factor: real = 2.0) -> real:
+         * >     return x * factor
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "greet".to_string(),
+                kind: Some(TString),
+                params: Some(vec![
+                    ("name".to_string(), TString),
+                    ("greeting".to_string(), TString),
+                ]),
+                defaults: vec![("greeting".to_string(), CString("Hello".to_string()))],
+                variadic: Some(("rest".to_string(), TString)),
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("greeting".to_string()))))),
+            })),
+            Box::new(FuncDef(Function {
+                name: "scale".to_string(),
+                kind: Some(TReal),
+                params: Some(vec![
+                    ("x".to_string(), TReal),
+                    ("factor".to_string(), TReal),
+                ]),
+                defaults: vec![("factor".to_string(), CReal(2.0))],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Mul(
+                    Box::new(Var("x".to_string())),
+                    Box::new(Var("factor".to_string())),
+                ))))),
+            })),
+        );
+
+        match check_stmt(program, &Environment::new()) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("unexpected return"),
+            Err(s) => panic!("{}", s),
+        }
+    }
+
+    fn keyword(name: &str, exp: Expression) -> Expression {
+        KeywordArg(name.to_string(), Box::new(exp))
+    }
+
+    #[test]
+    fn check_default_keyword_and_variadic_arguments() {
+        let env = greet_env();
+        let ann = || CString("Ann".to_string());
+        let hi = || CString("Hi".to_string());
+
+        /*
+         * > greet("Ann")
+         * > greet("Ann", greeting="Hi")
+         * > greet(greeting="Hi", name="Ann")
+         * > greet("Ann", "Hi", "and", "Bob")
+         * > scale(1, factor=0.5)
+         */
+        let calls = vec![
+            FuncCall("greet".to_string(), vec![ann()]),
+            FuncCall("greet".to_string(), vec![ann(), keyword("greeting", hi())]),
+            FuncCall(
+                "greet".to_string(),
+                vec![keyword("greeting", hi()), keyword("name", ann())],
+            ),
+            FuncCall(
+                "greet".to_string(),
+                vec![
+                    ann(),
+                    hi(),
+                    CString("and".to_string()),
+                    CString("Bob".to_string()),
+                ],
+            ),
+        ];
+
+        for call in calls {
+            assert_eq!(check_exp(call, &env), Ok(TString));
+        }
+
+        assert_eq!(
+            check_exp(
+                FuncCall(
+                    "scale".to_string(),
+                    vec![CInt(1), keyword("factor", CReal(0.5))]
+                ),
+                &env
+            ),
+            Ok(TReal)
+        );
+    }
+
+    #[test]
+    fn check_argument_errors() {
+        let env = greet_env();
+        let ann = || CString("Ann".to_string());
+
+        let errors = vec![
+            (
+                FuncCall("greet".to_string(), vec![]),
+                "[Type Error on '__main__()'] 'greet()' missing required argument 'name'.",
+            ),
+            (
+                FuncCall("greet".to_string(), vec![ann(), keyword("nick", ann())]),
+                "[Type Error on '__main__()'] 'greet()' got an unexpected keyword argument 'nick'.",
+            ),
+            (
+                FuncCall("greet".to_string(), vec![ann(), keyword("name", ann())]),
+                "[Type Error on '__main__()'] 'greet()' got multiple values for argument 'name'.",
+            ),
+            (
+                FuncCall("greet".to_string(), vec![ann(), ann(), CInt(3)]),
+                "[Type Error on '__main__()'] 'greet()' has mismatched arguments: expected 'TString', found 'TInteger'.",
+            ),
+            (
+                FuncCall("greet".to_string(), vec![keyword("greeting", ann()), ann()]),
+                "[Syntax Error] positional argument follows keyword argument in call to 'greet()'.",
+            ),
+            (
+                FuncCall("scale".to_string(), vec![CInt(1), CInt(2), CInt(3)]),
+                "[Type Error on '__main__()'] 'scale()' expected at most 2 arguments, found 3.",
+            ),
+        ];
+
+        for (call, error) in errors {
+            assert_eq!(check_exp(call, &env), Err(error.to_string()));
+        }
+    }
+
+    #[test]
+    fn check_default_definition_errors() {
+        let env: Environment<Type> = Environment::new();
+
+        let offset = |defaults: Vec<(Name, Expression)>| {
+            FuncDef(Function {
+                name: "offset".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![
+                    ("x".to_string(), TInteger),
+                    ("by".to_string(), TInteger),
+                ]),
+                defaults,
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Add(
+                    Box::new(Var("x".to_string())),
+                    Box::new(Var("by".to_string())),
+                ))))),
+            })
+        };
+
+        /* > def offset(x: int, by: int = "one") -> int */
+        assert_eq!(
+            check_stmt(
+                offset(vec![("by".to_string(), CString("one".to_string()))]),
+                &env
+            )
+            .err(),
+            Some(String::from(
+                "[Type Error] 'offset()' has a mismatched default for 'by': expected 'TInteger', found 'TString'."
+            ))
+        );
+
+        /* > def offset(x: int = 0, by: int) -> int */
+        assert_eq!(
+            check_stmt(offset(vec![("x".to_string(), CInt(0))]), &env).err(),
+            Some(String::from(
+                "[Syntax Error] 'offset()' has parameter 'by' without a default after one with a default."
+            ))
+        );
+    }
+
+    fn parity_functions() -> Statement {
+        /*
+         * > def is_even(n: int) -> bool:
+         * >     if n == 0:
+         * >         return True
+         * >     else:
+         * >         return is_odd(n - 1)
+         * >
+         * > def is_odd(n: int) -> bool:
+         * >     if n == 0:
+         * >         return False
+         * >     else:
+         * >         return is_even(n - 1)
+         */
+        let parity = |name: &str, base: Expression, other: &str| {
+            FuncDef(Function {
+                name: name.to_string(),
+                kind: Some(TBool),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(IfThenElse(
+                    Box::new(EQ(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
+                    Box::new(Return(Box::new(base))),
+                    Some(Box::new(Return(Box::new(FuncCall(
+                        other.to_string(),
+                        vec![Sub(Box::new(Var("n".to_string())), Box::new(CInt(1)))],
+                    ))))),
+                ))),
+            })
+        };
+
+        Sequence(
+            Box::new(parity("is_even", CTrue, "is_odd")),
+            Box::new(parity("is_odd", CFalse, "is_even")),
+        )
+    }
+
+    #[test]
+    fn check_mutual_recursion() {
+        let env: Environment<Type> = Environment::new();
+
+        /* > b: bool = is_even(4) */
+        let program = Sequence(
+            Box::new(parity_functions()),
+            Box::new(Assignment(
+                "b".to_string(),
+                Box::new(FuncCall("is_even".to_string(), vec![CInt(4)])),
+                Some(TBool),
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("is_odd".to_string()),
+                Some(&TFunction(Box::new(Some(TBool)), vec![TInteger]))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_forward_reference_errors() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def f() -> int:
+         * >     return g("1")
+         * >
+         * > def g(n: int) -> int:
+         * >     return n
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "f".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(FuncCall(
+                    "g".to_string(),
+                    vec![CString("1".to_string())],
+                ))))),
+            })),
+            Box::new(FuncDef(Function {
+                name: "g".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("n".to_string()))))),
+            })),
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Type Error on 'f()'] 'g()' has mismatched arguments: expected 'TInteger', found 'TString'."
+            ))
+        );
+
+        /*
+         * > r = g(1)
+         * >
+         * > def g(n: int) -> int:
+         * >     return n
+         */
+        let program = Sequence(
+            Box::new(Assignment(
+                "r".to_string(),
+                Box::new(FuncCall("g".to_string(), vec![CInt(1)])),
+                None,
+            )),
+            Box::new(FuncDef(Function {
+                name: "g".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("n".to_string()))))),
+            })),
+        );
+
+        assert_eq!(
+            check_stmt(program, &env).err(),
+            Some(String::from(
+                "[Name Error on '__main__()'] 'g()' is not defined."
+            ))
+        );
+    }
+
+    fn callback_env() -> Environment<Type> {
+        /*
+         * > Callback = (int) -> int
+         * >
+         * > def apply(f: Callback, x: int) -> int:
+         * >     return f(x)
+         * >
+         * > def inc(n: int) -> int:
+         * >     return n + 1
+         */
+        let program = Sequence(
+            Box::new(TypeAlias(
+                "Callback".to_string(),
+                TFunction(Box::new(Some(TInteger)), vec![TInteger]),
+            )),
+            Box::new(Sequence(
+                Box::new(FuncDef(Function {
+                    name: "apply".to_string(),
+                    kind: Some(TInteger),
+                    params: Some(vec![
+                        ("f".to_string(), TAlias("Callback".to_string())),
+                        ("x".to_string(), TInteger),
+                    ]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(FuncCall(
+                        "f".to_string(),
+                        vec![Var("x".to_string())],
+                    ))))),
+                })),
+                Box::new(FuncDef(Function {
+                    name: "inc".to_string(),
+                    kind: Some(TInteger),
+                    params: Some(vec![("n".to_string(), TInteger)]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(Add(
+                        Box::new(Var("n".to_string())),
+                        Box::new(CInt(1)),
+                    ))))),
+                })),
+            )),
+        );
+
+        match check_stmt(program, &Environment::new()) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("unexpected return"),
+            Err(s) => panic!("{}", s),
+        }
+    }
+
+    fn call(name: &str, args: Vec<Expression>) -> Expression {
+        FuncCall(name.to_string(), args)
+    }
 
     #[test]
-    fn check_tlist_comparison() {
-        let t_list1 = TList(Box::new(TInteger));
-        let t_list2 = TList(Box::new(TInteger));
+    fn check_builtin_calls() {
+        let env = checker_env();
+        let text = |s: &str| CString(s.to_string());
+
+        let calls = vec![
+            (call("abs", vec![CInt(-3)]), TInteger),
+            (call("abs", vec![CReal(-2.5)]), TReal),
+            (call("sqrt", vec![CInt(4)]), TReal),
+            (call("floor", vec![CReal(2.7)]), TInteger),
+            (call("min", vec![CInt(3), CInt(1), CInt(2)]), TInteger),
+            (call("max", vec![CInt(1), CReal(2.5)]), TReal),
+            (call("int", vec![text("42")]), TInteger),
+            (call("real", vec![CInt(1)]), TReal),
+            (call("str", vec![CReal(1.5)]), TString),
+            (call("len", vec![text("abc")]), TInteger),
+            (call("upper", vec![text("abc")]), TString),
+            (
+                call("replace", vec![text("a-b"), text("-"), text("+")]),
+                TString,
+            ),
+            (
+                call("split", vec![text("a b"), text(" ")]),
+                TList(Box::new(TString)),
+            ),
+            (
+                call(
+                    "join",
+                    vec![text(", "), call("split", vec![text("a b"), text(" ")])],
+                ),
+                TString,
+            ),
+        ];
 
-        assert_eq!(t_list1 == t_list2, true);
+        for (exp, kind) in calls {
+            assert_eq!(check_exp(exp, &env), Ok(kind));
+        }
     }
 
     #[test]
-    fn check_tlist_comparison_different_types() {
-        let t_list1 = TList(Box::new(TInteger));
-        let t_list2 = TList(Box::new(TBool));
+    fn check_builtin_errors() {
+        let env = checker_env();
+
+        assert_eq!(
+            check_exp(call("sqrt", vec![CString("four".to_string())]), &env),
+            Err(String::from("[Type Error on '__main__()'] 'sqrt()' has mismatched arguments: expected 'TReal', found 'TString'."))
+        );
+        assert_eq!(
+            check_exp(call("min", vec![CInt(1), CString("a".to_string())]), &env),
+            Err(String::from("[Type Error on '__main__()'] 'min()' has mismatched arguments: expected 'TVar(\"#Number\")', found 'TString'."))
+        );
+        assert_eq!(
+            check_exp(call("abs", vec![CString("x".to_string())]), &env),
+            Err(String::from("[Type Error on '__main__()'] 'abs()' has mismatched arguments: expected 'TVar(\"#Number\")', found 'TString'."))
+        );
+        assert_eq!(
+            check_exp(call("upper", vec![]), &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'upper()' expected 1 arguments, found 0."
+            ))
+        );
+
+        /*
+         * > def abs(x: str) -> str:
+         * >     return x
+         * >
+         * > y = abs("a")
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "abs".to_string(),
+                kind: Some(TString),
+                params: Some(vec![("x".to_string(), TString)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("x".to_string()))))),
+            })),
+            Box::new(Assignment(
+                "y".to_string(),
+                Box::new(call("abs", vec![CString("a".to_string())])),
+                None,
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("y".to_string()), Some(&TString))
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    fn geometry_module() -> Statement {
+        /*
+         * > _scale = 2
+         * > origin = 0
+         * >
+         * > def _double(x: int) -> int:
+         * >     return x * _scale
+         * >
+         * > def area(w: int, h: int = 1) -> int:
+         * >     return _double(w * h)
+         */
+        Sequence(
+            Box::new(Assignment("_scale".to_string(), Box::new(CInt(2)), None)),
+            Box::new(Sequence(
+                Box::new(Assignment("origin".to_string(), Box::new(CInt(0)), None)),
+                Box::new(Sequence(
+                    Box::new(FuncDef(Function {
+                        name: "_double".to_string(),
+                        kind: Some(TInteger),
+                        params: Some(vec![("x".to_string(), TInteger)]),
+                        defaults: vec![],
+                        variadic: None,
+                        module: None,
+                        body: Some(Box::new(Return(Box::new(Mul(
+                            Box::new(Var("x".to_string())),
+                            Box::new(Var("_scale".to_string())),
+                        ))))),
+                    })),
+                    Box::new(FuncDef(Function {
+                        name: "area".to_string(),
+                        kind: Some(TInteger),
+                        params: Some(vec![
+                            ("w".to_string(), TInteger),
+                            ("h".to_string(), TInteger),
+                        ]),
+                        defaults: vec![("h".to_string(), CInt(1))],
+                        variadic: None,
+                        module: None,
+                        body: Some(Box::new(Return(Box::new(FuncCall(
+                            "_double".to_string(),
+                            vec![Mul(
+                                Box::new(Var("w".to_string())),
+                                Box::new(Var("h".to_string())),
+                            )],
+                        ))))),
+                    })),
+                )),
+            )),
+        )
+    }
+
+    fn modules_env(modules: Vec<(&str, Statement)>) -> Environment<Type> {
+        let mut loader = MemoryLoader::new();
 
-        assert_eq!(t_list1 == t_list2, false);
+        for (name, program) in modules {
+            loader.insert(name, program);
+        }
+
+        let mut env = Environment::new();
+        env.modules = Modules::new(Rc::new(loader));
+        env
     }
 
     #[test]
-    fn check_ttuple_comparison() {
-        let t_tuple1 = TTuple(vec![TInteger, TBool]);
-        let t_tuple2 = TTuple(vec![TInteger, TBool]);
+    fn check_imports() {
+        let env = modules_env(vec![("geometry", geometry_module())]);
+
+        /*
+         * > import geometry
+         * > from geometry import area
+         * >
+         * > a = geometry.area(3, 4)
+         * > b = area(3, h=2)
+         * > c = geometry.origin
+         */
+        let program = Sequence(
+            Box::new(Import("geometry".to_string())),
+            Box::new(Sequence(
+                Box::new(FromImport("geometry".to_string(), vec!["area".to_string()])),
+                Box::new(Sequence(
+                    Box::new(Assignment(
+                        "a".to_string(),
+                        Box::new(MethodCall(
+                            Box::new(Var("geometry".to_string())),
+                            "area".to_string(),
+                            vec![CInt(3), CInt(4)],
+                        )),
+                        None,
+                    )),
+                    Box::new(Sequence(
+                        Box::new(Assignment(
+                            "b".to_string(),
+                            Box::new(FuncCall(
+                                "area".to_string(),
+                                vec![CInt(3), KeywordArg("h".to_string(), Box::new(CInt(2)))],
+                            )),
+                            None,
+                        )),
+                        Box::new(Assignment(
+                            "c".to_string(),
+                            Box::new(FieldAccess(
+                                Box::new(Var("geometry".to_string())),
+                                "origin".to_string(),
+                            )),
+                            None,
+                        )),
+                    )),
+                )),
+            )),
+        );
 
-        assert_eq!(t_tuple1 == t_tuple2, true);
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("geometry".to_string()),
+                    Some(&TModule("geometry".to_string()))
+                );
+                for name in ["a", "b", "c"] {
+                    assert_eq!(new_env.search_frame(name.to_string()), Some(&TInteger));
+                }
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
     }
 
     #[test]
-    fn check_ttuple_comparison_different_types() {
-        let t_tuple1 = TTuple(vec![TInteger, TBool]);
-        let t_tuple2 = TTuple(vec![TBool, TInteger]);
+    fn check_import_errors() {
+        let env = modules_env(vec![
+            ("geometry", geometry_module()),
+            ("a", Import("b".to_string())),
+            ("b", Import("a".to_string())),
+            (
+                "broken",
+                Assignment("x".to_string(), Box::new(Var("y".to_string())), None),
+            ),
+        ]);
 
-        assert_eq!(t_tuple1 == t_tuple2, false);
+        let errors = vec![
+            (
+                Import("missing".to_string()),
+                "[Import Error on '__main__()'] no module named 'missing'.",
+            ),
+            (
+                FromImport("geometry".to_string(), vec!["_double".to_string()]),
+                "[Import Error on '__main__()'] cannot import name '_double' from module 'geometry'.",
+            ),
+            (
+                Sequence(
+                    Box::new(Import("geometry".to_string())),
+                    Box::new(Assignment(
+                        "v".to_string(),
+                        Box::new(FieldAccess(
+                            Box::new(Var("geometry".to_string())),
+                            "volume".to_string(),
+                        )),
+                        None,
+                    )),
+                ),
+                "[Type Error on '__main__()'] module 'geometry' has no attribute 'volume'.",
+            ),
+            (
+                Import("a".to_string()),
+                "[Import Error on 'b()'] circular import: a -> b -> a.",
+            ),
+            (
+                Import("broken".to_string()),
+                "[Name Error on 'broken'] 'y' is not defined.",
+            ),
+        ];
+
+        for (program, error) in errors {
+            assert_eq!(check_stmt(program, &env).err(), Some(error.to_string()));
+        }
     }
 
     #[test]
-    fn check_constant() {
-        let env = Environment::new();
+    fn check_type_alias() {
+        let env = callback_env();
+
+        /*
+         * > Score = real
+         * > r: int = apply(inc, 1)
+         * > s: Score = 1
+         */
+        let program = Sequence(
+            Box::new(TypeAlias("Score".to_string(), TReal)),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "r".to_string(),
+                    Box::new(FuncCall(
+                        "apply".to_string(),
+                        vec![Var("inc".to_string()), CInt(1)],
+                    )),
+                    Some(TInteger),
+                )),
+                Box::new(Assignment(
+                    "s".to_string(),
+                    Box::new(CInt(1)),
+                    Some(TAlias("Score".to_string())),
+                )),
+            )),
+        );
 
-        let c10 = CInt(10);
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(
+                    new_env.search_frame("s".to_string()),
+                    Some(&TAlias("Score".to_string()))
+                );
+                assert_eq!(
+                    check_exp(
+                        Add(Box::new(Var("s".to_string())), Box::new(CReal(0.5))),
+                        &new_env
+                    ),
+                    Ok(TReal)
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
 
-        assert_eq!(check_exp(c10, &env), Ok(TInteger));
+    #[test]
+    fn check_type_alias_diagnostics() {
+        let env = callback_env();
+
+        assert_eq!(
+            check_exp(
+                FuncCall("apply".to_string(), vec![CInt(1), CInt(2)]),
+                &env
+            ),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'apply()' has mismatched arguments: expected 'TAlias(\"Callback\")', found 'TInteger'."
+            ))
+        );
+
+        assert_eq!(
+            check_stmt(
+                Assignment(
+                    "g".to_string(),
+                    Box::new(CString("inc".to_string())),
+                    Some(TAlias("Callback".to_string())),
+                ),
+                &env
+            )
+            .err(),
+            Some(String::from(
+                "[Type Error on '__main__()'] 'g' has mismatched types: expected 'TAlias(\"Callback\")', found 'TString'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_type_alias_errors() {
+        let env = callback_env();
+
+        /* > Tree = list[Maybe[Tree]] */
+        assert_eq!(
+            check_stmt(
+                TypeAlias(
+                    "Tree".to_string(),
+                    TList(Box::new(TMaybe(Box::new(TAlias("Tree".to_string()))))),
+                ),
+                &env
+            )
+            .err(),
+            Some(String::from(
+                "[Type Error on '__main__()'] type alias 'Tree' is recursive."
+            ))
+        );
+
+        assert_eq!(
+            check_stmt(
+                TypeAlias(
+                    "Handler".to_string(),
+                    TFunction(Box::new(None), vec![TAlias("Event".to_string())]),
+                ),
+                &env
+            )
+            .err(),
+            Some(String::from(
+                "[Name Error on '__main__()'] type 'Event' is not defined."
+            ))
+        );
+
+        assert_eq!(
+            check_stmt(TypeAlias("Callback".to_string(), TInteger), &env).err(),
+            Some(String::from(
+                "[Type Error on '__main__()'] type 'Callback' is already defined."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_inferred_variables() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > x = 1
+         * > y = x + 2.5
+         */
+        let program = Sequence(
+            Box::new(Assignment("x".to_string(), Box::new(CInt(1)), None)),
+            Box::new(Assignment(
+                "y".to_string(),
+                Box::new(Add(Box::new(Var("x".to_string())), Box::new(CReal(2.5)))),
+                None,
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                inferred_types(&new_env),
+                vec![
+                    ("__main__".to_string(), "x".to_string(), TInteger),
+                    ("__main__".to_string(), "y".to_string(), TReal),
+                ]
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_inferred_function() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def fact(n):
+         * >     if n <= 1:
+         * >         return 1
+         * >     return n * fact(n - 1)
+         */
+        let func = FuncDef(Function {
+            name: "fact".to_string(),
+            kind: Some(TUnknown),
+            params: Some(vec![("n".to_string(), TUnknown)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Sequence(
+                Box::new(IfThenElse(
+                    Box::new(LTE(Box::new(Var("n".to_string())), Box::new(CInt(1)))),
+                    Box::new(Return(Box::new(CInt(1)))),
+                    None,
+                )),
+                Box::new(Return(Box::new(Mul(
+                    Box::new(Var("n".to_string())),
+                    Box::new(FuncCall(
+                        "fact".to_string(),
+                        vec![Sub(Box::new(Var("n".to_string())), Box::new(CInt(1)))],
+                    )),
+                )))),
+            ))),
+        });
+
+        match check_stmt(func, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                let fact_type = TFunction(Box::new(Some(TInteger)), vec![TInteger]);
+
+                assert_eq!(new_env.search_frame("fact".to_string()), Some(&fact_type));
+                assert_eq!(
+                    inferred_types(&new_env),
+                    vec![("__main__".to_string(), "fact".to_string(), fact_type)]
+                );
+                assert_eq!(
+                    check_exp(
+                        FuncCall("fact".to_string(), vec![CString("3".to_string())]),
+                        &new_env
+                    ),
+                    Err(String::from("[Type Error on '__main__()'] 'fact()' has mismatched arguments: expected 'TInteger', found 'TString'."))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_inferred_function_is_generalized() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def same(x):
+         * >     return x
+         * >
+         * > n: int = same(1)
+         * > s: str = same("one")
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "same".to_string(),
+                kind: Some(TUnknown),
+                params: Some(vec![("x".to_string(), TUnknown)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("x".to_string()))))),
+            })),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "n".to_string(),
+                    Box::new(FuncCall("same".to_string(), vec![CInt(1)])),
+                    Some(TInteger),
+                )),
+                Box::new(Assignment(
+                    "s".to_string(),
+                    Box::new(FuncCall(
+                        "same".to_string(),
+                        vec![CString("one".to_string())],
+                    )),
+                    Some(TString),
+                )),
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("same".to_string()),
+                Some(&TFunction(
+                    Box::new(Some(TVar("T1".to_string()))),
+                    vec![TVar("T1".to_string())]
+                ))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_generic_named_like_a_builtin_variable() {
+        let env = checker_env();
+
+        /*
+         * > def ident(x: Number) -> Number:
+         * >     return x
+         * >
+         * > s: str = ident("one")
+         */
+        let number = || TVar("Number".to_string());
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "ident".to_string(),
+                kind: Some(number()),
+                params: Some(vec![("x".to_string(), number())]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(Var("x".to_string()))))),
+            })),
+            Box::new(Assignment(
+                "s".to_string(),
+                Box::new(FuncCall(
+                    "ident".to_string(),
+                    vec![CString("one".to_string())],
+                )),
+                Some(TString),
+            )),
+        );
+
+        assert!(matches!(
+            check_stmt(program, &env),
+            Ok(ControlFlow::Continue(_))
+        ));
+    }
+
+    #[test]
+    fn check_inferred_arithmetic_is_numeric() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def sq(x):
+         * >     return x * x
+         * >
+         * > def inc(x):
+         * >     return x + 1
+         */
+        let func = |name: &str, body: Expression| {
+            FuncDef(Function {
+                name: name.to_string(),
+                kind: Some(TUnknown),
+                params: Some(vec![("x".to_string(), TUnknown)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Return(Box::new(body)))),
+            })
+        };
+        let x = || Box::new(Var("x".to_string()));
+        let program = Sequence(
+            Box::new(func("sq", Mul(x(), x()))),
+            Box::new(func("inc", Add(x(), Box::new(CInt(1))))),
+        );
+
+        let new_env = match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("unexpected return"),
+            Err(s) => panic!("{}", s),
+        };
+        let call = |name: &str, arg: Expression| {
+            check_exp(FuncCall(name.to_string(), vec![arg]), &new_env)
+        };
+
+        assert_eq!(call("sq", CInt(3)), Ok(TInteger));
+        assert_eq!(call("sq", CReal(3.0)), Ok(TReal));
+        assert_eq!(call("inc", CInt(3)), Ok(TInteger));
+        assert_eq!(call("inc", CReal(2.5)), Ok(TReal));
+        assert_eq!(
+            call("sq", CString("x".to_string())),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'sq()' has mismatched arguments: expected 'TVar(\"T1\")', found 'TString'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_inference_conflict() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def bad(x):
+         * >     if x:
+         * >         return x + 1
+         * >     return 0
+         */
+        let func = FuncDef(Function {
+            name: "bad".to_string(),
+            kind: Some(TUnknown),
+            params: Some(vec![("x".to_string(), TUnknown)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Sequence(
+                Box::new(IfThenElse(
+                    Box::new(Var("x".to_string())),
+                    Box::new(Return(Box::new(Add(
+                        Box::new(Var("x".to_string())),
+                        Box::new(CInt(1)),
+                    )))),
+                    None,
+                )),
+                Box::new(Return(Box::new(CInt(0)))),
+            ))),
+        });
+
+        match check_stmt(func, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(s, "[Type Error] expecting numeric type values."),
+        }
+    }
+
+    fn unwrap_or_def() -> Statement {
+        /*
+         * > def or_else(m: Maybe[T], default: T) -> T:
+         * >     return unwrap_or(m, default)
+         */
+        FuncDef(Function {
+            name: "or_else".to_string(),
+            kind: Some(TVar("T".to_string())),
+            params: Some(vec![
+                ("m".to_string(), TMaybe(Box::new(TVar("T".to_string())))),
+                ("default".to_string(), TVar("T".to_string())),
+            ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(UnwrapOr(
+                Box::new(Var("m".to_string())),
+                Box::new(Var("default".to_string())),
+            ))))),
+        })
+    }
+
+    #[test]
+    fn check_generic_function_instantiation() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > n: int = or_else(Just(1), 0)
+         * > s: str = or_else(Nothing, "none")
+         */
+        let program = Sequence(
+            Box::new(unwrap_or_def()),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "n".to_string(),
+                    Box::new(FuncCall(
+                        "or_else".to_string(),
+                        vec![CJust(Box::new(CInt(1))), CInt(0)],
+                    )),
+                    Some(TInteger),
+                )),
+                Box::new(Assignment(
+                    "s".to_string(),
+                    Box::new(FuncCall(
+                        "or_else".to_string(),
+                        vec![CNothing, CString("none".to_string())],
+                    )),
+                    Some(TString),
+                )),
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(_)) => (),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
     }
 
     #[test]
-    fn check_add_integers() {
-        let env = Environment::new();
+    fn check_generic_function_unification_errors() {
+        let env = match check_stmt(unwrap_or_def(), &Environment::new()) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            _ => panic!("function definition failed"),
+        };
 
-        let c10 = CInt(10);
-        let c20 = CInt(20);
-        let add = Add(Box::new(c10), Box::new(c20));
+        assert_eq!(
+            check_exp(
+                FuncCall(
+                    "or_else".to_string(),
+                    vec![CJust(Box::new(CInt(1))), CString("zero".to_string())],
+                ),
+                &env
+            ),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'or_else()' cannot unify type variable 'T': bound to 'TInteger', found 'TString'."
+            ))
+        );
 
-        assert_eq!(check_exp(add, &env), Ok(TInteger));
+        assert_eq!(
+            check_exp(
+                FuncCall("or_else".to_string(), vec![CInt(1), CInt(0)]),
+                &env
+            ),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'or_else()' has mismatched arguments: expected 'TMaybe(TVar(\"T\"))', found 'TInteger'."
+            ))
+        );
     }
 
     #[test]
-    fn check_add_reals() {
-        let env = Environment::new();
+    fn check_generic_body_is_rigid() {
+        let env: Environment<Type> = Environment::new();
 
-        let c10 = CReal(10.5);
-        let c20 = CReal(20.3);
-        let add = Add(Box::new(c10), Box::new(c20));
+        /*
+         * > def double(x: T) -> T:
+         * >     return x + x
+         */
+        let func = FuncDef(Function {
+            name: "double".to_string(),
+            kind: Some(TVar("T".to_string())),
+            params: Some(vec![("x".to_string(), TVar("T".to_string()))]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(Add(
+                Box::new(Var("x".to_string())),
+                Box::new(Var("x".to_string())),
+            ))))),
+        });
 
-        assert_eq!(check_exp(add, &env), Ok(TReal));
+        assert!(check_stmt(func, &env).is_err());
     }
 
-    #[test]
-    fn check_add_real_and_integer() {
-        let env = Environment::new();
+    fn speak(class: &str, kind: Type) -> Function {
+        Function {
+            name: "speak".to_string(),
+            kind: Some(kind),
+            params: Some(vec![("self".to_string(), TClass(class.to_string()))]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Return(Box::new(CString("...".to_string()))))),
+        }
+    }
 
-        let c10 = CInt(10);
-        let c20 = CReal(20.3);
-        let add = Add(Box::new(c10), Box::new(c20));
+    fn animal_env() -> Environment<Type> {
+        /*
+         * > class Animal:
+         * >     name: str
+         * >     def __init__(self, name: str):
+         * >         self.name = name
+         * >     def speak(self) -> str:
+         * >         return "..."
+         */
+        let animal = ClassDef(Class {
+            name: "Animal".to_string(),
+            parent: None,
+            fields: vec![("name".to_string(), TString)],
+            methods: vec![
+                Function {
+                    name: "__init__".to_string(),
+                    kind: None,
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Animal".to_string())),
+                        ("name".to_string(), TString),
+                    ]),
+                    defaults: vec![],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(FieldAssignment(
+                        "self".to_string(),
+                        "name".to_string(),
+                        Box::new(Var("name".to_string())),
+                    ))),
+                },
+                speak("Animal", TString),
+            ],
+        });
 
-        assert_eq!(check_exp(add, &env), Ok(TReal));
+        match check_stmt(animal, &Environment::new()) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            _ => panic!("class definition failed"),
+        }
     }
 
-    #[test]
-    fn check_add_integer_and_real() {
-        let env = Environment::new();
+    fn dog(methods: Vec<Function>) -> Statement {
+        ClassDef(Class {
+            name: "Dog".to_string(),
+            parent: Some("Animal".to_string()),
+            fields: vec![("tricks".to_string(), TInteger)],
+            methods,
+        })
+    }
 
-        let c10 = CReal(10.5);
-        let c20 = CInt(20);
-        let add = Add(Box::new(c10), Box::new(c20));
+    #[test]
+    fn check_subclass_assignability() {
+        let env = animal_env();
+
+        /*
+         * > class Dog(Animal): ...
+         * > a: Animal = Dog("Rex")
+         * > s: str = a.speak()
+         */
+        let program = Sequence(
+            Box::new(dog(vec![speak("Dog", TString)])),
+            Box::new(Sequence(
+                Box::new(Assignment(
+                    "a".to_string(),
+                    Box::new(New("Dog".to_string(), vec![CString("Rex".to_string())])),
+                    Some(TClass("Animal".to_string())),
+                )),
+                Box::new(Assignment(
+                    "s".to_string(),
+                    Box::new(MethodCall(
+                        Box::new(Var("a".to_string())),
+                        "speak".to_string(),
+                        vec![],
+                    )),
+                    Some(TString),
+                )),
+            )),
+        );
 
-        assert_eq!(check_exp(add, &env), Ok(TReal));
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => assert_eq!(
+                new_env.search_frame("a".to_string()),
+                Some(&TClass("Animal".to_string()))
+            ),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
     }
 
     #[test]
-    fn check_type_error_arithmetic_expression() {
-        let env = Environment::new();
-
-        let c10 = CInt(10);
-        let bool = CFalse;
-        let add = Add(Box::new(c10), Box::new(bool));
+    fn check_superclass_not_assignable() {
+        let env = animal_env();
 
-        assert_eq!(
-            check_exp(add, &env),
-            Err(String::from("[Type Error] expecting numeric type values."))
+        let program = Sequence(
+            Box::new(dog(vec![])),
+            Box::new(Assignment(
+                "d".to_string(),
+                Box::new(New("Animal".to_string(), vec![CString("Rex".to_string())])),
+                Some(TClass("Dog".to_string())),
+            )),
         );
+
+        match check_stmt(program, &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] 'd' has mismatched types: expected 'TClass(\"Dog\")', found 'TClass(\"Animal\")'."
+            ),
+        }
     }
 
     #[test]
-    fn check_type_error_not_expression() {
-        let env = Environment::new();
+    fn check_method_arguments_bind_like_calls() {
+        let env: Environment<Type> = Environment::new();
 
-        let c10 = CInt(10);
-        let not = Not(Box::new(c10));
+        /*
+         * > class Greeter:
+         * >     greeting: str
+         * >     def __init__(self, greeting: str = "hello"):
+         * >         self.greeting = greeting
+         * >     def greet(self, name: str, mark: str = "!") -> list[str]:
+         * >         return [self.greeting, name, mark]
+         * >     def rest(self, first: str, *others: str) -> list[str]:
+         * >         return others
+         */
+        let greeter = ClassDef(Class {
+            name: "Greeter".to_string(),
+            parent: None,
+            fields: vec![("greeting".to_string(), TString)],
+            methods: vec![
+                Function {
+                    name: "__init__".to_string(),
+                    kind: None,
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Greeter".to_string())),
+                        ("greeting".to_string(), TString),
+                    ]),
+                    defaults: vec![("greeting".to_string(), CString("hello".to_string()))],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(FieldAssignment(
+                        "self".to_string(),
+                        "greeting".to_string(),
+                        Box::new(Var("greeting".to_string())),
+                    ))),
+                },
+                Function {
+                    name: "greet".to_string(),
+                    kind: Some(TList(Box::new(TString))),
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Greeter".to_string())),
+                        ("name".to_string(), TString),
+                        ("mark".to_string(), TString),
+                    ]),
+                    defaults: vec![("mark".to_string(), CString("!".to_string()))],
+                    variadic: None,
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(CList(vec![
+                        FieldAccess(Box::new(Var("self".to_string())), "greeting".to_string()),
+                        Var("name".to_string()),
+                        Var("mark".to_string()),
+                    ]))))),
+                },
+                Function {
+                    name: "rest".to_string(),
+                    kind: Some(TList(Box::new(TString))),
+                    params: Some(vec![
+                        ("self".to_string(), TClass("Greeter".to_string())),
+                        ("first".to_string(), TString),
+                    ]),
+                    defaults: vec![],
+                    variadic: Some(("others".to_string(), TString)),
+                    module: None,
+                    body: Some(Box::new(Return(Box::new(Var("others".to_string()))))),
+                },
+            ],
+        });
+
+        let new_env = match check_stmt(greeter, &env) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("unexpected return"),
+            Err(s) => panic!("{}", s),
+        };
+        let g = || Box::new(New("Greeter".to_string(), vec![]));
 
         assert_eq!(
-            check_exp(not, &env),
-            Err(String::from("[Type Error] expecting a boolean type value."))
+            check_exp(
+                MethodCall(
+                    g(),
+                    "greet".to_string(),
+                    vec![KeywordArg(
+                        "name".to_string(),
+                        Box::new(CString("Bo".to_string()))
+                    )],
+                ),
+                &new_env
+            ),
+            Ok(TList(Box::new(TString)))
+        );
+        assert_eq!(
+            check_exp(
+                MethodCall(
+                    g(),
+                    "rest".to_string(),
+                    vec![CString("x".to_string()), CString("y".to_string())],
+                ),
+                &new_env
+            ),
+            Ok(TList(Box::new(TString)))
+        );
+        assert_eq!(
+            check_exp(
+                MethodCall(
+                    g(),
+                    "greet".to_string(),
+                    vec![KeywordArg(
+                        "mark".to_string(),
+                        Box::new(CString("?".to_string()))
+                    )],
+                ),
+                &new_env
+            ),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'Greeter.greet()' missing required argument 'name'."
+            ))
+        );
+        assert_eq!(
+            check_exp(
+                MethodCall(g(), "rest".to_string(), vec![CInt(1)]),
+                &new_env
+            ),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'Greeter.rest()' has mismatched arguments: expected 'TString', found 'TInteger'."
+            ))
         );
     }
 
     #[test]
-    fn check_type_error_and_expression() {
-        let env = Environment::new();
+    fn check_class_errors() {
+        let env = animal_env();
 
-        let c10 = CInt(10);
-        let bool = CTrue;
-        let and = And(Box::new(c10), Box::new(bool));
+        match check_stmt(dog(vec![speak("Dog", TInteger)]), &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] method 'Dog.speak()' does not match the signature of the method it overrides."
+            ),
+        }
+
+        match check_stmt(dog(vec![speak("Animal", TString)]), &env) {
+            Ok(_) => assert!(false),
+            Err(s) => assert_eq!(
+                s,
+                "[Type Error on '__main__()'] method 'Dog.speak()' must take 'self: Dog' as its first parameter."
+            ),
+        }
 
         assert_eq!(
-            check_exp(and, &env),
-            Err(String::from("[Type Error] expecting boolean type values."))
+            check_exp(
+                MethodCall(
+                    Box::new(New("Animal".to_string(), vec![CString("Rex".to_string())])),
+                    "fetch".to_string(),
+                    vec![],
+                ),
+                &env
+            ),
+            Err(String::from(
+                "[Type Error on '__main__()'] class 'Animal' has no method 'fetch()'."
+            ))
         );
-    }
-
-    #[test]
-    fn check_type_error_or_expression() {
-        let env = Environment::new();
-
-        let c10 = CInt(10);
-        let bool = CTrue;
-        let or = Or(Box::new(c10), Box::new(bool));
 
         assert_eq!(
-            check_exp(or, &env),
-            Err(String::from("[Type Error] expecting boolean type values."))
+            check_exp(New("Animal".to_string(), vec![CInt(1)]), &env),
+            Err(String::from(
+                "[Type Error on '__main__()'] 'Animal()' has mismatched arguments: expected 'TString', found 'TInteger'."
+            ))
         );
     }
 
     #[test]
-    fn check_assignment() {
+    fn check_maybe_assignment() {
         let env: Environment<Type> = Environment::new();
 
-        let assignment = Assignment("a".to_string(), Box::new(CTrue), Some(TBool));
+        /*
+         * > x: Maybe[int] = Nothing
+         * > y = unwrap_or(x, 0)
+         */
+        let program = Sequence(
+            Box::new(Assignment(
+                "x".to_string(),
+                Box::new(CNothing),
+                Some(TMaybe(Box::new(TInteger))),
+            )),
+            Box::new(Assignment(
+                "y".to_string(),
+                Box::new(UnwrapOr(Box::new(Var("x".to_string())), Box::new(CInt(0)))),
+                Some(TInteger),
+            )),
+        );
 
-        match check_stmt(assignment, &env) {
+        match check_stmt(program, &env) {
             Ok(ControlFlow::Continue(new_env)) => {
-                assert_eq!(new_env.search_frame("a".to_string()), Some(TBool).as_ref());
+                assert_eq!(
+                    new_env.search_frame("x".to_string()),
+                    Some(&TMaybe(Box::new(TInteger)))
+                );
             }
             Ok(_) => assert!(false),
             Err(s) => assert!(false, "{}", s),
@@ -495,37 +5056,92 @@ mod tests {
     }
 
     #[test]
-    fn check_assignment_error1() {
+    fn check_result_mismatched_types() {
         let env: Environment<Type> = Environment::new();
 
-        let assignment = Assignment("a".to_string(), Box::new(CTrue), Some(TInteger));
+        let program = Assignment(
+            "r".to_string(),
+            Box::new(CErr(Box::new(CInt(1)))),
+            Some(TResult(Box::new(TInteger), Box::new(TString))),
+        );
 
-        match check_stmt(assignment, &env) {
+        match check_stmt(program, &env) {
             Ok(_) => assert!(false),
             Err(s) => assert_eq!(
                 s,
-                "[Type Error on '__main__()'] 'a' has mismatched types: expected 'TInteger', found 'TBool'."
+                "[Type Error on '__main__()'] 'r' has mismatched types: expected 'TResult(TInteger, TString)', found 'TResult(TUnknown, TInteger)'."
             ),
         }
     }
 
+    fn parse_positive(kind: Option<Type>) -> Statement {
+        /*
+         * > def parse_positive(r: Result[int, str]) -> kind:
+         * >     n = r?
+         * >     return Ok(n + 1)
+         */
+        FuncDef(Function {
+            name: "parse_positive".to_string(),
+            kind,
+            params: Some(vec![(
+                "r".to_string(),
+                TResult(Box::new(TInteger), Box::new(TString)),
+            )]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(Sequence(
+                Box::new(Assignment(
+                    "n".to_string(),
+                    Box::new(Propagate(Box::new(Var("r".to_string())))),
+                    Some(TInteger),
+                )),
+                Box::new(Return(Box::new(COk(Box::new(Add(
+                    Box::new(Var("n".to_string())),
+                    Box::new(CInt(1)),
+                )))))),
+            ))),
+        })
+    }
+
     #[test]
-    fn check_assignment_error2() {
+    fn check_propagate() {
         let env: Environment<Type> = Environment::new();
+        let func = parse_positive(Some(TResult(Box::new(TInteger), Box::new(TString))));
 
-        let assignment1 = Assignment("a".to_string(), Box::new(CTrue), Some(TBool));
-        let assignment2 = Assignment("a".to_string(), Box::new(CInt(1)), None);
-        let program = Sequence(Box::new(assignment1), Box::new(assignment2));
+        match check_stmt(func, &env) {
+            Ok(ControlFlow::Continue(_)) => (),
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
 
-        match check_stmt(program, &env) {
+    #[test]
+    fn check_propagate_into_incompatible_function() {
+        let env: Environment<Type> = Environment::new();
+        let func = parse_positive(Some(TMaybe(Box::new(TInteger))));
+
+        match check_stmt(func, &env) {
             Ok(_) => assert!(false),
             Err(s) => assert_eq!(
                 s,
-                "[Type Error on '__main__()'] 'a' has mismatched types: expected 'TBool', found 'TInteger'."
+                "[Type Error on 'parse_positive()'] '?' cannot propagate 'TResult(TInteger, TString)' from a function returning 'Some(TMaybe(TInteger))'."
             ),
         }
     }
 
+    #[test]
+    fn check_propagate_outside_function() {
+        let env: Environment<Type> = Environment::new();
+
+        assert_eq!(
+            check_exp(Propagate(Box::new(CJust(Box::new(CInt(1))))), &env),
+            Err(String::from(
+                "[Syntax Error] '?' used outside a function on '__main__()'."
+            ))
+        );
+    }
+
     #[test]
     fn check_if_then_else_error() {
         let env: Environment<Type> = Environment::new();
@@ -592,6 +5208,9 @@ mod tests {
                 ("a".to_string(), TInteger),
                 ("b".to_string(), TInteger),
             ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Return(Box::new(Add(
                 Box::new(Var("a".to_string())),
                 Box::new(Var("b".to_string())),
@@ -625,6 +5244,9 @@ mod tests {
                 ("a".to_string(), TInteger),
                 ("b".to_string(), TInteger),
             ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Return(Box::new(CTrue)))),
         });
 
@@ -637,6 +5259,248 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_missing_return_paths() {
+        let env: Environment<Type> = Environment::new();
+
+        let sign = |body: Statement| {
+            FuncDef(Function {
+                name: "sign".to_string(),
+                kind: Some(TInteger),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(body)),
+            })
+        };
+        let positive = || Box::new(GT(Box::new(Var("n".to_string())), Box::new(CInt(0))));
+        let return_one = || Box::new(Return(Box::new(CInt(1))));
+
+        /*
+         * > def sign(n: int) -> int:
+         * >     if n > 0:
+         * >         return 1
+         * >     else:
+         * >         n = 0
+         */
+        let one_branch = sign(IfThenElse(
+            positive(),
+            return_one(),
+            Some(Box::new(Assignment(
+                "n".to_string(),
+                Box::new(CInt(0)),
+                None,
+            ))),
+        ));
+
+        /*
+         * > def sign(n: int) -> int:
+         * >     while n > 0:
+         * >         return 1
+         */
+        let loop_body = sign(While(positive(), return_one()));
+
+        for func in [one_branch, loop_body] {
+            assert_eq!(
+                check_stmt(func, &env).err(),
+                Some(String::from(
+                    "[Type Error] 'sign()' does not return a value on every path."
+                ))
+            );
+        }
+
+        /*
+         * > def sign(n: int) -> int:
+         * >     if n > 0:
+         * >         return 1
+         * >     return 0
+         */
+        let fallback = sign(Sequence(
+            Box::new(IfThenElse(positive(), return_one(), None)),
+            Box::new(Return(Box::new(CInt(0)))),
+        ));
+
+        /*
+         * > def sign(n: int) -> int:
+         * >     if n > 0:
+         * >         return 1
+         * >     else:
+         * >         raise ValueError("neg")
+         */
+        let raises = sign(IfThenElse(
+            positive(),
+            return_one(),
+            Some(Box::new(Raise(
+                "ValueError".to_string(),
+                Box::new(CString("neg".to_string())),
+            ))),
+        ));
+
+        for func in [fallback, raises] {
+            match check_stmt(func, &env) {
+                Ok(ControlFlow::Continue(_)) => (),
+                Ok(_) => assert!(false),
+                Err(s) => assert!(false, "{}", s),
+            }
+        }
+    }
+
+    #[test]
+    fn check_procedure_returns() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def log(n: int):
+         * >     if n < 0:
+         * >         return
+         * >     print(n)
+         */
+        let log = |stmt: Statement| {
+            FuncDef(Function {
+                name: "log".to_string(),
+                kind: None,
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Sequence(
+                    Box::new(IfThenElse(
+                        Box::new(LT(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
+                        Box::new(stmt),
+                        None,
+                    )),
+                    Box::new(Print(Box::new(Var("n".to_string())))),
+                ))),
+            })
+        };
+
+        let new_env = match check_stmt(log(BareReturn), &env) {
+            Ok(ControlFlow::Continue(new_env)) => new_env,
+            Ok(_) => panic!("unexpected return"),
+            Err(s) => panic!("{}", s),
+        };
+
+        assert_eq!(
+            check_exp(FuncCall("log".to_string(), vec![CInt(1)]), &new_env),
+            Ok(TUnit)
+        );
+
+        assert_eq!(
+            check_stmt(log(Return(Box::new(CInt(0)))), &env).err(),
+            Some(String::from(
+                "[Type Error] 'log()' does not return a value."
+            ))
+        );
+
+        /*
+         * > def half(n: int) -> int:
+         * >     return
+         */
+        let half = FuncDef(Function {
+            name: "half".to_string(),
+            kind: Some(TInteger),
+            params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(BareReturn)),
+        });
+
+        assert_eq!(
+            check_stmt(half, &env).err(),
+            Some(String::from(
+                "[Type Error] 'half()' has mismatched types: expected 'TInteger', found 'TUnit'."
+            ))
+        );
+    }
+
+    #[test]
+    fn check_expression_statements() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def log(n: int) -> None:
+         * >     print(n)
+         * >
+         * > log(1)
+         * > x = log(2)
+         */
+        let program = Sequence(
+            Box::new(FuncDef(Function {
+                name: "log".to_string(),
+                kind: Some(TUnit),
+                params: Some(vec![("n".to_string(), TInteger)]),
+                defaults: vec![],
+                variadic: None,
+                module: None,
+                body: Some(Box::new(Print(Box::new(Var("n".to_string()))))),
+            })),
+            Box::new(Sequence(
+                Box::new(ExprStmt(Box::new(FuncCall(
+                    "log".to_string(),
+                    vec![CInt(1)],
+                )))),
+                Box::new(Assignment(
+                    "x".to_string(),
+                    Box::new(FuncCall("log".to_string(), vec![CInt(2)])),
+                    None,
+                )),
+            )),
+        );
+
+        match check_stmt(program, &env) {
+            Ok(ControlFlow::Continue(new_env)) => {
+                assert_eq!(new_env.search_frame("x".to_string()), Some(&TUnit));
+                assert_eq!(
+                    check_stmt(
+                        ExprStmt(Box::new(FuncCall("log".to_string(), vec![CTrue]))),
+                        &new_env
+                    )
+                    .err(),
+                    Some(String::from(
+                        "[Type Error on '__main__()'] 'log()' has mismatched arguments: expected 'TInteger', found 'TBool'."
+                    ))
+                );
+            }
+            Ok(_) => assert!(false),
+            Err(s) => assert!(false, "{}", s),
+        }
+    }
+
+    #[test]
+    fn check_consistent_inferred_returns() {
+        let env: Environment<Type> = Environment::new();
+
+        /*
+         * > def pick(b):
+         * >     if b:
+         * >         return 1
+         * >     else:
+         * >         return "one"
+         */
+        let pick = FuncDef(Function {
+            name: "pick".to_string(),
+            kind: Some(TUnknown),
+            params: Some(vec![("b".to_string(), TUnknown)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
+            body: Some(Box::new(IfThenElse(
+                Box::new(Var("b".to_string())),
+                Box::new(Return(Box::new(CInt(1)))),
+                Some(Box::new(Return(Box::new(CString("one".to_string()))))),
+            ))),
+        });
+
+        assert_eq!(
+            check_stmt(pick, &env).err(),
+            Some(String::from(
+                "[Type Error] 'pick()' has mismatched types: expected 'TInteger', found 'TString'."
+            ))
+        );
+    }
+
     #[test]
     fn check_return_outside_function() {
         let env: Environment<Type> = Environment::new();
@@ -660,6 +5524,9 @@ mod tests {
                 ("a".to_string(), TInteger),
                 ("b".to_string(), TInteger),
             ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Sequence(
                 Box::new(Assignment(
                     "c".to_string(),
@@ -716,6 +5583,9 @@ mod tests {
                 ("a".to_string(), TInteger),
                 ("b".to_string(), TInteger),
             ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Sequence(
                 Box::new(Assignment(
                     "c".to_string(),
@@ -790,6 +5660,9 @@ mod tests {
             name: "factorial".to_string(),
             kind: Some(TInteger),
             params: Some(vec![("n".to_string(), TInteger)]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(IfThenElse(
                 Box::new(EQ(Box::new(Var("n".to_string())), Box::new(CInt(0)))),
                 Box::new(Return(Box::new(CInt(1)))),
@@ -826,6 +5699,9 @@ mod tests {
                 ("a".to_string(), TInteger),
                 ("b".to_string(), TInteger),
             ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(IfThenElse(
                 Box::new(GT(
                     Box::new(Var("a".to_string())),
@@ -850,6 +5726,9 @@ mod tests {
             name: "wrong_return".to_string(),
             kind: Some(TInteger),
             params: None,
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Return(Box::new(CReal(1.0))))),
         });
 
@@ -873,6 +5752,9 @@ mod tests {
                 ("x".to_string(), TInteger),
                 ("x".to_string(), TInteger), // Mesmo nome de parâmetro
             ]),
+            defaults: vec![],
+            variadic: None,
+            module: None,
             body: Some(Box::new(Return(Box::new(Var("x".to_string()))))),
         });
 
@@ -881,4 +5763,53 @@ mod tests {
             Err(msg) => assert_eq!(msg, "[Parameter Error] Duplicate parameter name 'x'"),
         }
     }
+
+    #[test]
+    fn check_sandbox_file_operations() {
+        let mut env: Environment<Type> = Environment::new();
+        let policy = Policy {
+            roots: vec!["/sandbox".into()],
+            max_bytes_written: Some(4),
+            check_statically: true,
+            ..Policy::new()
+        };
+        env.sandbox = Sandbox::with_policy(policy.clone());
+        env.insert_variable("path".to_string(), TString);
+
+        let read = |path: &str| ReadFile(Box::new(CString(path.to_string())));
+        let write = |path: Expression, content: &str| {
+            WriteToFile(Box::new(path), Box::new(CString(content.to_string())))
+        };
+
+        assert_eq!(check_exp(read("/sandbox/in.txt"), &env), Ok(TString));
+        assert_eq!(
+            check_exp(read("/etc/passwd"), &env),
+            Err(String::from(
+                "[Sandbox Error on '__main__()'] '/etc/passwd' is outside the allowed directories."
+            ))
+        );
+        assert_eq!(
+            check_stmt(write(CString("/sandbox/out.txt".to_string()), "hello"), &env).err(),
+            Some(String::from("[Sandbox Error on '__main__()'] writing 5 bytes to '/sandbox/out.txt' exceeds the limit of 4 bytes (0 already written)."))
+        );
+        /* a path known only at runtime is left to the interpreter */
+        assert!(check_stmt(write(Var("path".to_string()), "hi"), &env).is_ok());
+
+        env.sandbox = Sandbox::with_policy(Policy {
+            read_only: true,
+            ..policy.clone()
+        });
+        assert_eq!(
+            check_stmt(write(Var("path".to_string()), "hi"), &env).err(),
+            Some(String::from(
+                "[Sandbox Error on '__main__()'] cannot write files in read-only mode."
+            ))
+        );
+
+        env.sandbox = Sandbox::with_policy(Policy {
+            check_statically: false,
+            ..policy
+        });
+        assert_eq!(check_exp(read("/etc/passwd"), &env), Ok(TString));
+    }
 }